@@ -1,17 +1,23 @@
 use crate::{
-    backend::Backend,
-    frontend::Frontend,
+    backend::{Backend, EngineError},
+    demo::{DemoEvent, DemoPlayback, DemoScript},
+    frontend::{FrameTimingStats, Frontend, TextureData},
+    inspector::Inspector,
     utils::{
+        aabb::Aabb,
         consts::{
-            FRAME_TIME_MS, KEY_COOLDOWN_MS, MOUSE_PRESS_COOLDOWN_MS, MS_BUFFER, SIM_MAX_SCALE,
-            TARGET_FPS,
+            BLOOM_INTENSITY_STEP, FRAME_TIME_MS, FRAME_TIME_WINDOW, HELP_BACKGROUND, INIT_HEIGHT,
+            INIT_TITLE, INIT_WIDTH, MAX_PHYSICS_STEPS, MOUSE_PRESS_COOLDOWN_MS, MS_BUFFER,
+            PHYSICS_DT, SCROLL_PIXELS_PER_NOTCH, SIM_MAX_SCALE, SIM_SPEEDS, TARGET_FPS, WHITE,
         },
+        font::GLYPH_HEIGHT,
         input_data::{InputData, MouseInput},
-        vec2::{vec2, Vec2, WindowSpace},
+        vec2::{vec2, TextureSpace, Vec2, WindowSpace},
+        world::World,
     },
 };
 use educe::Educe;
-use log::{info, trace, warn};
+use log::{error, info, trace, warn};
 use std::{
     mem::transmute,
     rc::Rc,
@@ -19,7 +25,7 @@ use std::{
 };
 use winit::{
     dpi::PhysicalSize,
-    event::{ElementState, Event, KeyEvent, MouseButton, WindowEvent},
+    event::{ElementState, Event, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
     event_loop::{EventLoop, EventLoopWindowTarget},
     keyboard::{KeyCode, PhysicalKey},
     window::{Window, WindowBuilder},
@@ -30,6 +36,285 @@ pub struct App<'a, F: Frontend> {
     pub frontend: F,
     pub backend: Backend<'a>,
     pub inputs: InputData,
+    pub sim_speed: f32, // multiplier on the delta time given to the frontend, 0.0 == paused.
+    pub show_help: bool,
+    // frontend texture + help panel, only drawn while show_help.
+    help_overlay: World,
+    // scripted inputs replayed on top of the user's, None outside of demo mode.
+    pub demo: Option<DemoPlayback>,
+    // debug window, toggled on Ctrl+I.
+    inspector: Option<Inspector>,
+    // Ctrl+A, keeps the sim at aspect_ratio & letterboxes the rest of the window.
+    pub lock_aspect_ratio: bool,
+    aspect_ratio: f64, // INIT_WIDTH / INIT_HEIGHT
+    // top left of the letterboxed viewport, subtracted from cursor positions.
+    letterbox_offset: Vec2<f64, WindowSpace>,
+}
+
+// Bindings handled by the app itself, listed after the frontend's in the help overlay.
+const APP_HELP_TEXT: &[(KeyCode, &str)] = &[
+    (KeyCode::Slash, "Shift+? toggle help"),
+    (KeyCode::Minus, "Decrease scale"),
+    (KeyCode::Equal, "Increase scale"),
+    (KeyCode::ControlLeft, "+0..5 sim speed"),
+    (KeyCode::KeyD, "Ctrl+D start demo"),
+    (KeyCode::KeyI, "Ctrl+I toggle inspector"),
+    (KeyCode::KeyA, "Ctrl+A lock aspect ratio"),
+    (KeyCode::KeyB, "Ctrl+B toggle bloom, Alt+=/- intensity"),
+    (KeyCode::Escape, "Quit"),
+];
+
+// Circular buffer of the most recent frame times.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameTimes {
+    frame_times: [Duration; FRAME_TIME_WINDOW],
+    index: usize,
+    count: usize,
+}
+
+impl FrameTimes {
+    pub fn push(&mut self, frame_time: Duration) -> FrameTimingStats {
+        self.frame_times[self.index] = frame_time;
+        self.index = (self.index + 1) % FRAME_TIME_WINDOW;
+        self.count = (self.count + 1).min(FRAME_TIME_WINDOW);
+
+        let window = &self.frame_times[..self.count];
+        FrameTimingStats {
+            current: frame_time,
+            avg: window.iter().sum::<Duration>() / self.count as u32,
+            min: *window.iter().min().unwrap(),
+            max: *window.iter().max().unwrap(),
+            sim_speed: 1.0,
+        }
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            frame_times: [Duration::ZERO; FRAME_TIME_WINDOW],
+            index: 0,
+            count: 0,
+        }
+    }
+}
+
+// Paces frames to TARGET_FPS. Sleeps are coarse on some platforms (Windows), so time_credit
+// tracks how far ahead (+) or behind (-) of the target recent frames finished, in ms.
+// Sleeping is skipped while behind, letting the next frame start early to catch up.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameScheduler {
+    frame_times: FrameTimes,
+    frame_timer: Instant,
+    time_credit: f64,
+    stats: FrameTimingStats,
+}
+
+impl FrameScheduler {
+    // (average frame time, whether the rest of this frame is worth sleeping through)
+    pub fn tick(&self) -> (Duration, bool) {
+        let remaining = FRAME_TIME_MS - self.frame_timer.elapsed().as_millis_f64();
+        (self.stats.avg, self.should_sleep(remaining))
+    }
+
+    fn should_sleep(&self, remaining_ms: f64) -> bool {
+        remaining_ms > MS_BUFFER && self.time_credit > 0.0
+    }
+
+    // Records a finished frame's time, sleep included.
+    fn end_frame(&mut self, frame_time: Duration) {
+        self.stats = self.frame_times.push(frame_time);
+        // bounded, a long stall shouldn't disable sleeping for seconds afterwards.
+        self.time_credit = (self.time_credit + FRAME_TIME_MS - frame_time.as_millis_f64())
+            .clamp(-FRAME_TIME_MS, FRAME_TIME_MS);
+    }
+
+    // Ends the frame, sleeping if tick allows it.
+    // TODO(TOM): instead of sleeping, have multiple frames in flight, prob max 2 (front & back buffer)
+    pub fn timing(&mut self, frame: usize) -> FrameTimingStats {
+        optick::event!("FrameScheduler::timing");
+
+        let elapsed = self.frame_timer.elapsed();
+        if let (_, true) = self.tick() {
+            let with_buffer = FRAME_TIME_MS - elapsed.as_millis_f64() - MS_BUFFER;
+            std::thread::sleep(Duration::from_millis(with_buffer as u64));
+        }
+
+        // frame time includes the sleep, so FPS is accurate.
+        self.end_frame(self.frame_timer.elapsed());
+        self.frame_timer = Instant::now();
+
+        if frame % TARGET_FPS as usize == 0 {
+            info!(
+                "Frametime: {elapsed:.2?} | Min: {:.2?} | Avg: {:.2?} | Max: {:.2?} | Credit: {:.2}ms",
+                self.stats.min, self.stats.avg, self.stats.max, self.time_credit
+            );
+        }
+
+        self.stats
+    }
+
+    // Starts the current frame now, e.g. after time spent unfocused.
+    pub fn restart(&mut self) {
+        self.frame_timer = Instant::now();
+    }
+
+    pub fn stats(&self) -> FrameTimingStats {
+        self.stats
+    }
+
+    pub fn new() -> Self {
+        let mut frame_times = FrameTimes::new();
+        let stats = frame_times.push(Duration::from_millis(FRAME_TIME_MS as u64));
+        Self {
+            frame_times,
+            frame_timer: Instant::now(),
+            time_credit: 0.0,
+            stats,
+        }
+    }
+}
+
+// Fixed timestep accumulator, real time goes in & whole PHYSICS_DT steps come out,
+// so physics advances the same however long frames take to render.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhysicsClock {
+    accumulator: f64, // seconds
+}
+
+impl PhysicsClock {
+    // Steps to run for elapsed seconds of real time scaled by sim_speed, at most MAX_PHYSICS_STEPS.
+    pub fn advance(&mut self, elapsed: f64, sim_speed: f32) -> u32 {
+        self.accumulator += elapsed * f64::from(sim_speed);
+        self.accumulator = self
+            .accumulator
+            .min(PHYSICS_DT * f64::from(MAX_PHYSICS_STEPS));
+
+        let mut steps = 0;
+        while self.accumulator >= PHYSICS_DT {
+            self.accumulator -= PHYSICS_DT;
+            steps += 1;
+        }
+        steps
+    }
+
+    // How far between the last step & the next, for render_interpolated.
+    pub fn alpha(&self) -> f64 {
+        self.accumulator / PHYSICS_DT
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+// How much of the frontend's texture each frame uploads. The help overlay is a copy drawn
+// over, so it and the first frame after it's hidden upload everything, as does the frame
+// after a skipped one, whose dirty region never reached the gpu.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UploadTracker {
+    help_was_shown: bool,
+    full_upload_pending: bool,
+}
+
+impl UploadTracker {
+    // None uploads the whole texture.
+    pub fn region(
+        &mut self,
+        dirty_region: Option<Aabb<u32, TextureSpace>>,
+        show_help: bool,
+    ) -> Option<Aabb<u32, TextureSpace>> {
+        let full_upload = show_help || self.help_was_shown || self.full_upload_pending;
+        self.help_was_shown = show_help;
+        self.full_upload_pending = false;
+        if full_upload {
+            None
+        } else {
+            dirty_region
+        }
+    }
+
+    pub fn upload_failed(&mut self) {
+        self.full_upload_pending = true;
+    }
+}
+
+// Frontend updates are skipped while the window is unfocused. Refocusing restarts the frame &
+// physics timers, so the time away isn't counted as one long frame or a burst of physics steps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FocusPause {
+    paused: bool,
+}
+
+impl FocusPause {
+    pub fn focus_changed(
+        &mut self,
+        focused: bool,
+        scheduler: &mut FrameScheduler,
+        physics_clock: &mut PhysicsClock,
+        physics_timer: &mut Instant,
+    ) {
+        self.paused = !focused;
+        if focused {
+            scheduler.restart();
+            *physics_timer = Instant::now();
+            physics_clock.reset();
+        }
+        info!(
+            "Simulation {} on focus change",
+            if focused { "resumed" } else { "paused" }
+        );
+    }
+
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+// Turns wheel events into whole zoom notches. Line deltas are a notch each however small,
+// pixel deltas (trackpads, hi-res wheels) only count once SCROLL_PIXELS_PER_NOTCH add up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScrollWheel {
+    pixels: f64, // pixel delta since the last notch
+}
+
+impl ScrollWheel {
+    // Notches for one event, positive is up / zoom in. Horizontal only scrolls are 0.
+    pub fn notches(&mut self, delta: MouseScrollDelta) -> f64 {
+        match delta {
+            MouseScrollDelta::LineDelta(_, lines) if lines != 0.0 => f64::from(lines.signum()),
+            MouseScrollDelta::LineDelta(..) => 0.0,
+            MouseScrollDelta::PixelDelta(pixels) => {
+                self.pixels += pixels.y;
+                let notches = (self.pixels / SCROLL_PIXELS_PER_NOTCH).trunc();
+                self.pixels -= notches * SCROLL_PIXELS_PER_NOTCH;
+                notches
+            }
+        }
+    }
+}
+
+// '?', Shift+Slash on either shift.
+fn help_toggled(inputs: &InputData) -> bool {
+    let shift_held = inputs.is_held(KeyCode::ShiftLeft) || inputs.is_held(KeyCode::ShiftRight);
+    shift_held && inputs.is_pressed(KeyCode::Slash)
+}
+
+// Largest size of the given aspect ratio that fits in the window, and the
+// left, right, top, bottom padding that centres it, e.g. 4:3 in 1920x1080 -> 1440x1080, 240px bars.
+pub fn letterbox(
+    window_size: Vec2<u32, WindowSpace>,
+    aspect_ratio: f64,
+) -> (Vec2<u32, WindowSpace>, [u32; 4]) {
+    let window = window_size.cast::<f64>();
+    let size: Vec2<u32, WindowSpace> = match window.x / window.y > aspect_ratio {
+        true => vec2((window.y * aspect_ratio).round(), window.y),
+        false => vec2(window.x, (window.x / aspect_ratio).round()),
+    }
+    .cast();
+    let pad = window_size - size;
+    (
+        size,
+        [pad.x / 2, pad.x - pad.x / 2, pad.y / 2, pad.y - pad.y / 2],
+    )
 }
 
 pub fn init_window(
@@ -54,54 +339,90 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
         init_scale_factor: u32,
     ) -> Self {
         let frontend = F::new(window_size, init_scale_factor);
-        let backend = pollster::block_on(Backend::new(
+        let help_overlay = World::new(frontend.get_texture_data().texture_size);
+        let mut backend = pollster::block_on(Backend::new(
             window,
             window_size,
             frontend.get_texture_data(),
-        ));
+        ))
+        .expect("Failed to initialise the GPU backend");
+        backend.set_clear_colour(frontend.clear_colour().map(wgpu::Color::from));
 
         App {
             event_loop,
             frontend,
             backend,
-            inputs: InputData {
-                mouse_pos: vec2(0.0, 0.0),
-                mouse_down: false,
-                mouse_pressed: MouseInput {
-                    state: false,
-                    pos: vec2(0.0, 0.0),
-                    time: Instant::now(),
-                },
-                mouse_released: MouseInput {
-                    state: false,
-                    pos: vec2(0.0, 0.0),
-                    time: Instant::now(),
-                },
-                keys_held: [false; 256],
-                keys_pressed: [false; 256],
-                tap_cooldowns: [Instant::now(); 256],
-            },
+            inputs: InputData::default(),
+            sim_speed: 1.0,
+            show_help: false,
+            help_overlay,
+            demo: None,
+            inspector: None,
+            lock_aspect_ratio: false,
+            aspect_ratio: f64::from(INIT_WIDTH) / f64::from(INIT_HEIGHT),
+            letterbox_offset: vec2(0.0, 0.0),
         }
     }
 
     pub fn run(mut self) {
         let start = Instant::now();
-        let mut frame_timer = start;
-        let mut avg_frame_time = Duration::from_millis(FRAME_TIME_MS as u64);
+        let mut scheduler = FrameScheduler::new();
+        let mut frame_timing = scheduler.stats();
+        let mut hud_speed = self.sim_speed;
         let mut frame = 0;
+        let mut physics_timer = start;
+        let mut physics_clock = PhysicsClock::default();
+        let mut focus_pause = FocusPause::default();
+        let mut upload_tracker = UploadTracker::default();
+        let mut scroll_wheel = ScrollWheel::default();
 
         self.event_loop
             .run(move |event, control_flow| match event {
                 Event::AboutToWait => {
                     self.backend.window.request_redraw();
                 }
+                Event::WindowEvent {
+                    ref event,
+                    window_id,
+                } if self
+                    .inspector
+                    .as_ref()
+                    .is_some_and(|inspector| inspector.id() == window_id) =>
+                {
+                    match event {
+                        WindowEvent::CloseRequested => self.inspector = None,
+                        WindowEvent::MouseWheel {
+                            delta: MouseScrollDelta::LineDelta(_, lines),
+                            ..
+                        } => {
+                            if let Some(inspector) = &mut self.inspector {
+                                inspector.scroll_by(-*lines as isize);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 Event::WindowEvent {
                     ref event,
                     window_id,
                 } if window_id == self.backend.window.id() => match event {
                     WindowEvent::CloseRequested => control_flow.exit(),
+                    WindowEvent::Focused(focused) => focus_pause.focus_changed(
+                        *focused,
+                        &mut scheduler,
+                        &mut physics_clock,
+                        &mut physics_timer,
+                    ),
                     WindowEvent::KeyboardInput { event, .. } => {
                         Self::register_keyboard_input(event, &mut self.inputs, control_flow);
+
+                        // Any real keypress hands control back to the user.
+                        if event.state == ElementState::Pressed
+                            && !event.repeat
+                            && self.demo.take().is_some()
+                        {
+                            info!("Demo interrupted");
+                        }
                     }
                     WindowEvent::MouseInput {
                         state,
@@ -109,8 +430,9 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
                         ..
                     } => match *state {
                         ElementState::Pressed => {
-                            // Unconditionaly set mouse_down.
+                            // Unconditionaly set mouse_down, the real mouse takes over from any touch.
                             self.inputs.mouse_down = true;
+                            self.inputs.mouse_touch = None;
 
                             // Only activate a press event if sufficient time has elapsed.
                             if self.inputs.mouse_pressed.time.elapsed()
@@ -132,9 +454,25 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
                             self.inputs.mouse_down = false;
                         }
                     },
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Right,
+                        ..
+                    } => {
+                        self.inputs.right_click = MouseInput {
+                            state: true,
+                            pos: self.inputs.mouse_pos,
+                            time: Instant::now(),
+                        };
+                    }
                     WindowEvent::CursorMoved { position, .. } => {
-                        self.inputs.mouse_pos = vec2(position.x, position.y);
+                        self.inputs.mouse_pos =
+                            vec2(position.x, position.y) - self.letterbox_offset;
                     }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        self.inputs.scroll_delta += scroll_wheel.notches(*delta);
+                    }
+                    WindowEvent::Touch(touch) => self.inputs.register_touch(touch),
                     WindowEvent::Resized(physical_size) => {
                         if self.backend.window.is_minimized().unwrap() {
                             return;
@@ -143,8 +481,12 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
 
                         optick::event!("Window Resize");
 
-                        self.frontend.resize_texture(size);
-                        self.backend.resize(size, &self.frontend.get_texture_data());
+                        self.letterbox_offset = Self::resize_window(
+                            &mut self.frontend,
+                            &mut self.backend,
+                            size,
+                            self.lock_aspect_ratio.then_some(self.aspect_ratio),
+                        );
                     }
                     WindowEvent::RedrawRequested if window_id == self.backend.window.id() => {
                         if self.backend.window.is_minimized().unwrap() {
@@ -154,20 +496,117 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
                         frame += 1;
                         optick::next_frame();
 
+                        if let Some(demo) = &mut self.demo {
+                            Self::apply_demo_events(demo, &mut self.frontend, &mut self.inputs);
+                            if demo.is_finished() {
+                                self.demo = None;
+                                info!("Demo finished");
+                            }
+                        }
+
                         Self::handle_window_inputs(
                             &mut self.frontend,
                             &mut self.backend,
                             &mut self.inputs,
+                            &mut self.sim_speed,
+                            &mut self.show_help,
+                            &mut self.demo,
                         );
 
-                        self.frontend.update(&mut self.inputs, avg_frame_time);
+                        // Unfocused, the last frame is rendered again without updating.
+                        if !focus_pause.is_paused() {
+                            self.frontend.update(
+                                &mut self.inputs,
+                                frame_timing.with_sim_speed(self.sim_speed),
+                            );
+
+                            // Fixed timestep, physics is independent of the render frame time.
+                            let steps = physics_clock
+                                .advance(physics_timer.elapsed().as_secs_f64(), self.sim_speed);
+                            physics_timer = Instant::now();
+
+                            for _ in 0..steps {
+                                #[cfg(feature = "compute")]
+                                if let Some(particle_data) = self.frontend.gpu_particles() {
+                                    self.backend.compute_forces(particle_data);
+                                }
+                                self.frontend
+                                    .physics_step(Duration::from_secs_f64(PHYSICS_DT));
+                            }
+                            self.frontend.render_interpolated(physics_clock.alpha());
+                        }
+
+                        if self.inputs.is_held(KeyCode::ControlLeft)
+                            && self.inputs.is_pressed(KeyCode::KeyI)
+                        {
+                            Self::toggle_inspector(&mut self.inspector, control_flow);
+                        }
+
+                        if self.inputs.is_held(KeyCode::ControlLeft)
+                            && self.inputs.is_pressed(KeyCode::KeyA)
+                        {
+                            self.lock_aspect_ratio = !self.lock_aspect_ratio;
+                            info!("Aspect ratio locked: {}", self.lock_aspect_ratio);
+                            let size = self.backend.window.inner_size();
+                            self.letterbox_offset = Self::resize_window(
+                                &mut self.frontend,
+                                &mut self.backend,
+                                vec2(size.width, size.height),
+                                self.lock_aspect_ratio.then_some(self.aspect_ratio),
+                            );
+                        }
 
                         Self::clear_inputs(&mut self.inputs);
 
-                        let texture_data = self.frontend.get_texture_data();
-                        self.backend.render(&texture_data, start);
+                        let dirty_region = upload_tracker
+                            .region(self.frontend.take_dirty_region(), self.show_help);
+                        let texture_data = TextureData {
+                            dirty_region,
+                            ..self.frontend.get_texture_data()
+                        };
+                        // the frontend may rescale itself, e.g. GravitySim zooming to fit.
+                        self.backend.resize_texture(&texture_data);
+                        let rendered = if self.show_help {
+                            Self::draw_help_overlay(&mut self.help_overlay, &texture_data);
+                            self.backend.render(
+                                &TextureData {
+                                    texture_buffer: self.help_overlay.get_viewport_texture(),
+                                    texture_size: texture_data.texture_size,
+                                    dirty_region: None,
+                                },
+                                start,
+                            )
+                        } else {
+                            self.backend.render(&texture_data, start)
+                        };
+                        if rendered.is_err() {
+                            upload_tracker.upload_failed();
+                        }
+                        match rendered {
+                            Ok(()) => {}
+                            // can't gracefully exit in oom states, non-zero as it's a failure.
+                            Err(EngineError::SurfaceError(wgpu::SurfaceError::OutOfMemory)) => {
+                                error!("Surface out of memory, exiting");
+                                std::process::exit(1);
+                            }
+                            Err(err) => error!("Skipped frame: {err}"),
+                        }
+
+                        if let Some(inspector) = &mut self.inspector {
+                            inspector.update(|| self.frontend.inspector_text());
+                            if let Err(err) = inspector.present() {
+                                warn!("Inspector present failed: {err}");
+                            }
+                        }
+
+                        frame_timing = scheduler.timing(frame);
 
-                        let avg_frame_time = Self::timing(frame, start, &mut frame_timer);
+                        if frame % TARGET_FPS as usize == 0 || hud_speed != self.sim_speed {
+                            hud_speed = self.sim_speed;
+                            self.backend
+                                .window
+                                .set_title(&Self::hud_title(hud_speed, &frame_timing));
+                        }
                     }
                     _ => {}
                 },
@@ -185,26 +624,7 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
             PhysicalKey::Code(KeyCode::Escape) => {
                 control_flow.exit();
             }
-            PhysicalKey::Code(code) => {
-                let code = code as usize;
-                if code > 255 {
-                    return;
-                }
-                match event.state {
-                    ElementState::Pressed => {
-                        if inputs.tap_cooldowns[code].elapsed()
-                            > Duration::from_millis(KEY_COOLDOWN_MS)
-                        {
-                            inputs.keys_held[code] = true;
-                            inputs.keys_pressed[code] = true;
-                            inputs.tap_cooldowns[code] = Instant::now();
-                        }
-                    }
-                    ElementState::Released => {
-                        inputs.keys_held[code] = false;
-                    }
-                }
-            }
+            PhysicalKey::Code(code) => inputs.register_key(code, event.state),
             PhysicalKey::Unidentified(_) => {
                 warn!("Unidentified key pressed.");
             }
@@ -212,46 +632,354 @@ impl<'a, F: Frontend + std::fmt::Debug> App<'a, F> {
     }
 
     // Unified input handling for tasks that involve both frontend and backend (e.g resize)
-    fn handle_window_inputs(frontend: &mut F, backend: &mut Backend<'_>, inputs: &mut InputData) {
+    fn handle_window_inputs(
+        frontend: &mut F,
+        backend: &mut Backend<'_>,
+        inputs: &mut InputData,
+        sim_speed: &mut f32,
+        show_help: &mut bool,
+        demo: &mut Option<DemoPlayback>,
+    ) {
         optick::event!("App::handle_inputs");
 
-        // Scale factor on KeyPlus and KeyMinus
-        if inputs.is_pressed(KeyCode::Minus) && frontend.get_texture_scale() > 1 {
+        if help_toggled(inputs) {
+            *show_help = !*show_help;
+        }
+
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyD) {
+            *demo = Some(DemoPlayback::new(DemoScript::solar_system_showcase()));
+            info!("Demo started");
+        }
+
+        // Simulation speed on Ctrl+0..5, Ctrl+0 pauses physics but keeps rendering.
+        if inputs.is_held(KeyCode::ControlLeft) {
+            let speed_keys = [
+                KeyCode::Digit0,
+                KeyCode::Digit1,
+                KeyCode::Digit2,
+                KeyCode::Digit3,
+                KeyCode::Digit4,
+                KeyCode::Digit5,
+            ];
+            for (key, speed) in speed_keys.into_iter().zip(SIM_SPEEDS) {
+                if inputs.is_pressed(key) && *sim_speed != speed {
+                    *sim_speed = speed;
+                    info!("Sim speed: {speed}x");
+                }
+            }
+        }
+
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyB) {
+            backend.set_bloom_enabled(!backend.bloom_enabled());
+        }
+
+        // Bloom intensity on Alt+Plus/Minus, only while bloom is on.
+        let alt_held = inputs.is_held(KeyCode::AltLeft);
+        if alt_held && backend.bloom_enabled() {
+            let steps =
+                inputs.is_pressed(KeyCode::Equal) as i32 - inputs.is_pressed(KeyCode::Minus) as i32;
+            if steps != 0 {
+                backend.set_bloom_intensity(
+                    backend.bloom_intensity() + steps as f32 * BLOOM_INTENSITY_STEP,
+                );
+            }
+        }
+
+        // Scale factor on KeyPlus and KeyMinus, or a scroll wheel notch / whole line of pinch.
+        // Ctrl/Shift+Plus/Minus are left to the frontend, Alt+Plus/Minus adjust bloom.
+        let scroll_lines = inputs.scroll_delta.trunc();
+        inputs.scroll_delta -= scroll_lines;
+        let keys = !inputs.is_held(KeyCode::ControlLeft)
+            && !inputs.is_held(KeyCode::ShiftLeft)
+            && !alt_held;
+        let zoom_out = (keys && inputs.is_pressed(KeyCode::Minus)) || scroll_lines < 0.0;
+        let zoom_in = (keys && inputs.is_pressed(KeyCode::Equal)) || scroll_lines > 0.0;
+        if zoom_out && frontend.get_texture_scale() > 1 {
             frontend.rescale_texture(frontend.get_texture_scale() - 1);
             backend.resize_texture(&frontend.get_texture_data());
-        } else if inputs.is_pressed(KeyCode::Equal) && frontend.get_texture_scale() < SIM_MAX_SCALE
-        {
+        } else if zoom_in && frontend.get_texture_scale() < SIM_MAX_SCALE {
             frontend.rescale_texture(frontend.get_texture_scale() + 1);
             backend.resize_texture(&frontend.get_texture_data());
         }
     }
 
+    // Copies the frontend texture & draws the key bindings over the right third of it.
+    fn draw_help_overlay(overlay: &mut World, texture_data: &TextureData) {
+        optick::event!("App::draw_help_overlay");
+
+        if overlay.viewport_size != texture_data.texture_size {
+            overlay.resize(texture_data.texture_size);
+        }
+        overlay
+            .viewport_texture
+            .copy_from_slice(texture_data.texture_buffer);
+
+        let size = texture_data.texture_size.cast::<i32>();
+        let left = size.x * 2 / 3;
+        overlay.draw_rect_blend(vec2(left, 0), vec2(size.x, size.y), HELP_BACKGROUND);
+
+        for (i, (key, description)) in F::help_text().iter().chain(APP_HELP_TEXT).enumerate() {
+            let key = format!("{key:?}");
+            let line = format!("{}: {description}", key.trim_start_matches("Key"));
+            let y = 2 + i as i32 * (GLYPH_HEIGHT + 1);
+            overlay.draw_text(vec2(left + 2, y), &line, WHITE);
+        }
+    }
+
+    // Injects the script's due events as if they were this frame's inputs.
+    fn apply_demo_events(demo: &mut DemoPlayback, frontend: &mut F, inputs: &mut InputData) {
+        for (time, event) in demo.due_events() {
+            trace!("Demo event at {time:?}: {event:?}");
+            match *event {
+                DemoEvent::MouseClick(pos) => {
+                    let click = MouseInput {
+                        state: true,
+                        pos,
+                        time: Instant::now(),
+                    };
+                    inputs.mouse_pos = pos;
+                    inputs.mouse_pressed = click;
+                    inputs.mouse_released = click;
+                }
+                DemoEvent::KeyPress(key) => inputs.keys_pressed[key as usize] = true,
+                DemoEvent::LoadScenario(preset) => frontend.load_scenario(preset),
+                DemoEvent::Explosion(pos) => frontend.explode_at(pos),
+            }
+        }
+    }
+
+    // Resizes the sim to the window, or to the largest aspect_ratio area that fits it.
+    // Returns the letterboxed viewport's top left corner.
+    fn resize_window(
+        frontend: &mut F,
+        backend: &mut Backend<'_>,
+        window_size: Vec2<u32, WindowSpace>,
+        aspect_ratio: Option<f64>,
+    ) -> Vec2<f64, WindowSpace> {
+        let (sim_size, [left, right, top, bottom]) = match aspect_ratio {
+            Some(aspect_ratio) => letterbox(window_size, aspect_ratio),
+            None => (window_size, [0; 4]),
+        };
+
+        frontend.resize_texture(sim_size);
+        backend.resize(window_size, &frontend.get_texture_data());
+        backend.set_letterbox_padding(left, right, top, bottom);
+        vec2(f64::from(left), f64::from(top))
+    }
+
+    fn toggle_inspector(inspector: &mut Option<Inspector>, target: &EventLoopWindowTarget<()>) {
+        *inspector = match inspector.take() {
+            Some(_) => None,
+            None => Inspector::new(target)
+                .inspect_err(|err| warn!("Failed to open inspector: {err}"))
+                .ok(),
+        };
+    }
+
+    pub fn start_demo(&mut self, script: DemoScript) {
+        self.demo = Some(DemoPlayback::new(script));
+    }
+
     fn clear_inputs(inputs: &mut InputData) {
         // Mouse held is bound by press,release events, these are not.
         inputs.mouse_pressed.state = false;
         inputs.mouse_released.state = false;
+        inputs.right_click.state = false;
         inputs.keys_pressed = [false; 256];
     }
 
-    // TODO(TOM): instead of sleeping, have multiple frames in flight, prob max 2 (front & back buffer)
-    fn timing(frame: usize, start: Instant, frame_timer: &mut Instant) -> Duration {
-        optick::event!("App::timing");
+    fn hud_title(sim_speed: f32, stats: &FrameTimingStats) -> String {
+        format!(
+            "{INIT_TITLE} | Speed: {sim_speed}x | FPS: {:.0} | min: {:.1}ms | avg: {:.1}ms | max: {:.1}ms",
+            stats.fps(),
+            stats.min.as_millis_f64(),
+            stats.avg.as_millis_f64(),
+            stats.max.as_millis_f64(),
+        )
+    }
+}
 
-        let elapsed = frame_timer.elapsed();
-        let remaining_frame_time = (FRAME_TIME_MS - elapsed.as_millis_f64()).max(0.0);
-        let avg_frame_time = start.elapsed() / frame as u32;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::dpi::PhysicalPosition;
 
-        // avg frametime
-        if frame % TARGET_FPS as usize == 0 {
-            info!("Frametime: {elapsed:.2?} | Avg Frametime: {avg_frame_time:.2?}",);
+    #[test]
+    fn hiding_help_uploads_the_whole_texture() {
+        let dirty = Some(Aabb::new(vec2(50, 60), vec2(54, 61)));
+        let mut tracker = UploadTracker::default();
+        assert_eq!(tracker.region(dirty, false), dirty);
+
+        // the overlay covers the frontend's texture, then has to be drawn over on the way out.
+        assert_eq!(tracker.region(dirty, true), None);
+        assert_eq!(tracker.region(dirty, true), None);
+        assert_eq!(tracker.region(dirty, false), None);
+        assert_eq!(tracker.region(dirty, false), dirty);
+    }
+
+    #[test]
+    fn horizontal_scroll_doesnt_zoom() {
+        let mut wheel = ScrollWheel::default();
+        assert_eq!(wheel.notches(MouseScrollDelta::LineDelta(1.0, 0.0)), 0.0);
+        assert_eq!(wheel.notches(MouseScrollDelta::LineDelta(0.0, 0.1)), 1.0);
+        assert_eq!(wheel.notches(MouseScrollDelta::LineDelta(0.0, -3.0)), -1.0);
+    }
+
+    #[test]
+    fn pixel_scroll_accumulates_to_a_notch() {
+        let mut wheel = ScrollWheel::default();
+        let pixels = |y| MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, y));
+        let step = SCROLL_PIXELS_PER_NOTCH / 4.0;
+        for _ in 0..3 {
+            assert_eq!(wheel.notches(pixels(step)), 0.0);
         }
+        assert_eq!(wheel.notches(pixels(step)), 1.0);
+        assert_eq!(wheel.notches(pixels(-step)), 0.0);
+        assert_eq!(wheel.notches(pixels(-SCROLL_PIXELS_PER_NOTCH)), -1.0);
+    }
 
-        if remaining_frame_time > MS_BUFFER {
-            let with_buffer = remaining_frame_time - MS_BUFFER;
-            std::thread::sleep(Duration::from_millis(with_buffer as u64));
+    #[test]
+    fn skipped_frame_uploads_the_whole_texture() {
+        let dirty = Some(Aabb::new(vec2(0, 0), vec2(3, 3)));
+        let mut tracker = UploadTracker::default();
+        tracker.upload_failed();
+        assert_eq!(tracker.region(dirty, false), None);
+        assert_eq!(tracker.region(dirty, false), dirty);
+    }
+
+    #[test]
+    fn early_frames_build_credit_but_skip_short_sleeps() {
+        let mut scheduler = FrameScheduler::new();
+        assert!(!scheduler.should_sleep(FRAME_TIME_MS));
+
+        let early = Duration::from_secs_f64((FRAME_TIME_MS - 1.0) / 1000.0);
+        for _ in 0..10 {
+            scheduler.end_frame(early);
         }
-        *frame_timer = Instant::now();
+        // 1ms ahead per frame, capped at a frame's worth.
+        assert!((scheduler.time_credit - FRAME_TIME_MS).abs() < 1e-6);
+        assert!(scheduler.should_sleep(FRAME_TIME_MS));
+        // another frame like those leaves 1ms, inside MS_BUFFER, so the sleep is suppressed.
+        assert!(!scheduler.should_sleep(1.0));
+
+        // falling behind spends the credit, sleeping stops until it's earned back.
+        let late = Duration::from_secs_f64(3.0 * FRAME_TIME_MS / 1000.0);
+        scheduler.end_frame(late);
+        assert!(!scheduler.should_sleep(FRAME_TIME_MS));
+    }
+
+    #[test]
+    fn physics_advances_in_whole_fixed_steps() {
+        let mut clock = PhysicsClock::default();
+        // uneven frames adding up to 3.3 steps of real time.
+        let steps: u32 = [0.3, 1.9, 0.5, 0.6]
+            .into_iter()
+            .map(|frame| clock.advance(frame * PHYSICS_DT, 1.0))
+            .sum();
+        assert_eq!(steps, 3);
+        assert!((clock.alpha() - 0.3).abs() < 1e-9);
+
+        // a long stall is capped, rather than spiralling.
+        assert_eq!(clock.advance(1.0, 1.0), MAX_PHYSICS_STEPS);
+    }
+
+    #[test]
+    fn sim_speed_scales_the_delta_time() {
+        let stats = FrameTimes::new().push(Duration::from_millis(8));
+        let doubled = stats.with_sim_speed(2.0);
+        assert_eq!(doubled.avg, stats.avg * 2);
+        assert_eq!(doubled.current, Duration::from_millis(16));
+        assert_eq!(doubled.sim_speed, 2.0);
+
+        // the same wall clock time covers twice as many physics steps, none while paused.
+        let elapsed = 1.5 * PHYSICS_DT;
+        let steps = |speed| PhysicsClock::default().advance(elapsed, speed);
+        assert_eq!(steps(1.0), 1);
+        assert_eq!(steps(2.0), 3);
+        assert_eq!(steps(0.0), 0);
+    }
+
+    #[test]
+    fn frame_times_average_over_the_window() {
+        let mut frame_times = FrameTimes::new();
+        let mut stats = frame_times.push(Duration::from_millis(7));
+        for _ in 0..FRAME_TIME_WINDOW {
+            stats = frame_times.push(Duration::from_millis(8));
+        }
+        // the 7ms frame has been pushed out of the window.
+        assert_eq!(stats.avg, Duration::from_millis(8));
+        assert_eq!(stats.min, Duration::from_millis(8));
+
+        let stats = frame_times.push(Duration::from_millis(16));
+        let expected = Duration::from_millis(8 * (FRAME_TIME_WINDOW as u64 - 1) + 16)
+            / FRAME_TIME_WINDOW as u32;
+        assert_eq!(stats.avg, expected);
+        assert_eq!(stats.current, Duration::from_millis(16));
+        assert_eq!(stats.max, Duration::from_millis(16));
+    }
+
+    #[test]
+    fn letterbox_pillarboxes_a_wide_window() {
+        let (size, padding) = letterbox(vec2(1920, 1080), 4.0 / 3.0);
+        assert_eq!(size, vec2(1440, 1080));
+        assert_eq!(padding, [240, 240, 0, 0]);
+
+        // too tall instead, bars above & below.
+        let (size, padding) = letterbox(vec2(1440, 1280), 4.0 / 3.0);
+        assert_eq!(size, vec2(1440, 1080));
+        assert_eq!(padding, [0, 0, 100, 100]);
+    }
+
+    #[test]
+    fn refocusing_restarts_the_timers() {
+        let mut focus_pause = FocusPause::default();
+        let mut scheduler = FrameScheduler::new();
+        let mut physics_clock = PhysicsClock::default();
+        let away = Instant::now() - Duration::from_secs(5);
+        let mut physics_timer = away;
+        scheduler.frame_timer = away;
+        physics_clock.advance(PHYSICS_DT / 2.0, 1.0);
+
+        focus_pause.focus_changed(
+            false,
+            &mut scheduler,
+            &mut physics_clock,
+            &mut physics_timer,
+        );
+        // run skips frontend.update while paused, so no frames pass in the gap.
+        let updates = (0..10).filter(|_| !focus_pause.is_paused()).count();
+        assert_eq!(updates, 0);
+
+        focus_pause.focus_changed(true, &mut scheduler, &mut physics_clock, &mut physics_timer);
+        assert!(!focus_pause.is_paused());
+        assert!(scheduler.frame_timer.elapsed() < Duration::from_secs(1));
+        assert!(physics_timer.elapsed() < Duration::from_secs(1));
+        assert_eq!(physics_clock.alpha(), 0.0);
+    }
+
+    #[test]
+    fn question_mark_toggles_help() {
+        let mut inputs = InputData::default();
+        inputs.keys_pressed[KeyCode::Slash as usize] = true;
+        assert!(!help_toggled(&inputs));
+
+        inputs.keys_held[KeyCode::ShiftRight as usize] = true;
+        let mut show_help = false;
+        for _ in 0..2 {
+            if help_toggled(&inputs) {
+                show_help = !show_help;
+            }
+        }
+        assert!(!show_help);
+    }
 
-        return avg_frame_time;
+    #[test]
+    fn every_frontend_has_help_text() {
+        use crate::frontends::{
+            cell_sim::CellSim, falling_everything::FallingEverything, gravity_sim::GravitySim,
+        };
+        assert!(!CellSim::help_text().is_empty());
+        assert!(!FallingEverything::help_text().is_empty());
+        assert!(!GravitySim::help_text().is_empty());
     }
 }
@@ -1,19 +1,61 @@
 use crate::{
     frontend::TextureData,
     utils::{
-        consts::INIT_TITLE,
+        aabb::Aabb,
+        consts::{BLOOM_THRESHOLD, INIT_BLOOM_INTENSITY, INIT_TITLE, MAX_BLOOM_INTENSITY},
         vec2::{vec2, TextureSpace, Vec2, WindowSpace},
     },
 };
 use image::Frame;
 use log::{error, info, trace};
-use std::time::Instant;
+use std::{error::Error, fmt, time::Instant};
 use wgpu::{CompositeAlphaMode, DeviceDescriptor};
 use winit::{
     dpi::PhysicalSize,
     event_loop::EventLoop,
     window::{Window, WindowAttributes, WindowBuilder},
 };
+#[cfg(feature = "compute")]
+use {crate::frontend::GpuParticleData, log::warn};
+
+#[derive(Debug)]
+pub enum EngineError {
+    SurfaceCreationFailed(wgpu::CreateSurfaceError),
+    AdapterNotFound,
+    DeviceCreationFailed(wgpu::RequestDeviceError),
+    SurfaceConfigurationFailed, // the adapter reports no usable surface formats
+    TextureFormatUnsupported(wgpu::TextureFormat),
+    SurfaceError(wgpu::SurfaceError),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SurfaceCreationFailed(e) => write!(f, "failed to create surface: {e}"),
+            Self::AdapterNotFound => write!(f, "no compatible GPU adapter found"),
+            Self::DeviceCreationFailed(e) => write!(f, "failed to create device: {e}"),
+            Self::SurfaceConfigurationFailed => write!(f, "surface has no supported formats"),
+            Self::TextureFormatUnsupported(format) => {
+                write!(
+                    f,
+                    "surface doesn't support {format:?}, only Rgba8Unorm is handled"
+                )
+            }
+            Self::SurfaceError(e) => write!(f, "surface error: {e}"),
+        }
+    }
+}
+
+impl Error for EngineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SurfaceCreationFailed(e) => Some(e),
+            Self::DeviceCreationFailed(e) => Some(e),
+            Self::SurfaceError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 pub struct Backend<'a> {
     pub window: &'a Window,
@@ -29,6 +71,13 @@ pub struct Backend<'a> {
     gpu_data_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
     sampler: wgpu::Sampler,
+    clear_colour: Option<wgpu::Color>, // None keeps the previous frame (LoadOp::Load)
+    letterbox: [u32; 4],               // left, right, top, bottom padding in window pixels
+    bloom_enabled: bool,
+    bloom_pipelines: BloomPipelines,
+    bloom_targets: BloomTargets, // texture sized, recreated with the texture
+    #[cfg(feature = "compute")]
+    gpu_forces: GpuForces,
 }
 
 // Data to pass to gpu, MUST have 16 byte alignment
@@ -38,30 +87,91 @@ pub struct GpuUniforms {
     pub padding: [f32; 3],
     pub time: f32,
     pub texture_size: [f32; 2],
-    pub window_size: [f32; 2],
+    pub window_size: [f32; 2], // viewport size, the window minus any letterbox bars
+    pub viewport_offset: [f32; 2],
+    pub bloom_intensity: f32,
+    pub bloom_threshold: f32, // luminance [0, 1]
 }
 
 unsafe impl bytemuck::Zeroable for GpuUniforms {}
 unsafe impl bytemuck::Pod for GpuUniforms {}
 
+// One per bloom render pass, all share bind_group_layout, composite also takes it as group 1.
+struct BloomPipelines {
+    horizontal: wgpu::RenderPipeline,
+    vertical: wgpu::RenderPipeline,
+    composite: wgpu::RenderPipeline,
+}
+
+// Intermediate blur targets, each bound in bind_group_layout's texture slot for the next pass.
+struct BloomTargets {
+    horizontal_view: wgpu::TextureView,
+    horizontal_bind_group: wgpu::BindGroup,
+    vertical_view: wgpu::TextureView,
+    vertical_bind_group: wgpu::BindGroup,
+}
+
+// Matches compute.wgsl's GpuParticle, 32 bytes with vec2's 8 byte alignment.
+#[cfg(feature = "compute")]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GpuParticle {
+    pub pos: [f32; 2],
+    pub vel: [f32; 2],
+    pub force: [f32; 2], // written by the compute pass
+    pub mass: f32,
+    pub radius: f32,
+}
+
+#[cfg(feature = "compute")]
+unsafe impl bytemuck::Zeroable for GpuParticle {}
+#[cfg(feature = "compute")]
+unsafe impl bytemuck::Pod for GpuParticle {}
+
+#[cfg(feature = "compute")]
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct GpuForceParams {
+    count: u32,
+    g: f32,
+    softening: f32,
+    padding: f32,
+}
+
+#[cfg(feature = "compute")]
+unsafe impl bytemuck::Zeroable for GpuForceParams {}
+#[cfg(feature = "compute")]
+unsafe impl bytemuck::Pod for GpuForceParams {}
+
+// N-body force pipeline, the particle & staging buffers grow to fit the largest slice seen.
+#[cfg(feature = "compute")]
+struct GpuForces {
+    pipeline: wgpu::ComputePipeline,
+    params_buffer: wgpu::Buffer,
+    particle_buffer: wgpu::Buffer, // STORAGE, read & written by compute.wgsl
+    staging_buffer: wgpu::Buffer,  // MAP_READ copy of particle_buffer for the CPU
+    bind_group: wgpu::BindGroup,
+    capacity: usize, // particles
+}
+
 impl<'a> Backend<'a> {
-    pub fn render(&mut self, texture_data: &TextureData, start: Instant) {
+    // Errors skip the frame, only SurfaceError::OutOfMemory is unrecoverable.
+    pub fn render(
+        &mut self,
+        texture_data: &TextureData,
+        start: Instant,
+    ) -> Result<(), EngineError> {
         optick::event!("Backend::render");
 
         let frame = match self.surface.get_current_texture() {
             Ok(frame) => frame,
-            // can't gracefully exit in oom states
-            Err(wgpu::SurfaceError::OutOfMemory) => std::process::exit(0),
             Err(wgpu::SurfaceError::Lost) => {
                 self.resize(self.window_size, texture_data);
                 // TODO(TOM): logging the error, but not handling it.
                 error!("SurfaceError::Lost, cannot resize simulation in this scope. fix this tom!");
-                return;
-            }
-            Err(e) => {
-                error!("{e:#?}");
-                return;
+                return Ok(());
             }
+            Err(e) => return Err(EngineError::SurfaceError(e)),
         };
 
         // Creates necessary metadata of the texture for the render pass.
@@ -77,13 +187,65 @@ impl<'a> Backend<'a> {
                 label: Some("Render Encoder"),
             });
 
+        {
+            optick::event!("Update gpu uniforms");
+            // Writing new time value to a GPU buffer, for shader code to access!
+            self.gpu_uniforms.time = start.elapsed().as_millis_f32();
+            self.queue.write_buffer(
+                &self.gpu_data_buffer,
+                0, // the entire uniform buffer is updated.
+                bytemuck::cast_slice(&[self.gpu_uniforms]),
+            );
+        }
+        {
+            optick::event!("Update texture");
+            Self::update_texture(&self.queue, &self.texture, texture_data, false);
+        }
+
+        // Bright texels are blurred horizontally then vertically into the bloom targets, the
+        // composite pass below adds the result on top of the texture.
+        if self.bloom_enabled {
+            optick::event!("Bloom blur passes");
+            let blur_passes = [
+                (
+                    &self.bloom_pipelines.horizontal,
+                    &self.bind_group,
+                    &self.bloom_targets.horizontal_view,
+                ),
+                (
+                    &self.bloom_pipelines.vertical,
+                    &self.bloom_targets.horizontal_bind_group,
+                    &self.bloom_targets.vertical_view,
+                ),
+            ];
+            for (pipeline, bind_group, target) in blur_passes {
+                let mut blur_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Bloom Blur Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: target,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+                blur_pass.set_pipeline(pipeline);
+                blur_pass.set_bind_group(0, bind_group, &[]);
+                blur_pass.draw(0..6, 0..1);
+            }
+        }
+
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
+                    load: Self::load_op(self.clear_colour, self.is_letterboxed()),
                     store: wgpu::StoreOp::Store,
                 },
             })],
@@ -91,25 +253,29 @@ impl<'a> Backend<'a> {
             occlusion_query_set: None,
             timestamp_writes: None,
         });
-        render_pass.set_pipeline(&self.render_pipeline);
-        render_pass.set_bind_group(0, &self.bind_group, &[]);
-        trace!("Bound items to render pass");
-
-        {
-            optick::event!("Update gpu uniforms");
-            // Writing new time value to a GPU buffer, for shader code to access!
-            self.gpu_uniforms.time = start.elapsed().as_millis_f32();
-            self.queue.write_buffer(
-                &self.gpu_data_buffer,
-                0, // the entire uniform buffer is updated.
-                bytemuck::cast_slice(&[self.gpu_uniforms]),
+        if self.is_letterboxed() {
+            let [left, _, top, _] = self.letterbox;
+            let viewport = self.viewport_size();
+            render_pass.set_viewport(
+                left as f32,
+                top as f32,
+                viewport.x as f32,
+                viewport.y as f32,
+                0.0,
+                1.0,
             );
         }
+        if self.bloom_enabled {
+            render_pass.set_pipeline(&self.bloom_pipelines.composite);
+            render_pass.set_bind_group(1, &self.bloom_targets.vertical_bind_group, &[]);
+        } else {
+            render_pass.set_pipeline(&self.render_pipeline);
+        }
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        trace!("Bound items to render pass");
 
         {
-            optick::event!("Update texture && draw");
-            Self::update_texture(&self.queue, &self.texture, texture_data);
-
+            optick::event!("Draw");
             // Takes 6 vertices (2 triangles = 1 square) and the vertex & fragment shader
             render_pass.draw(0..6, 0..1);
         }
@@ -121,6 +287,60 @@ impl<'a> Backend<'a> {
             self.queue.submit(std::iter::once(encoder.finish()));
             frame.present();
         }
+        Ok(())
+    }
+
+    pub fn set_clear_colour(&mut self, colour: Option<wgpu::Color>) {
+        info!("Clear colour: {colour:?}");
+        self.clear_colour = colour;
+    }
+
+    pub fn bloom_enabled(&self) -> bool {
+        self.bloom_enabled
+    }
+
+    pub fn set_bloom_enabled(&mut self, enabled: bool) {
+        info!("Bloom: {enabled}");
+        self.bloom_enabled = enabled;
+    }
+
+    pub fn bloom_intensity(&self) -> f32 {
+        self.gpu_uniforms.bloom_intensity
+    }
+
+    // Clamped to [0, MAX_BLOOM_INTENSITY].
+    pub fn set_bloom_intensity(&mut self, intensity: f32) {
+        self.gpu_uniforms.bloom_intensity = intensity.clamp(0.0, MAX_BLOOM_INTENSITY);
+        info!("Bloom intensity: {:.1}", self.gpu_uniforms.bloom_intensity);
+    }
+
+    // Shrinks the viewport by the given padding, the bars around it are cleared to black.
+    pub fn set_letterbox_padding(&mut self, left: u32, right: u32, top: u32, bottom: u32) {
+        trace!("Letterbox padding: {left}, {right}, {top}, {bottom}");
+        self.letterbox = [left, right, top, bottom];
+        self.gpu_uniforms.window_size = self.viewport_size().cast().to_array();
+        self.gpu_uniforms.viewport_offset = [left as f32, top as f32];
+    }
+
+    // How the render pass starts, the clear colour if there is one or the previous frame.
+    fn load_op(clear_colour: Option<wgpu::Color>, letterboxed: bool) -> wgpu::LoadOp<wgpu::Color> {
+        // letterbox bars are outside the viewport, so would never be overwritten.
+        if letterboxed {
+            return wgpu::LoadOp::Clear(wgpu::Color::BLACK);
+        }
+        clear_colour.map_or(wgpu::LoadOp::Load, wgpu::LoadOp::Clear)
+    }
+
+    fn is_letterboxed(&self) -> bool {
+        self.letterbox.iter().any(|&padding| padding > 0)
+    }
+
+    fn viewport_size(&self) -> Vec2<u32, WindowSpace> {
+        let [left, right, top, bottom] = self.letterbox;
+        vec2(
+            self.window_size.x.saturating_sub(left + right),
+            self.window_size.y.saturating_sub(top + bottom),
+        )
     }
 
     pub fn resize(&mut self, window_size: Vec2<u32, WindowSpace>, texture_data: &TextureData) {
@@ -171,7 +391,10 @@ impl<'a> Backend<'a> {
             padding: self.gpu_uniforms.padding,
             time: self.gpu_uniforms.time,
             texture_size: texture_data.texture_size.cast().to_array(),
-            window_size: self.window_size.cast().to_array(),
+            window_size: self.viewport_size().cast().to_array(),
+            viewport_offset: self.gpu_uniforms.viewport_offset,
+            bloom_intensity: self.gpu_uniforms.bloom_intensity,
+            bloom_threshold: self.gpu_uniforms.bloom_threshold,
         };
 
         // update binding group
@@ -196,11 +419,24 @@ impl<'a> Backend<'a> {
                 },
             ],
         });
+        self.bloom_targets = Self::create_bloom_targets(
+            &self.device,
+            &self.bind_group_layout,
+            &self.texture,
+            &self.sampler,
+            &self.gpu_data_buffer,
+        );
 
-        Self::update_texture(&self.queue, &self.texture, texture_data);
+        // New texture, so everything must be uploaded.
+        Self::update_texture(&self.queue, &self.texture, texture_data, true);
     }
 
-    fn update_texture(queue: &wgpu::Queue, texture: &wgpu::Texture, texture_data: &TextureData) {
+    fn update_texture(
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        texture_data: &TextureData,
+        full_upload: bool,
+    ) {
         let tex_size = texture.size();
         let computed_data_len =
             (4 * texture_data.texture_size.x * texture_data.texture_size.y) as usize;
@@ -213,56 +449,132 @@ impl<'a> Backend<'a> {
             "{texture_data:#?}"
         );
 
+        if !full_upload {
+            if let Some(region) = texture_data.dirty_region {
+                if let Some((origin, extent)) =
+                    Self::dirty_region_upload(region, texture_data.texture_size)
+                {
+                    Self::update_texture_region(queue, texture, texture_data, origin, extent);
+                }
+                return;
+            }
+        }
+
+        Self::write_whole_texture(queue, texture, texture_data);
+    }
+
+    // Texel rectangle to upload for an inclusive dirty region, None if it's inverted (or off the texture).
+    fn dirty_region_upload(
+        region: Aabb<u32, TextureSpace>,
+        texture_size: Vec2<u32, TextureSpace>,
+    ) -> Option<(wgpu::Origin3d, wgpu::Extent3d)> {
+        let max = region.max.clamp(vec2(0, 0), texture_size - 1);
+        if region.min.x > max.x || region.min.y > max.y {
+            return None;
+        }
+        Some((
+            wgpu::Origin3d {
+                x: region.min.x,
+                y: region.min.y,
+                z: 0,
+            },
+            wgpu::Extent3d {
+                width: max.x + 1 - region.min.x,
+                height: max.y + 1 - region.min.y,
+                depth_or_array_layers: 1,
+            },
+        ))
+    }
+
+    fn write_whole_texture(
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        texture_data: &TextureData,
+    ) {
+        Self::update_texture_region(
+            queue,
+            texture,
+            texture_data,
+            wgpu::Origin3d::ZERO,
+            wgpu::Extent3d {
+                width: texture_data.texture_size.x,
+                height: texture_data.texture_size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    // Uploads only the extent sized rectangle at origin, read in place from the full buffer.
+    fn update_texture_region(
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        texture_data: &TextureData,
+        origin: wgpu::Origin3d,
+        extent: wgpu::Extent3d,
+    ) {
+        let bytes_per_row = 4 * texture_data.texture_size.x;
+        let start = (origin.y * bytes_per_row + 4 * origin.x) as usize;
+        let end = start + ((extent.height - 1) * bytes_per_row + 4 * extent.width) as usize;
+
         queue.write_texture(
             wgpu::ImageCopyTexture {
                 texture,
                 mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
+                origin,
                 aspect: wgpu::TextureAspect::All,
             },
-            texture_data.texture_buffer,
+            &texture_data.texture_buffer[start..end],
             wgpu::ImageDataLayout {
                 offset: 0,
-                bytes_per_row: Some(4 * texture_data.texture_size.x),
-                rows_per_image: Some(texture_data.texture_size.y),
-            },
-            wgpu::Extent3d {
-                width: texture_data.texture_size.x,
-                height: texture_data.texture_size.y,
-                depth_or_array_layers: 1,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(extent.height),
             },
+            extent,
         );
     }
 
+    // None for compatible_surface accepts any adapter, e.g. without a window.
+    async fn request_adapter(
+        instance: &wgpu::Instance,
+        compatible_surface: Option<&wgpu::Surface<'_>>,
+    ) -> Result<wgpu::Adapter, EngineError> {
+        instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or(EngineError::AdapterNotFound)
+    }
+
     async fn create_surface(
         window: &'a Window,
         window_size: Vec2<u32, WindowSpace>,
         instance: &wgpu::Instance,
-    ) -> (
-        wgpu::Surface<'a>,
-        wgpu::Device,
-        wgpu::Queue,
-        wgpu::SurfaceConfiguration,
-    ) {
-        let surface: wgpu::Surface<'a> = unsafe { instance.create_surface(window) }.unwrap();
+    ) -> Result<
+        (
+            wgpu::Surface<'a>,
+            wgpu::Device,
+            wgpu::Queue,
+            wgpu::SurfaceConfiguration,
+        ),
+        EngineError,
+    > {
+        let surface: wgpu::Surface<'a> = instance
+            .create_surface(window)
+            .map_err(EngineError::SurfaceCreationFailed)?;
         info!("Surface created");
 
         // >> Requesting Adapter (gpu abstraction) <<
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
+        let adapter = Self::request_adapter(instance, Some(&surface)).await?;
         info!("Adapter created");
 
         // >> Creating Device and Queue <<
         let (device, queue) = adapter
             .request_device(&DeviceDescriptor::default(), None)
             .await
-            .unwrap();
+            .map_err(EngineError::DeviceCreationFailed)?;
         info!("Device and Queue created");
 
         // >> Creating Surface Config <<
@@ -271,9 +583,12 @@ impl<'a> Backend<'a> {
             .formats
             .iter()
             .find(|x| **x == wgpu::TextureFormat::Rgba8Unorm)
+            .or(capabilities.formats.first())
             .copied()
-            .unwrap_or(capabilities.formats[0]);
-        assert_eq!(surface_format, wgpu::TextureFormat::Rgba8Unorm);
+            .ok_or(EngineError::SurfaceConfigurationFailed)?;
+        if surface_format != wgpu::TextureFormat::Rgba8Unorm {
+            return Err(EngineError::TextureFormatUnsupported(surface_format));
+        }
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -286,9 +601,9 @@ impl<'a> Backend<'a> {
             view_formats: Vec::new(),
         };
         surface.configure(&device, &config);
-        info!("Surface configured with format '{surface_format:?}', {window_size:?}");
+        info!("Surface configured with format '{surface_format:?}', {window_size}");
 
-        (surface, device, queue, config)
+        Ok((surface, device, queue, config))
     }
 
     fn create_texture(
@@ -317,7 +632,7 @@ impl<'a> Backend<'a> {
             // not supported on the WebGL2 backend.
             view_formats: &[],
         });
-        Self::update_texture(queue, &texture, texture_data);
+        Self::update_texture(queue, &texture, texture_data, true);
         info!("Texture created, size: {:?}", texture.size());
 
         texture
@@ -326,7 +641,7 @@ impl<'a> Backend<'a> {
     fn create_render_pipeline(
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
-    ) -> (wgpu::RenderPipeline, wgpu::BindGroupLayout) {
+    ) -> (wgpu::RenderPipeline, BloomPipelines, wgpu::BindGroupLayout) {
         // >> Creating bind group layout <<
         let shader = device.create_shader_module(wgpu::include_wgsl!("shader.wgsl"));
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -363,27 +678,131 @@ impl<'a> Backend<'a> {
             ],
         });
 
-        // >> Creating Render Pipeline <<
+        // >> Creating Render Pipelines <<
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Pipeline Layout"),
             bind_group_layouts: &[&bind_group_layout],
             push_constant_ranges: &[],
         });
+        // group 1 only supplies the vertical blur's texture.
+        let composite_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Bloom Composite Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
+        let pipeline = |label: &str, layout: &wgpu::PipelineLayout, fs_entry_point: &str| {
+            Self::create_fullscreen_pipeline(
+                device,
+                label,
+                layout,
+                &shader,
+                fs_entry_point,
+                config.format,
+            )
+        };
+        let render_pipeline = pipeline("Render Pipeline", &pipeline_layout, "fs_main");
+        let bloom_pipelines = BloomPipelines {
+            horizontal: pipeline(
+                "Bloom Horizontal Pipeline",
+                &pipeline_layout,
+                "fs_blur_horizontal",
+            ),
+            vertical: pipeline(
+                "Bloom Vertical Pipeline",
+                &pipeline_layout,
+                "fs_blur_vertical",
+            ),
+            composite: pipeline(
+                "Bloom Composite Pipeline",
+                &composite_layout,
+                "fs_main_composite",
+            ),
+        };
+        info!("Render Pipelines created");
+
+        (render_pipeline, bloom_pipelines, bind_group_layout)
+    }
+
+    // Both blur targets match texture's size & format, each with a bind group sampling it.
+    fn create_bloom_targets(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        texture: &wgpu::Texture,
+        sampler: &wgpu::Sampler,
+        gpu_data_buffer: &wgpu::Buffer,
+    ) -> BloomTargets {
+        let target = |label| {
+            let view = device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: texture.size(),
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: texture.format(),
+                    // drawn into by one blur pass, sampled by the next.
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(label),
+                layout: bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: gpu_data_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+            (view, bind_group)
+        };
+
+        let (horizontal_view, horizontal_bind_group) = target("Bloom Horizontal Target");
+        let (vertical_view, vertical_bind_group) = target("Bloom Vertical Target");
+        trace!("Bloom targets created, size: {:?}", texture.size());
+
+        BloomTargets {
+            horizontal_view,
+            horizontal_bind_group,
+            vertical_view,
+            vertical_bind_group,
+        }
+    }
+
+    // A full screen quad from vs_main, shaded by fs_entry_point into a format target.
+    fn create_fullscreen_pipeline(
+        device: &wgpu::Device,
+        label: &str,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        fs_entry_point: &str,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: shader,
                 entry_point: "vs_main",
                 buffers: &[],
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
+                module: shader,
+                entry_point: fs_entry_point,
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format,
                     blend: Some(wgpu::BlendState::REPLACE),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -409,10 +828,7 @@ impl<'a> Backend<'a> {
             },
             multiview: None,
             cache: None,
-        });
-        info!("Render Pipeline created");
-
-        (render_pipeline, bind_group_layout)
+        })
     }
 
     fn create_gpu_uniforms(
@@ -426,6 +842,9 @@ impl<'a> Backend<'a> {
             time: 0.0,
             texture_size: texture_size.cast().to_array(),
             window_size: window_size.cast().to_array(),
+            viewport_offset: [0.0; 2],
+            bloom_intensity: INIT_BLOOM_INTENSITY,
+            bloom_threshold: BLOOM_THRESHOLD,
         };
         let gpu_data_buffer = wgpu::util::DeviceExt::create_buffer_init(
             device,
@@ -485,7 +904,7 @@ impl<'a> Backend<'a> {
         window: &'a Window,
         window_size: Vec2<u32, WindowSpace>,
         texture_data: TextureData<'_>,
-    ) -> Self {
+    ) -> Result<Self, EngineError> {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::PRIMARY,
             // TODO(TOM): if wasm, use GL.
@@ -494,11 +913,12 @@ impl<'a> Backend<'a> {
         info!("Instance created");
 
         let (surface, device, queue, config) =
-            Self::create_surface(&window, window_size, &instance).await;
+            Self::create_surface(&window, window_size, &instance).await?;
 
         let texture = Self::create_texture(&texture_data, &queue, &device, &config);
 
-        let (render_pipeline, bind_group_layout) = Self::create_render_pipeline(&device, &config);
+        let (render_pipeline, bloom_pipelines, bind_group_layout) =
+            Self::create_render_pipeline(&device, &config);
 
         let (gpu_uniforms, gpu_data_buffer) =
             Self::create_gpu_uniforms(&device, texture_data.texture_size, window_size);
@@ -506,7 +926,18 @@ impl<'a> Backend<'a> {
         let (bind_group, sampler) =
             Self::create_bind_group(&device, &bind_group_layout, &texture, &gpu_data_buffer);
 
-        Self {
+        let bloom_targets = Self::create_bloom_targets(
+            &device,
+            &bind_group_layout,
+            &texture,
+            &sampler,
+            &gpu_data_buffer,
+        );
+
+        #[cfg(feature = "compute")]
+        let gpu_forces = Self::create_gpu_forces(&device, 1);
+
+        Ok(Self {
             window,
             window_size,
             surface,
@@ -520,6 +951,311 @@ impl<'a> Backend<'a> {
             gpu_data_buffer,
             bind_group,
             sampler,
+            clear_colour: None,
+            letterbox: [0; 4],
+            bloom_enabled: false,
+            bloom_pipelines,
+            bloom_targets,
+            #[cfg(feature = "compute")]
+            gpu_forces,
+        })
+    }
+}
+
+#[cfg(feature = "compute")]
+impl<'a> Backend<'a> {
+    // Runs the O(n²) gravity sum on the gpu, one workgroup per particle, & writes each force
+    // back into particles. Blocks on the readback, so call it before the frame is rendered.
+    pub fn compute_forces(&mut self, particle_data: GpuParticleData) {
+        optick::event!("Backend::compute_forces");
+        let GpuParticleData {
+            particles,
+            g,
+            softening,
+        } = particle_data;
+        if particles.is_empty() {
+            return;
+        }
+        let max_workgroups = self.device.limits().max_compute_workgroups_per_dimension;
+        if particles.len() > max_workgroups as usize {
+            warn!(
+                "{} particles exceeds {max_workgroups} workgroups, skipping gpu forces",
+                particles.len()
+            );
+            return;
+        }
+        if particles.len() > self.gpu_forces.capacity {
+            self.gpu_forces = Self::create_gpu_forces(&self.device, particles.len());
+        }
+
+        let forces = &self.gpu_forces;
+        let params = GpuForceParams {
+            count: particles.len() as u32,
+            g,
+            softening,
+            padding: 0.0,
+        };
+        self.queue
+            .write_buffer(&forces.params_buffer, 0, bytemuck::cast_slice(&[params]));
+        self.queue
+            .write_buffer(&forces.particle_buffer, 0, bytemuck::cast_slice(particles));
+
+        let size = (particles.len() * std::mem::size_of::<GpuParticle>()) as wgpu::BufferAddress;
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Compute Encoder"),
+            });
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Gravity Compute Pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(&forces.pipeline);
+            compute_pass.set_bind_group(0, &forces.bind_group, &[]);
+            compute_pass.dispatch_workgroups(particles.len() as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&forces.particle_buffer, 0, &forces.staging_buffer, 0, size);
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let staging = forces.staging_buffer.slice(..size);
+        staging.map_async(wgpu::MapMode::Read, |result| {
+            if let Err(e) = result {
+                error!("Failed to map gpu force staging buffer: {e}");
+            }
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        {
+            let mapped = staging.get_mapped_range();
+            let results: &[GpuParticle] = bytemuck::cast_slice(&mapped);
+            for (particle, result) in particles.iter_mut().zip(results) {
+                particle.force = result.force;
+            }
+        }
+        forces.staging_buffer.unmap();
+    }
+
+    fn create_gpu_forces(device: &wgpu::Device, capacity: usize) -> GpuForces {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("compute.wgsl"));
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("compute_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compute Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Gravity Compute Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "cs_main",
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let size = (capacity * std::mem::size_of::<GpuParticle>()) as wgpu::BufferAddress;
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compute Params Buffer"),
+            size: std::mem::size_of::<GpuForceParams>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Storage Buffer"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Staging Buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("compute_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        info!("Gravity compute pipeline created, capacity: {capacity} particles");
+
+        GpuForces {
+            pipeline,
+            params_buffer,
+            particle_buffer,
+            staging_buffer,
+            bind_group,
+            capacity,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use super::*;
+    use crate::{frontend::Frontend, frontends::cell_sim::CellSim, utils::colour::Rgba};
+
+    // Bytes write_texture copies for the frame's dirty region, everything without one.
+    fn uploaded_bytes(
+        dirty_region: Option<Aabb<u32, TextureSpace>>,
+        texture_size: Vec2<u32, TextureSpace>,
+    ) -> u32 {
+        match dirty_region {
+            Some(region) => Backend::dirty_region_upload(region, texture_size)
+                .map_or(0, |(_, extent)| 4 * extent.width * extent.height),
+            None => 4 * texture_size.x * texture_size.y,
+        }
+    }
+
+    #[test]
+    fn dirty_region_upload_scales_with_changed_cells() {
+        let mut sim = CellSim::new(vec2(1600, 1200), 1);
+        let texture_size = sim.get_texture_data().texture_size;
+        // the first frame uploads everything.
+        assert_eq!(
+            uploaded_bytes(sim.take_dirty_region(), texture_size),
+            4 * 1600 * 1200
+        );
+        assert_eq!(uploaded_bytes(sim.take_dirty_region(), texture_size), 0);
+
+        // 10 live cells in a 5x2 block, only their bounding box is uploaded.
+        let cells: Vec<_> = (0..10).map(|i| (i % 5, i / 5)).collect();
+        sim.paste_pattern(&cells, vec2(50, 60));
+        let dirty_region = sim.take_dirty_region();
+        assert_eq!(dirty_region, Some(Aabb::new(vec2(50, 60), vec2(54, 61))));
+        assert_eq!(uploaded_bytes(dirty_region, texture_size), 4 * 10);
+    }
+
+    #[test]
+    fn no_backends_is_adapter_not_found() {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::empty(),
+            ..Default::default()
+        });
+        let adapter = pollster::block_on(Backend::request_adapter(&instance, None));
+        assert!(matches!(adapter, Err(EngineError::AdapterNotFound)));
+    }
+
+    #[test]
+    fn reading_texture_data_keeps_the_dirty_region() {
+        let mut sim = CellSim::new(vec2(400, 300), 1);
+        sim.take_dirty_region();
+        sim.paste_pattern(&[(0, 0)], vec2(10, 10));
+        // App reads the texture size between frames, that mustn't drop pending changes.
+        sim.get_texture_data();
+        sim.get_texture_data();
+        assert_eq!(
+            sim.take_dirty_region(),
+            Some(Aabb::new(vec2(10, 10), vec2(10, 10)))
+        );
+    }
+
+    // Copies the rows write_texture would read, a stand-in for the upload without a gpu.
+    fn copy_upload(
+        texture_data: &TextureData,
+        dirty_region: Option<Aabb<u32, TextureSpace>>,
+        staging: &mut Vec<u8>,
+    ) {
+        let size = texture_data.texture_size;
+        let (origin, extent) = match dirty_region {
+            Some(region) => match Backend::dirty_region_upload(region, size) {
+                Some(upload) => upload,
+                None => return,
+            },
+            None => (
+                wgpu::Origin3d::ZERO,
+                wgpu::Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+            ),
+        };
+        staging.clear();
+        for y in origin.y..origin.y + extent.height {
+            let start = (4 * (y * size.x + origin.x)) as usize;
+            staging.extend_from_slice(
+                &texture_data.texture_buffer[start..start + 4 * extent.width as usize],
+            );
+        }
+    }
+
+    #[test]
+    fn clear_colour_picks_the_load_op() {
+        let black: wgpu::Color = Rgba::from_rgb(0, 0, 0).into();
+        assert_eq!(black, wgpu::Color::BLACK);
+        assert!(matches!(
+            Backend::load_op(Some(black), false),
+            wgpu::LoadOp::Clear(colour) if colour == wgpu::Color::BLACK
+        ));
+        assert!(matches!(Backend::load_op(None, false), wgpu::LoadOp::Load));
+        // the letterbox bars are always cleared, even when the frame is kept.
+        assert!(matches!(
+            Backend::load_op(None, true),
+            wgpu::LoadOp::Clear(colour) if colour == wgpu::Color::BLACK
+        ));
+    }
+
+    // One frame of 10 changed cells at 1600x1200, compare with full_upload_1600x1200.
+    #[bench]
+    fn dirty_region_upload_1600x1200(b: &mut test::Bencher) {
+        let mut sim = CellSim::new(vec2(1600, 1200), 1);
+        let cells: Vec<_> = (0..10).map(|i| (i % 5, i / 5)).collect();
+        let mut staging = Vec::new();
+        b.iter(|| {
+            sim.paste_pattern(&cells, vec2(50, 60));
+            let dirty_region = sim.take_dirty_region();
+            copy_upload(&sim.get_texture_data(), dirty_region, &mut staging);
+            test::black_box(&staging);
+        });
+    }
+
+    #[bench]
+    fn full_upload_1600x1200(b: &mut test::Bencher) {
+        let mut sim = CellSim::new(vec2(1600, 1200), 1);
+        let cells: Vec<_> = (0..10).map(|i| (i % 5, i / 5)).collect();
+        let mut staging = Vec::new();
+        b.iter(|| {
+            sim.paste_pattern(&cells, vec2(50, 60));
+            sim.take_dirty_region();
+            copy_upload(&sim.get_texture_data(), None, &mut staging);
+            test::black_box(&staging);
+        });
+    }
+}
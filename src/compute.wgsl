@@ -0,0 +1,63 @@
+// N-body gravity, one workgroup per particle, its invocations split the O(n) sum & reduce it.
+// Only built with the "compute" feature, GravitySim's CPU loop is the fallback.
+
+struct GpuParticle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    force: vec2<f32>,
+    mass: f32,
+    radius: f32,
+}
+
+struct Params {
+    count: u32,
+    g: f32,
+    softening: f32, // force uses r² + softening² so close passes can't blow up
+    _padding: f32,
+}
+
+const WORKGROUP_SIZE: u32 = 64u;
+
+@group(0) @binding(0) var<storage, read_write> particles: array<GpuParticle>;
+@group(0) @binding(1) var<uniform> params: Params;
+
+var<workgroup> partial_forces: array<vec2<f32>, WORKGROUP_SIZE>;
+
+@compute @workgroup_size(64)
+fn cs_main(
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32,
+) {
+    // uniform across the workgroup, so every invocation still reaches the barriers.
+    let i = workgroup_id.x;
+    let in_range = i < params.count;
+
+    var force = vec2<f32>(0.0, 0.0);
+    if in_range {
+        let p = particles[i];
+        for (var j = local_index; j < params.count; j += WORKGROUP_SIZE) {
+            if j == i {
+                continue;
+            }
+            let other = particles[j];
+            let delta = other.pos - p.pos;
+            let dist_sq = dot(delta, delta) + params.softening * params.softening;
+            // G * m1 * m2 / r², along delta / r.
+            force += delta * (params.g * p.mass * other.mass / (dist_sq * sqrt(dist_sq)));
+        }
+    }
+    partial_forces[local_index] = force;
+    workgroupBarrier();
+
+    // tree reduction, halving the active invocations each step.
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if local_index < stride {
+            partial_forces[local_index] += partial_forces[local_index + stride];
+        }
+        workgroupBarrier();
+    }
+
+    if in_range && local_index == 0u {
+        particles[i].force = partial_forces[0];
+    }
+}
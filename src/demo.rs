@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+
+use winit::keyboard::KeyCode;
+
+use crate::utils::vec2::{vec2, Vec2, WindowSpace};
+
+// Predefined scenes a frontend can load, e.g. from a demo script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioPreset {
+    SolarSystem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DemoEvent {
+    MouseClick(Vec2<f64, WindowSpace>),
+    KeyPress(KeyCode),
+    LoadScenario(ScenarioPreset),
+    // sent through Frontend::explode_at, each frontend binds explosions differently (if at all).
+    Explosion(Vec2<f64, WindowSpace>),
+}
+
+// Inputs replayed at a time after the demo starts, sorted by time.
+#[derive(Debug, Clone, Default)]
+pub struct DemoScript {
+    pub events: Vec<(Duration, DemoEvent)>,
+}
+
+impl DemoScript {
+    pub fn solar_system_showcase() -> Self {
+        let secs = Duration::from_secs_f64;
+        Self {
+            events: vec![
+                (
+                    secs(0.0),
+                    DemoEvent::LoadScenario(ScenarioPreset::SolarSystem),
+                ),
+                (secs(5.0), DemoEvent::KeyPress(KeyCode::Equal)), // zoom in
+                (secs(10.0), DemoEvent::KeyPress(KeyCode::KeyT)), // trails, if supported
+                // clicks are staggered, only one press is registered per frame.
+                (secs(15.0), DemoEvent::MouseClick(vec2(200.0, 150.0))),
+                (secs(15.2), DemoEvent::MouseClick(vec2(600.0, 150.0))),
+                (secs(15.4), DemoEvent::MouseClick(vec2(400.0, 450.0))),
+                (secs(20.0), DemoEvent::Explosion(vec2(400.0, 300.0))),
+                (secs(25.0), DemoEvent::KeyPress(KeyCode::Minus)), // zoom out
+                (secs(30.0), DemoEvent::KeyPress(KeyCode::KeyR)),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DemoPlayback {
+    script: DemoScript,
+    start: Instant,
+    next: usize,
+}
+
+impl DemoPlayback {
+    // Events that have come due since the last call.
+    pub fn due_events(&mut self) -> &[(Duration, DemoEvent)] {
+        let elapsed = self.start.elapsed();
+        let first = self.next;
+        while self
+            .script
+            .events
+            .get(self.next)
+            .is_some_and(|(time, _)| *time <= elapsed)
+        {
+            self.next += 1;
+        }
+        &self.script.events[first..self.next]
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.script.events.len()
+    }
+
+    pub fn new(script: DemoScript) -> Self {
+        Self {
+            script,
+            start: Instant::now(),
+            next: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn showcase_events_are_in_order() {
+        let script = DemoScript::solar_system_showcase();
+        let times: Vec<_> = script
+            .events
+            .iter()
+            .map(|(time, _)| time.as_secs_f64())
+            .collect();
+        assert!(times.windows(2).all(|pair| pair[0] <= pair[1]), "{times:?}");
+
+        let at = |secs: f64| {
+            script
+                .events
+                .iter()
+                .find(|(time, _)| time.as_secs_f64() == secs)
+                .map(|(_, event)| *event)
+        };
+        assert_eq!(
+            at(0.0),
+            Some(DemoEvent::LoadScenario(ScenarioPreset::SolarSystem))
+        );
+        assert_eq!(at(5.0), Some(DemoEvent::KeyPress(KeyCode::Equal)));
+        assert_eq!(at(10.0), Some(DemoEvent::KeyPress(KeyCode::KeyT)));
+        assert!(matches!(at(20.0), Some(DemoEvent::Explosion(_))));
+        let clicks = script
+            .events
+            .iter()
+            .filter(|(_, event)| matches!(event, DemoEvent::MouseClick(_)))
+            .count();
+        assert_eq!(clicks, 3);
+    }
+
+    #[test]
+    fn playback_only_returns_due_events() {
+        let mut playback = DemoPlayback::new(DemoScript::solar_system_showcase());
+        // only the t = 0 scenario load is due straight away.
+        assert_eq!(playback.due_events().len(), 1);
+        assert!(playback.due_events().is_empty());
+        assert!(!playback.is_finished());
+    }
+}
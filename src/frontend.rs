@@ -1,9 +1,17 @@
-use crate::utils::{
-    input_data::InputData,
-    vec2::{TextureSpace, Vec2, WindowSpace},
+#[cfg(feature = "compute")]
+use crate::backend::GpuParticle;
+use crate::{
+    demo::ScenarioPreset,
+    utils::{
+        aabb::Aabb,
+        colour::Rgba,
+        input_data::InputData,
+        vec2::{TextureSpace, Vec2, WindowSpace},
+    },
 };
 use educe::Educe;
 use std::time::Duration;
+use winit::keyboard::KeyCode;
 
 #[derive(Educe)]
 #[educe(Debug)]
@@ -11,17 +19,85 @@ pub struct TextureData<'a> {
     #[educe(Debug(ignore))]
     pub texture_buffer: &'a [u8],
     pub texture_size: Vec2<u32, TextureSpace>,
+    // Inclusive box of texels changed since the last frame, None uploads the whole texture.
+    // An inverted box (min > max) means nothing changed. Frontends leave this None,
+    // App fills it from Frontend::take_dirty_region once per rendered frame.
+    pub dirty_region: Option<Aabb<u32, TextureSpace>>,
+}
+
+// N-body particles for Backend::compute_forces, each force is written back in place.
+#[cfg(feature = "compute")]
+pub struct GpuParticleData<'a> {
+    pub particles: &'a mut [GpuParticle],
+    pub g: f32,
+    pub softening: f32, // world units, force uses r² + softening²
+}
+
+// Frame time statistics over the last FRAME_TIME_WINDOW frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTimingStats {
+    pub current: Duration,
+    pub avg: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub sim_speed: f32, // what the durations were scaled by, shown in the frontends' HUDs.
+}
+
+impl FrameTimingStats {
+    pub fn fps(&self) -> f64 {
+        1.0 / self.avg.as_secs_f64().max(f64::EPSILON)
+    }
+
+    // Scales every duration by the simulation speed multiplier.
+    pub fn with_sim_speed(self, sim_speed: f32) -> Self {
+        Self {
+            current: self.current.mul_f32(sim_speed),
+            avg: self.avg.mul_f32(sim_speed),
+            min: self.min.mul_f32(sim_speed),
+            max: self.max.mul_f32(sim_speed),
+            sim_speed,
+        }
+    }
 }
 
 // Public facing methods
 pub trait Frontend {
     fn get_texture_data(&self) -> TextureData;
+    // Texels changed since the last call, called once per rendered frame. None uploads everything.
+    fn take_dirty_region(&mut self) -> Option<Aabb<u32, TextureSpace>> {
+        None
+    }
     fn get_texture_scale(&self) -> u32;
 
     fn resize_texture(&mut self, window_size: Vec2<u32, WindowSpace>);
     fn rescale_texture(&mut self, scale: u32);
 
-    fn update(&mut self, inputs: &mut InputData, avg_frame_time: Duration);
+    // Input handling & non-physics state, called once per rendered frame.
+    fn update(&mut self, inputs: &mut InputData, frame_timing: FrameTimingStats);
+    // Advances the simulation by a fixed timestep, may be called 0..N times per frame.
+    fn physics_step(&mut self, dt: Duration);
+    // Renders between the previous & current physics state, alpha in [0, 1).
+    fn render_interpolated(&mut self, alpha: f64);
+
+    // Replaces the current scene with a predefined one.
+    fn load_scenario(&mut self, preset: ScenarioPreset);
+    // Scripted full strength explosion, frontends without explosions ignore it.
+    fn explode_at(&mut self, _pos: Vec2<f64, WindowSpace>) {}
+
+    // Plain text state dump for the inspector window, one item per line.
+    fn inspector_text(&self) -> String;
+
+    // Render pass clear colour, None preserves the previous frame's contents (e.g. for trails).
+    fn clear_colour(&self) -> Option<Rgba>;
+
+    // Particles whose gravity the gpu sums before each physics step, None stays on the CPU path.
+    #[cfg(feature = "compute")]
+    fn gpu_particles(&mut self) -> Option<GpuParticleData<'_>> {
+        None
+    }
+
+    // Key bindings shown in the help overlay, toggled on '?'.
+    fn help_text() -> &'static [(KeyCode, &'static str)];
 
     fn new(window_size: Vec2<u32, WindowSpace>, scale: u32) -> Self;
 }
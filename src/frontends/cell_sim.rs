@@ -1,51 +1,704 @@
-use std::{mem::transmute, time::Duration};
+use std::{
+    f32::consts::{SQRT_2, TAU},
+    fmt::Write,
+    fs, io,
+    mem::transmute,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
-    frontend::{FrameData, Frontend},
+    demo::ScenarioPreset,
+    frontend::{FrameTimingStats, Frontend, TextureData},
     utils::{
-        canvas::Shape,
+        aabb::Aabb,
         colour::Rgba,
-        consts::{DGRAY, GREEN, INIT_DRAW_SIZE, MAX_DRAW_SIZE, WHITE},
+        consts::{
+            GRAY, GREEN, INIT_DRAW_SIZE, MAX_COLOUR_AGE, MAX_DRAW_SIZE, PNG_EXPORT_PREFIX,
+            POP_HISTORY_LEN, RESET_DENSITY, RESET_SEED, RLE_PATH, SELECTION_COLOUR,
+            SELECTION_DASH_LEN, SPARKLINE_LEN, TARGET_FPS, WHITE,
+        },
         input_data::InputData,
-        vec2::{vec2, RenderSpace, Scale, ScreenSpace, Vec2},
+        sync_cell::SyncCell,
+        vec2::{vec2, TextureSpace, Vec2, WindowSpace},
+        world::Shape,
     },
 };
-use log::{info, trace};
-use winit::{dpi::Pixel, keyboard::KeyCode};
+use log::{error, info, trace, warn};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use winit::keyboard::KeyCode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Material {
     Dead,
     Alive,
+    Fire,
+    Steam,
+    // Wire World, Dead is the empty state.
+    Wire,
+    ElectronHead,
+    ElectronTail,
+    // Falls straight down, else slides diagonally, piling up.
+    Sand,
     Count,
 }
 
+// Everything the update rules & renderer need to know about a material,
+// adding a material only means adding its entry to MATERIAL_REGISTRY.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialProperties {
+    pub colour: Rgba,
+    pub density: f32,
+    pub falls: bool,
+    pub rises: bool,
+    pub spreads: bool,
+    pub flammable: bool,
+}
+
+// Indexed by `Material as usize`.
+pub static MATERIAL_REGISTRY: [MaterialProperties; Material::COUNT] = [
+    // Dead
+    MaterialProperties {
+        colour: GRAY,
+        density: 0.0,
+        falls: false,
+        rises: false,
+        spreads: false,
+        flammable: false,
+    },
+    // Alive
+    MaterialProperties {
+        colour: GREEN,
+        density: 1.0,
+        falls: false,
+        rises: false,
+        spreads: false,
+        flammable: true,
+    },
+    // Fire
+    MaterialProperties {
+        colour: Rgba::from_rgb(255, 100, 20),
+        density: 0.1,
+        falls: false,
+        rises: true,
+        spreads: true,
+        flammable: false,
+    },
+    // Steam
+    MaterialProperties {
+        colour: Rgba::from_rgb(200, 200, 220),
+        density: 0.05,
+        falls: false,
+        rises: true,
+        spreads: true,
+        flammable: false,
+    },
+    // Wire
+    MaterialProperties {
+        colour: Rgba::from_rgb(200, 120, 40),
+        density: 1.0,
+        falls: false,
+        rises: false,
+        spreads: false,
+        flammable: false,
+    },
+    // ElectronHead
+    MaterialProperties {
+        colour: Rgba::from_rgb(80, 160, 255),
+        density: 1.0,
+        falls: false,
+        rises: false,
+        spreads: false,
+        flammable: false,
+    },
+    // ElectronTail
+    MaterialProperties {
+        colour: Rgba::from_rgb(255, 80, 40),
+        density: 1.0,
+        falls: false,
+        rises: false,
+        spreads: false,
+        flammable: false,
+    },
+    // Sand
+    MaterialProperties {
+        colour: Rgba::from_rgb(220, 190, 110),
+        density: 1.6,
+        falls: true,
+        rises: false,
+        spreads: false,
+        flammable: false,
+    },
+];
+
 impl Material {
-    pub const fn get_rgb(self) -> Rgba {
+    pub const COUNT: usize = Self::Count as usize;
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::Dead,
+        Self::Alive,
+        Self::Fire,
+        Self::Steam,
+        Self::Wire,
+        Self::ElectronHead,
+        Self::ElectronTail,
+        Self::Sand,
+    ];
+
+    pub fn properties(self) -> &'static MaterialProperties {
+        &MATERIAL_REGISTRY[self as usize]
+    }
+
+    pub fn get_rgb(self) -> Rgba {
+        self.properties().colour
+    }
+
+    // Inverse of get_rgb, None for a colour no material uses. Alpha is ignored.
+    pub fn from_rgb(rgb: [u8; 3]) -> Option<Self> {
+        Self::ALL.into_iter().find(|mat| {
+            let colour = mat.get_rgb();
+            [colour.r, colour.g, colour.b] == rgb
+        })
+    }
+
+    // Run-length encoded (RLE) pattern format, 'b' (dead) & 'o' (alive) are standard,
+    // 's' (sand) is our own extension.
+    pub const fn rle_char(self) -> char {
         match self {
-            Self::Dead => DGRAY,
-            Self::Alive => GREEN,
+            Self::Alive => 'o',
+            Self::Sand => 's',
+            // the rest aren't saved.
+            Self::Dead
+            | Self::Fire
+            | Self::Steam
+            | Self::Wire
+            | Self::ElectronHead
+            | Self::ElectronTail => 'b',
             Self::Count => panic!("Material::Count"),
         }
     }
+
+    pub const fn from_rle_char(c: char) -> Option<Self> {
+        match c {
+            'b' => Some(Self::Dead),
+            'o' => Some(Self::Alive),
+            's' => Some(Self::Sand),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Cell {
     mat: Material,
     updated: bool,
+    age: u16, // generations survived while Alive, 0 when born or for any other material
     mat_to: Material,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleSet {
+    // Bit k set == a cell with k live neighbours is born / survives.
+    Deterministic {
+        born: u16,
+        survive: u16,
+    },
+    // [k] == probability a cell with k live neighbours is born / survives.
+    Stochastic {
+        born_prob: [f32; 9],
+        survive_prob: [f32; 9],
+    },
+    // Never changes a cell, e.g. for frozen displays.
+    Static,
+}
+
+impl RuleSet {
+    pub const CONWAY: Self = Self::Deterministic {
+        born: 1 << 3,
+        survive: 1 << 2 | 1 << 3,
+    };
+    // Conway, but births & survivals occasionally fail.
+    pub const NOISY_CONWAY: Self = Self::Stochastic {
+        born_prob: [0.0, 0.0, 0.0, 0.9, 0.0, 0.0, 0.0, 0.0, 0.0],
+        survive_prob: [0.0, 0.0, 0.95, 0.95, 0.0, 0.0, 0.0, 0.0, 0.0],
+    };
+    pub const HIGH_LIFE: Self = Self::Deterministic {
+        born: 1 << 3 | 1 << 6,
+        survive: 1 << 2 | 1 << 3,
+    };
+    pub const DAY_AND_NIGHT: Self = Self::Deterministic {
+        born: 1 << 3 | 1 << 6 | 1 << 7 | 1 << 8,
+        survive: 1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8,
+    };
+    pub const SEEDS: Self = Self::Deterministic {
+        born: 1 << 2,
+        survive: 0,
+    };
+    pub const REPLICATOR: Self = Self::Deterministic {
+        born: 1 << 1 | 1 << 3 | 1 << 5 | 1 << 7,
+        survive: 1 << 1 | 1 << 3 | 1 << 5 | 1 << 7,
+    };
+    pub const NAMED_RULES: [(&'static str, Self); 5] = [
+        ("Conway", Self::CONWAY),
+        ("HighLife", Self::HIGH_LIFE),
+        ("DayAndNight", Self::DAY_AND_NIGHT),
+        ("Seeds", Self::SEEDS),
+        ("Replicator", Self::REPLICATOR),
+    ];
+    // Cycled through on KeyN.
+    pub const PRESETS: [Self; 7] = [
+        Self::CONWAY,
+        Self::HIGH_LIFE,
+        Self::DAY_AND_NIGHT,
+        Self::SEEDS,
+        Self::REPLICATOR,
+        Self::NOISY_CONWAY,
+        Self::Static,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::PRESETS.iter().position(|rule| *rule == self);
+        index.map_or(Self::CONWAY, |i| {
+            Self::PRESETS[(i + 1) % Self::PRESETS.len()]
+        })
+    }
+
+    pub fn name(self) -> Option<&'static str> {
+        Self::NAMED_RULES
+            .iter()
+            .find(|(_, rule)| *rule == self)
+            .map(|(name, _)| *name)
+    }
+
+    // B/S notation, e.g. "B3/S23" for Conway or "B2/S" for Seeds, case insensitive.
+    pub fn from_bs_notation(s: &str) -> Result<Self, RuleParseError> {
+        let (born, survive) = s
+            .trim()
+            .split_once('/')
+            .ok_or(RuleParseError::MissingSlash)?;
+        let digits = |part: &str, prefix: char| {
+            let mut chars = part.chars();
+            if !chars
+                .next()
+                .is_some_and(|c| c.eq_ignore_ascii_case(&prefix))
+            {
+                return Err(RuleParseError::MissingPrefix(prefix));
+            }
+            chars.try_fold(0u16, |mask, c| match c.to_digit(10) {
+                Some(n) if n <= 8 => Ok(mask | 1 << n),
+                _ => Err(RuleParseError::InvalidNeighbourCount(c)),
+            })
+        };
+        Ok(Self::Deterministic {
+            born: digits(born, 'B')?,
+            survive: digits(survive, 'S')?,
+        })
+    }
+
+    // Inverse of from_bs_notation, None for stochastic rules. Static survives on any count.
+    pub fn to_bs_notation(self) -> Option<String> {
+        let (born, survive) = match self {
+            Self::Deterministic { born, survive } => (born, survive),
+            Self::Static => (0, 0x1ff),
+            Self::Stochastic { .. } => return None,
+        };
+        let digits = |mask: u16| {
+            (0..=8)
+                .filter(|&n| (mask >> n) & 1 == 1)
+                .filter_map(|n| char::from_digit(n, 10))
+                .collect::<String>()
+        };
+        Some(format!("B{}/S{}", digits(born), digits(survive)))
+    }
+
+    // None if the cell doesn't change, or isn't a game of life cell (Alive or Dead).
+    pub fn apply(self, mat: Material, neighbours: u32) -> Option<Material> {
+        let alive = match mat {
+            Material::Alive => true,
+            Material::Dead => false,
+            _ => return None,
+        };
+        let next_alive = match self {
+            Self::Deterministic { born, survive } => {
+                let rule = if alive { survive } else { born };
+                (rule >> neighbours) & 1 == 1
+            }
+            Self::Stochastic {
+                born_prob,
+                survive_prob,
+            } => {
+                let prob = if alive { survive_prob } else { born_prob };
+                rand::thread_rng().gen::<f32>() < prob[neighbours as usize]
+            }
+            Self::Static => alive,
+        };
+
+        match (alive, next_alive) {
+            (true, false) => Some(Material::Dead),
+            (false, true) => Some(Material::Alive),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleParseError {
+    MissingSlash,
+    MissingPrefix(char),         // 'B' or 'S'
+    InvalidNeighbourCount(char), // anything but 0-8
+}
+
+// Each mode has its own update rule & drawing material, cycled on Ctrl+W.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationMode {
+    GameOfLife,
+    // materials move by their falls/rises/spreads properties.
+    FallingSand,
+    WireWorld,
+}
+
+impl SimulationMode {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::GameOfLife => Self::FallingSand,
+            Self::FallingSand => Self::WireWorld,
+            Self::WireWorld => Self::GameOfLife,
+        }
+    }
+
+    // What the mouse draws with.
+    pub const fn draw_material(self) -> Material {
+        match self {
+            Self::GameOfLife => Material::Alive,
+            Self::FallingSand => Material::Steam,
+            Self::WireWorld => Material::Wire,
+        }
+    }
+}
+
+// Wire World layouts, '#' wire, 'H' electron head, 't' electron tail, anything else is empty.
+// Placed side by side on reset (KeyR) in SimulationMode::WireWorld.
+pub static WIRE_WORLD_PATTERNS: [(&str, &[&str]); 2] = [
+    // 6 cell loop, emits an electron down the wire every 6 generations.
+    ("clock 6", &[".tH.", "#..###########", ".##."]),
+    // 12 cell loop, half the frequency.
+    (
+        "clock 12",
+        &[".tH##.", "#.....#", "#.....############", ".#####."],
+    ),
+];
+
+// Named patterns, as live cell offsets from the pattern's centre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    Glider,
+    RPentomino,
+    Acorn,
+}
+
+impl Pattern {
+    pub const fn cells(self) -> &'static [(i32, i32)] {
+        match self {
+            Self::Glider => &[(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)],
+            Self::RPentomino => &[(0, -1), (1, -1), (-1, 0), (0, 0), (0, 1)],
+            Self::Acorn => &[(-2, -1), (0, 0), (-3, 1), (-2, 1), (1, 1), (2, 1), (3, 1)],
+        }
+    }
+
+    // Golly/LifeWiki RLE, every non dead cell as its offset from the pattern's top left.
+    // '#' comment & "x = .." header lines are skipped, everything after '!' is ignored.
+    // The one RLE parser, CellSim::import_rle reads its files through this too.
+    pub fn from_rle(s: &str) -> Result<Vec<((i32, i32), Material)>, RleParseError> {
+        let body = s.lines().filter(|line| {
+            let line = line.trim_start();
+            !line.starts_with('#') && !line.starts_with('x')
+        });
+
+        let mut cells = vec![];
+        let (mut x, mut y) = (0, 0);
+        let mut run: Option<i32> = None;
+        for c in body.flat_map(str::chars) {
+            if let Some(digit) = c.to_digit(10) {
+                run = Some(run.unwrap_or(0) * 10 + digit as i32);
+                continue;
+            }
+            let count = run.take().unwrap_or(1);
+            match c {
+                '$' => {
+                    x = 0;
+                    y += count;
+                }
+                '!' => return Ok(cells),
+                c if c.is_whitespace() => {}
+                c => {
+                    let mat = Material::from_rle_char(c).ok_or(RleParseError::UnexpectedChar(c))?;
+                    if mat != Material::Dead {
+                        cells.extend((x..x + count).map(|x| ((x, y), mat)));
+                    }
+                    x += count;
+                }
+            }
+        }
+        Err(RleParseError::MissingTerminator)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RleParseError {
+    UnexpectedChar(char), // anything but digits, 'b', 'o', 's', '$' & '!'
+    MissingTerminator,    // no '!'
+}
+
+// (name, RLE), pasted with Pattern::from_rle.
+pub const BUILT_IN_PATTERNS: &[(&str, &str)] = &[
+    ("glider", "x = 3, y = 3, rule = B3/S23\nbob$2bo$3o!"),
+    ("blinker", "x = 3, y = 1, rule = B3/S23\n3o!"),
+    (
+        "lightweight spaceship",
+        "x = 5, y = 4, rule = B3/S23\nbo2bo$o4b$o3bo$4o!",
+    ),
+];
+
+// What reset_sim (KeyR) restores the grid to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitialPattern {
+    Blank,
+    // each cell is alive with this probability, seeded by RESET_SEED.
+    RandomDensity(f32),
+    // placed at the centre of the grid.
+    Pattern(Pattern),
+    // alive where perlin noise > 0.5, scale is noise periods per cell.
+    Noise { seed: u64, scale: f32 },
+}
+
+// Classic 2D perlin noise mapped to [0, 1], gradients are hashed from the lattice point & seed.
+fn perlin(x: f32, y: f32, seed: u64) -> f32 {
+    let gradient = |ix: i32, iy: i32| {
+        let mut h = seed
+            ^ (ix as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (iy as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+        h ^= h >> 33;
+        let angle = (h as f32 / u64::MAX as f32) * TAU;
+        (angle.cos(), angle.sin())
+    };
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let dot = |ix: i32, iy: i32| {
+        let (gx, gy) = gradient(x0 as i32 + ix, y0 as i32 + iy);
+        gx * (fx - ix as f32) + gy * (fy - iy as f32)
+    };
+    let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let (u, v) = (fade(fx), fade(fy));
+    let noise = lerp(
+        lerp(dot(0, 0), dot(1, 0), u),
+        lerp(dot(0, 1), dot(1, 1), u),
+        v,
+    );
+    // 2D perlin lies within +/- sqrt(0.5).
+    ((noise * SQRT_2 + 1.0) / 2.0).clamp(0.0, 1.0)
+}
+
+// (min, max) of the rect spanned by two corners, in any order.
+fn rect_from_corners(
+    a: Vec2<i32, TextureSpace>,
+    b: Vec2<i32, TextureSpace>,
+) -> (Vec2<i32, TextureSpace>, Vec2<i32, TextureSpace>) {
+    (
+        vec2(a.x.min(b.x), a.y.min(b.y)),
+        vec2(a.x.max(b.x), a.y.max(b.y)),
+    )
+}
+
+// Cells are stored relative to the selection's top left.
+type Clipboard = Vec<(Vec2<i32, TextureSpace>, Material)>;
+
+#[derive(Debug, Clone, Default)]
+enum SelectionMode {
+    #[default]
+    None,
+    // Ctrl+LeftDrag in progress.
+    Selecting {
+        start: Vec2<i32, TextureSpace>,
+    },
+    // rect is (min, max), both inclusive.
+    Selected {
+        rect: (Vec2<i32, TextureSpace>, Vec2<i32, TextureSpace>),
+        clipboard: Clipboard,
+    },
+    // Clipboard follows the cursor, a click places it.
+    Pasting {
+        clipboard: Clipboard,
+    },
+}
+
+// What a left click does, KeyE toggles Erase & KeyF toggles Fill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawMode {
+    Place, // State::brush
+    Erase, // Material::Dead
+    Fill,  // flood fills the region under the cursor with State::brush
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridTopology {
+    Square,
+    // Odd-r offset layout, each cell is 2 pixels wide & odd rows are shoved right by 1 pixel.
+    Hexagonal,
+}
+
+// Odd-r offset neighbours, odd rows sit half a cell to the right of even rows.
+const fn hex_neighbours(x: i32, y: i32) -> [(i32, i32); 6] {
+    let shift = y & 1; // 1 on odd rows
+    [
+        (x - 1, y),
+        (x + 1, y),
+        (x - 1 + shift, y - 1),
+        (x + shift, y - 1),
+        (x - 1 + shift, y + 1),
+        (x + shift, y + 1),
+    ]
+}
+
+// Alive cell count of the last POP_HISTORY_LEN generations, oldest overwritten first.
+#[derive(Debug, Clone, Copy)]
+pub struct PopHistory {
+    counts: [u32; POP_HISTORY_LEN],
+    next: usize, // slot the next count is written to
+    len: usize,
+}
+
+impl Default for PopHistory {
+    fn default() -> Self {
+        Self {
+            counts: [0; POP_HISTORY_LEN],
+            next: 0,
+            len: 0,
+        }
+    }
+}
+
+impl PopHistory {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    pub fn record(&mut self, count: u32) {
+        self.counts[self.next] = count;
+        self.next = (self.next + 1) % POP_HISTORY_LEN;
+        self.len = (self.len + 1).min(POP_HISTORY_LEN);
+    }
+
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    // Oldest to newest, at most the last `n` counts.
+    fn recent(&self, n: usize) -> impl Iterator<Item = u32> + '_ {
+        let n = n.min(self.len);
+        let start = self.next + POP_HISTORY_LEN - n;
+        (start..start + n).map(|i| self.counts[i % POP_HISTORY_LEN])
+    }
+
+    pub fn current_count(&self) -> Option<u32> {
+        self.recent(1).next()
+    }
+
+    pub fn peak(&self) -> Option<u32> {
+        self.recent(self.len).max()
+    }
+
+    pub fn min(&self) -> Option<u32> {
+        self.recent(self.len).min()
+    }
+
+    // The last SPARKLINE_LEN counts, scaled between their own min & max.
+    pub fn sparkline(&self) -> String {
+        let lo = self.recent(SPARKLINE_LEN).min().unwrap_or(0);
+        let hi = self.recent(SPARKLINE_LEN).max().unwrap_or(0);
+        let range = (hi - lo).max(1) as usize;
+        let top = Self::BLOCKS.len() - 1;
+        self.recent(SPARKLINE_LEN)
+            .map(|count| Self::BLOCKS[(count - lo) as usize * top / range])
+            .collect()
+    }
+}
+
+// Texels written since the dirty region was last taken, inverted (min > max) when clean.
+#[derive(Debug, Clone, Copy)]
+struct DirtyRegion {
+    dirty_min: Vec2<i32, TextureSpace>,
+    dirty_max: Vec2<i32, TextureSpace>,
+    // e.g. after a resize, the whole buffer is new.
+    full_upload_requested: bool,
+}
+
+impl DirtyRegion {
+    fn clean() -> Self {
+        Self {
+            dirty_min: vec2(i32::MAX, i32::MAX),
+            dirty_max: vec2(0, 0),
+            full_upload_requested: false,
+        }
+    }
+
+    // min & max inclusive texel coordinates.
+    fn extend(&mut self, min: Vec2<i32, TextureSpace>, max: Vec2<i32, TextureSpace>) {
+        self.dirty_min = vec2(self.dirty_min.x.min(min.x), self.dirty_min.y.min(min.y));
+        self.dirty_max = vec2(self.dirty_max.x.max(max.x), self.dirty_max.y.max(max.y));
+    }
+
+    // None uploads everything.
+    fn to_aabb(self) -> Option<Aabb<u32, TextureSpace>> {
+        if self.full_upload_requested {
+            return None;
+        }
+        Some(Aabb::new(
+            self.dirty_min.cast().cast_unit(),
+            self.dirty_max.cast().cast_unit(),
+        ))
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct State {
     frame: usize,
     draw_size: i32,
     draw_shape: Shape,
-    scale: Scale<i32, ScreenSpace, RenderSpace>,
+    texture_scale: u32,
     running: bool,
     step_sim: bool,
-    mouse: Vec2<f64, ScreenSpace>,
+    mouse: Vec2<f64, WindowSpace>,
+    rule_set: RuleSet,
+    topology: GridTopology,
+    mode: SimulationMode,
+    wrap: bool,       // toroidal edges in update_gol, toggled on KeyW
+    age_colour: bool, // alive cells fade GREEN -> WHITE with age, toggled on KeyA
+    draw_mode: DrawMode,
+    brush: Material, // SimulationMode::draw_material on Digit1, Sand on Digit2
+}
+
+impl State {
+    fn screen_to_cell(&self, pos: Vec2<f64, WindowSpace>) -> Vec2<i32, TextureSpace> {
+        let pixel = pos.to_texture_space(self.texture_scale).cast::<i32>();
+        match self.topology {
+            GridTopology::Square => pixel,
+            GridTopology::Hexagonal => vec2((pixel.x - (pixel.y & 1)).div_euclid(2), pixel.y),
+        }
+    }
+}
+
+// Number of cells that fit in a texture, hex cells are 2 pixels wide plus the odd row shove.
+fn grid_size(
+    texture_size: Vec2<i32, TextureSpace>,
+    topology: GridTopology,
+) -> Vec2<i32, TextureSpace> {
+    match topology {
+        GridTopology::Square => texture_size,
+        GridTopology::Hexagonal => vec2((texture_size.x - 1) / 2, texture_size.y),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -53,32 +706,47 @@ pub struct CellSim {
     state: State,
     prev_state: State,
 
-    window_size: Vec2<i32, ScreenSpace>,
-    sim_size: Vec2<i32, RenderSpace>,
-    sim_buf: Vec<Cell>,
+    window_size: Vec2<u32, WindowSpace>,
+    sim_size: Vec2<i32, TextureSpace>,
+    texture_size: Vec2<i32, TextureSpace>, // == sim_size for GridTopology::Square
+    sim_buf: Vec<SyncCell<Cell>>,
     buf: Vec<u8>, // TODO(TOM): swap this out for a [u8] buffer.
+    selection: SelectionMode,
+    // cells the selection overlay drew over last frame, restored before redrawing.
+    selection_overlay: Vec<Vec2<i32, TextureSpace>>,
+    init_pattern: InitialPattern,
+    pop_history: PopHistory,
+    // taken by take_dirty_region, so each frame only uploads what changed since the last.
+    dirty: SyncCell<DirtyRegion>,
 }
 
 impl Frontend for CellSim {
     // region: Utility
-    fn get_frame_data(&self) -> FrameData<'_> {
-        FrameData {
-            buf: &self.buf,
-            size: self.sim_size.cast(),
-            frame: self.state.frame,
+    fn get_texture_data(&self) -> TextureData<'_> {
+        TextureData {
+            texture_buffer: &self.buf,
+            texture_size: self.texture_size.cast(),
+            dirty_region: None,
         }
     }
 
-    fn get_scale(&self) -> u32 {
-        self.state.scale.get() as u32
+    fn take_dirty_region(&mut self) -> Option<Aabb<u32, TextureSpace>> {
+        std::mem::replace(self.dirty.get_mut(), DirtyRegion::clean()).to_aabb()
+    }
+
+    fn get_texture_scale(&self) -> u32 {
+        self.state.texture_scale
     }
     // endregion
     // region: Sim Manipulation
     // TODO(TOM): resize from the centre of the screen, not the top left || from mouse with scroll wheel.
-    fn resize_sim(&mut self, window_size: Vec2<u32, ScreenSpace>) {
-        let window_size = window_size.cast();
-        let new_sim_size = window_size.scale(self.state.scale);
-        if new_sim_size == self.sim_size {
+    fn resize_texture(&mut self, window_size: Vec2<u32, WindowSpace>) {
+        self.window_size = window_size;
+        let texture_size = window_size
+            .to_texture_space(self.state.texture_scale)
+            .cast::<i32>();
+        let new_sim_size = grid_size(texture_size, self.state.topology);
+        if new_sim_size == self.sim_size && texture_size == self.texture_size {
             info!("Sim size unchanged, skipping resize. {new_sim_size:?}");
             return;
         }
@@ -86,7 +754,7 @@ impl Frontend for CellSim {
         let cell_count = (new_sim_size.x * new_sim_size.y) as usize;
         trace!(
             "Resizing sim to: {new_sim_size:?} | {window_size:?} | scale: {} | {cell_count}",
-            self.state.scale.get()
+            self.state.texture_scale
         );
 
         // TODO(TOM): if current buffer is big enough, map cells inline << custom slice required.
@@ -96,81 +764,187 @@ impl Frontend for CellSim {
                 // if the coordinate is within the existing sim_space then copy the cell
                 // otherwise create a new dead cell.
                 if x >= self.sim_size.x || y >= self.sim_size.y {
-                    new_sim_buf.push(Cell {
+                    new_sim_buf.push(SyncCell::new(Cell {
                         mat: Material::Dead,
                         updated: false,
+                        age: 0,
                         mat_to: Material::Dead,
-                    });
+                    }));
                 } else {
-                    new_sim_buf.push(self.sim_buf[self.get_index(vec2(x, y))]);
+                    new_sim_buf.push(self.sim_buf[self.get_index(vec2(x, y))].clone());
                 }
             }
         }
 
-        self.window_size = window_size;
         self.sim_size = new_sim_size;
+        self.texture_size = texture_size;
         self.sim_buf = new_sim_buf;
-        self.buf = vec![44; cell_count * 4];
-        for y in 0..self.sim_size.y {
-            for x in 0..self.sim_size.x {
-                self.update_rgba(vec2(x, y), self.get_cell(vec2(x, y)).mat);
-            }
-        }
+        self.buf = vec![44; (texture_size.x * texture_size.y * 4) as usize];
+        self.dirty.get_mut().full_upload_requested = true;
+        self.redraw_cells();
     }
 
-    fn rescale_sim(&mut self, scale: u32) {
-        let scale = Scale::new(scale as i32);
-        if self.state.scale == scale {
+    fn rescale_texture(&mut self, scale: u32) {
+        if self.state.texture_scale == scale {
             info!("Sim scale unchanged, skipping rescale. {scale:?}");
             return;
         }
-        info!("New scale: {:?} | {:?}", scale.get(), self.window_size);
-        self.state.scale = scale;
-        self.resize_sim(self.window_size.cast());
+        info!("New scale: {scale:?} | {:?}", self.window_size);
+        self.state.texture_scale = scale;
+        self.resize_texture(self.window_size);
     }
     // endregion
     // region: update
-    fn update(&mut self, inputs: &mut InputData, _avg_frame_time: Duration) {
+    fn update(&mut self, inputs: &mut InputData, _frame_timing: FrameTimingStats) {
         self.handle_input_state(inputs);
+        // drawn cells show straight away, even while paused.
+        self.apply_updated_cells();
 
-        if self.state.running || self.state.step_sim {
-            self.update_gol();
+        // paused, nothing new has been recorded since the last log.
+        if self.state.running && self.state.frame % TARGET_FPS as usize == 0 {
+            if let Some(count) = self.pop_history.current_count() {
+                info!("Population: {count} {}", self.pop_history.sparkline());
+            }
         }
+        self.state.frame += 1;
+    }
 
-        for y in 1..self.sim_size.y - 1 {
-            for x in 1..self.sim_size.x - 1 {
-                let cell = self.get_cell(vec2(x, y));
-                if cell.updated {
-                    self.update_cell(vec2(x, y), cell.mat_to);
-                }
-            }
+    // One generation per step, cells have no motion to interpolate.
+    fn physics_step(&mut self, _dt: Duration) {
+        if !(self.state.running || self.state.step_sim) {
+            return;
         }
+        self.state.step_sim = false;
 
-        // TODO(TOM): this will work for cellular automata (ish), but not for particles
-        // particles
-        //     .par_iter()
-        //     .zip(texture_buf.par_chunks_exact_mut(4))
-        //     .filter(|(p, c)| {
-        //         p.pos.x >= 0.0
-        //             && p.pos.x < (sim_size.width - 1) as f64
-        //             && p.pos.y >= 0.0
-        //             && p.pos.y < (sim_size.height - 1) as f64
-        //     })
-        //     .for_each(|(p, c)| {
-        //         c[0] = WHITE.r;
-        //         c[1] = WHITE.g;
-        //         c[2] = WHITE.b;
-        //         c[3] = WHITE.a;
-        //     });
+        match self.state.mode {
+            SimulationMode::GameOfLife => self.update_gol(),
+            SimulationMode::FallingSand => self.update_falling_sand(),
+            SimulationMode::WireWorld => self.update_wire_world(),
+        }
+        self.apply_updated_cells();
+    }
 
+    fn render_interpolated(&mut self, _alpha: f64) {
         self.clear_last_mouse_outline(WHITE);
+        self.clear_selection_overlay();
+        self.render_selection_overlay();
         self.render_mouse_outline(WHITE);
-
         self.prev_state = self.state;
-        self.state.step_sim = false;
-        self.state.frame += 1;
     }
     // endregion
+
+    fn load_scenario(&mut self, preset: ScenarioPreset) {
+        warn!("{preset:?} has no cell sim equivalent, resetting instead");
+        self.reset_sim();
+    }
+
+    fn inspector_text(&self) -> String {
+        let mut text = format!(
+            "Mode: {:?} | Rule set: {:?} | Running: {}\n",
+            self.state.mode, self.state.rule_set, self.state.running
+        );
+        writeln!(
+            text,
+            "Grid: {:?} {:?} | Wrap: {}",
+            self.state.topology, self.sim_size, self.state.wrap
+        )
+        .unwrap();
+        if let Some(count) = self.pop_history.current_count() {
+            writeln!(text, "Population: {count} {}", self.pop_history.sparkline()).unwrap();
+        }
+        text
+    }
+
+    fn clear_colour(&self) -> Option<Rgba> {
+        Some(GRAY)
+    }
+
+    fn help_text() -> &'static [(KeyCode, &'static str)] {
+        &[
+            (KeyCode::Space, "Toggle simulation"),
+            (KeyCode::ArrowRight, "Step simulation"),
+            (KeyCode::ArrowUp, "Larger brush"),
+            (KeyCode::ArrowDown, "Smaller brush"),
+            (KeyCode::Tab, "Cycle brush shape"),
+            (KeyCode::Digit1, "Draw the mode's material"),
+            (KeyCode::Digit2, "Draw sand"),
+            (KeyCode::KeyE, "Toggle erasing"),
+            (KeyCode::KeyF, "Toggle flood fill"),
+            (KeyCode::KeyA, "Toggle age colouring"),
+            (KeyCode::KeyW, "Toggle wrapping edges"),
+            (KeyCode::KeyN, "Cycle rule set"),
+            (KeyCode::KeyP, "Paste a glider"),
+            (KeyCode::KeyC, "Clear cells"),
+            (KeyCode::KeyR, "Reset cells"),
+            (KeyCode::F2, "Save png"),
+            (KeyCode::F3, "Load newest png"),
+            (KeyCode::KeyS, "Ctrl+S save rle, Ctrl+L load it"),
+            (KeyCode::ControlLeft, "+Drag select, +C/X/V copy/cut/paste"),
+            (KeyCode::ControlLeft, "+T toggle hex grid, +W cycle mode"),
+        ]
+    }
+
+    fn new(window_size: Vec2<u32, WindowSpace>, scale: u32) -> Self {
+        assert!(window_size.x > 0 && window_size.y > 0 && scale > 0);
+
+        let sim_size = window_size.to_texture_space(scale).cast::<i32>();
+        let cell_count = (sim_size.x * sim_size.y) as usize;
+
+        let sim_buf = vec![
+            SyncCell::new(Cell {
+                mat: Material::Dead,
+                updated: false,
+                age: 0,
+                mat_to: Material::Alive,
+            });
+            cell_count
+        ];
+        let mut buf = Vec::with_capacity(cell_count * 4);
+        for cell in &sim_buf {
+            let rgb = cell.get().mat.get_rgb();
+            buf.push(rgb.r);
+            buf.push(rgb.g);
+            buf.push(rgb.b);
+            buf.push(255);
+        }
+        info!("Sim rgba buf len: {}", buf.len());
+
+        let state = State {
+            frame: 0,
+            draw_shape: Shape::CircleFill,
+            draw_size: INIT_DRAW_SIZE,
+            running: false,
+            step_sim: false,
+            texture_scale: scale,
+            mouse: vec2(0.0, 0.0),
+            rule_set: RuleSet::CONWAY,
+            topology: GridTopology::Square,
+            mode: SimulationMode::GameOfLife,
+            wrap: false,
+            age_colour: false,
+            draw_mode: DrawMode::Place,
+            brush: SimulationMode::GameOfLife.draw_material(),
+        };
+
+        Self {
+            state,
+            prev_state: state,
+
+            window_size,
+            sim_size,
+            texture_size: sim_size,
+            sim_buf,
+            buf,
+            selection: SelectionMode::None,
+            selection_overlay: vec![],
+            init_pattern: InitialPattern::RandomDensity(RESET_DENSITY),
+            pop_history: PopHistory::default(),
+            dirty: SyncCell::new(DirtyRegion {
+                full_upload_requested: true,
+                ..DirtyRegion::clean()
+            }),
+        }
+    }
 }
 
 impl CellSim {
@@ -178,84 +952,300 @@ impl CellSim {
     // TODO(TOM): adjacent  using an index, not Pos<T>
 
     #[inline]
-    const fn get_index(&self, pos: Vec2<i32, RenderSpace>) -> usize {
+    const fn get_index(&self, pos: Vec2<i32, TextureSpace>) -> usize {
         (pos.y * self.sim_size.x + pos.x) as usize
     }
 
+    // The cell's first (leftmost) texel.
+    fn get_texel(&self, pos: Vec2<i32, TextureSpace>) -> Vec2<i32, TextureSpace> {
+        let texel = self.get_index_texture(pos) as i32 / 4;
+        vec2(texel % self.texture_size.x, texel / self.texture_size.x)
+    }
+
     #[inline]
-    const fn get_index_texture(&self, pos: Vec2<i32, RenderSpace>) -> usize {
-        4 * (pos.y * self.sim_size.x + pos.x) as usize
+    const fn get_index_texture(&self, pos: Vec2<i32, TextureSpace>) -> usize {
+        // index of the cell's first (leftmost) pixel.
+        let x = match self.state.topology {
+            GridTopology::Square => pos.x,
+            GridTopology::Hexagonal => 2 * pos.x + (pos.y & 1),
+        };
+        4 * (pos.y * self.texture_size.x + x) as usize
     }
 
     #[inline]
-    fn get_cell(&self, pos: Vec2<i32, RenderSpace>) -> &Cell {
+    fn get_cell(&self, pos: Vec2<i32, TextureSpace>) -> &Cell {
         assert!(!self.out_of_bounds(pos));
         let index = self.get_index(pos);
-        &self.sim_buf[index]
+        self.sim_buf[index].get()
     }
 
     #[inline]
-    fn get_cell_mut(&mut self, pos: Vec2<i32, RenderSpace>) -> &mut Cell {
+    fn get_cell_mut(&mut self, pos: Vec2<i32, TextureSpace>) -> &mut Cell {
         assert!(!self.out_of_bounds(pos));
         let index = self.get_index(pos);
-        &mut self.sim_buf[index]
+        self.sim_buf[index].get_mut()
+    }
+
+    // Out of bounds reads as a dead cell, so neighbour lookups never need bounds checks.
+    #[inline]
+    fn get_cell_safe(&self, pos: Vec2<i32, TextureSpace>) -> Cell {
+        if self.out_of_bounds(pos) {
+            return Cell {
+                mat: Material::Dead,
+                updated: false,
+                age: 0,
+                mat_to: Material::Dead,
+            };
+        }
+        *self.sim_buf[self.get_index(pos)].get()
+    }
+
+    #[inline]
+    fn get_cell_mut_safe(&mut self, pos: Vec2<i32, TextureSpace>) -> Option<&mut Cell> {
+        if self.out_of_bounds(pos) {
+            return None;
+        }
+        let index = self.get_index(pos);
+        Some(self.sim_buf[index].get_mut())
     }
 
     #[inline]
-    fn update_cell(&mut self, pos: Vec2<i32, RenderSpace>, mat: Material) {
+    fn update_cell(&mut self, pos: Vec2<i32, TextureSpace>, mat: Material) {
         let cell = self.get_cell_mut(pos);
+        if cell.mat != mat {
+            cell.age = 0;
+        }
         cell.mat = mat;
         cell.updated = false;
         self.update_rgba(pos, mat);
     }
 
     #[inline]
-    fn update_rgba(&mut self, pos: Vec2<i32, RenderSpace>, mat: Material) {
-        let rgba = mat.get_rgb();
+    fn update_rgba(&mut self, pos: Vec2<i32, TextureSpace>, mat: Material) {
+        let rgba = if self.state.age_colour && mat == Material::Alive {
+            Self::age_colour(self.get_cell(pos).age)
+        } else {
+            mat.get_rgb()
+        };
         let index = self.get_index_texture(pos);
-        self.buf[index + 0] = rgba.r;
-        self.buf[index + 1] = rgba.g;
-        self.buf[index + 2] = rgba.b;
-    }
+        let pixels = match self.state.topology {
+            GridTopology::Square => 1,
+            GridTopology::Hexagonal => 2,
+        };
+        for pixel in self.buf[index..index + 4 * pixels].chunks_exact_mut(4) {
+            pixel[0] = rgba.r;
+            pixel[1] = rgba.g;
+            pixel[2] = rgba.b;
+        }
 
-    const fn out_of_bounds(&self, pos: Vec2<i32, RenderSpace>) -> bool {
-        pos.x >= self.sim_size.x || pos.y >= self.sim_size.y
+        let min = self.get_texel(pos);
+        self.dirty
+            .get_mut()
+            .extend(min, min + vec2(pixels as i32 - 1, 0));
     }
 
-    fn reset_sim(&mut self) {
-        todo!("cell_sim::reset_sim")
+    // GREEN when born, linearly to WHITE at MAX_COLOUR_AGE generations.
+    fn age_colour(age: u16) -> Rgba {
+        let t = f32::from(age.min(MAX_COLOUR_AGE)) / f32::from(MAX_COLOUR_AGE);
+        GREEN.lerp(WHITE, t)
     }
 
-    fn clear_sim(&mut self) {
+    // Commits the mat_to of every updated cell, from drawing or a generation step.
+    fn apply_updated_cells(&mut self) {
         for y in 0..self.sim_size.y {
             for x in 0..self.sim_size.x {
-                self.update_cell(vec2(x, y), Material::Dead);
+                let cell = self.get_cell(vec2(x, y));
+                if cell.updated {
+                    self.update_cell(vec2(x, y), cell.mat_to);
+                }
             }
         }
     }
-    // endregion
-    // region: Drawing
-    fn draw_pressed(&mut self, pos: Vec2<f64, ScreenSpace>) {
-        // draw is already bounded by the window size, so no need to check bounds here.
-        let cell = pos.scale(self.state.scale);
-
-        self.state
-            .draw_shape
-            .draw(self.state.draw_size, |off_x: i32, off_y: i32| {
-                let mut off_pos = cell.cast::<i32>() + vec2(off_x, off_y);
-                off_pos = off_pos.clamp(vec2(0, 0), self.sim_size - 1);
 
-                let cell = self.get_cell_mut(off_pos);
-                cell.updated = true;
-                cell.mat_to = Material::Alive;
-            });
+    // Repaints every cell, e.g. after the colouring changes.
+    fn redraw_cells(&mut self) {
+        for y in 0..self.sim_size.y {
+            for x in 0..self.sim_size.x {
+                let mat = self.get_cell(vec2(x, y)).mat;
+                self.update_rgba(vec2(x, y), mat);
+            }
+        }
     }
 
-    fn draw_held(&mut self, pos: Vec2<f64, ScreenSpace>) {
-        self.draw_pressed(pos);
+    const fn out_of_bounds(&self, pos: Vec2<i32, TextureSpace>) -> bool {
+        pos.x < 0 || pos.y < 0 || pos.x >= self.sim_size.x || pos.y >= self.sim_size.y
     }
 
-    fn draw_released(&mut self, pressed: Vec2<f64, ScreenSpace>, released: Vec2<f64, ScreenSpace>) {
+    fn reset_sim(&mut self) {
+        self.clear_sim();
+        self.pop_history.clear();
+        if self.state.mode == SimulationMode::WireWorld {
+            self.place_wire_world_patterns();
+            return;
+        }
+        match self.init_pattern {
+            InitialPattern::Blank => {}
+            InitialPattern::RandomDensity(density) => {
+                let mut rng = SmallRng::seed_from_u64(RESET_SEED);
+                for y in 0..self.sim_size.y {
+                    for x in 0..self.sim_size.x {
+                        if rng.gen::<f32>() < density {
+                            self.update_cell(vec2(x, y), Material::Alive);
+                        }
+                    }
+                }
+            }
+            InitialPattern::Pattern(pattern) => {
+                let centre = self.sim_size / 2;
+                for &(x, y) in pattern.cells() {
+                    let pos = centre + vec2(x, y);
+                    if !self.out_of_bounds(pos) {
+                        self.update_cell(pos, Material::Alive);
+                    }
+                }
+            }
+            InitialPattern::Noise { seed, scale } => {
+                for y in 0..self.sim_size.y {
+                    for x in 0..self.sim_size.x {
+                        if perlin(x as f32 * scale, y as f32 * scale, seed) > 0.5 {
+                            self.update_cell(vec2(x, y), Material::Alive);
+                        }
+                    }
+                }
+            }
+        }
+        info!("Reset sim to {:?}", self.init_pattern);
+    }
+
+    // WIRE_WORLD_PATTERNS left to right, vertically centred.
+    fn place_wire_world_patterns(&mut self) {
+        let mut origin = vec2(2, self.sim_size.y / 2);
+        for (name, rows) in &WIRE_WORLD_PATTERNS {
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    let mat = match c {
+                        '#' => Material::Wire,
+                        'H' => Material::ElectronHead,
+                        't' => Material::ElectronTail,
+                        _ => continue,
+                    };
+                    let pos = origin + vec2(x as i32, y as i32);
+                    if !self.out_of_bounds(pos) {
+                        self.update_cell(pos, mat);
+                    }
+                }
+            }
+            let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+            origin.x += width as i32 + 2;
+            trace!("Placed wire world pattern '{name}'");
+        }
+        info!("Reset sim to WIRE_WORLD_PATTERNS");
+    }
+
+    pub fn set_init_pattern(&mut self, init_pattern: InitialPattern) {
+        self.init_pattern = init_pattern;
+    }
+
+    // Cell layouts don't map between topologies, so this starts from a cleared grid.
+    fn set_topology(&mut self, topology: GridTopology) {
+        self.state.topology = topology;
+        self.selection = SelectionMode::None;
+        self.selection_overlay.clear();
+
+        self.sim_size = grid_size(self.texture_size, topology);
+        let cell_count = (self.sim_size.x * self.sim_size.y) as usize;
+        self.sim_buf = vec![
+            SyncCell::new(Cell {
+                mat: Material::Dead,
+                updated: false,
+                age: 0,
+                mat_to: Material::Dead,
+            });
+            cell_count
+        ];
+        self.buf = vec![44; (self.texture_size.x * self.texture_size.y * 4) as usize];
+        self.dirty.get_mut().full_upload_requested = true;
+        self.clear_sim();
+        info!("Grid topology: {topology:?} | {:?} cells", self.sim_size);
+    }
+
+    fn clear_sim(&mut self) {
+        for y in 0..self.sim_size.y {
+            for x in 0..self.sim_size.x {
+                self.update_cell(vec2(x, y), Material::Dead);
+            }
+        }
+    }
+    // endregion
+    // region: Drawing
+    fn draw_pressed(&mut self, pos: Vec2<f64, WindowSpace>) {
+        // draw is already bounded by the window size, so no need to check bounds here.
+        let cell = self.state.screen_to_cell(pos);
+        let mat = match self.state.draw_mode {
+            DrawMode::Place => self.state.brush,
+            DrawMode::Erase => Material::Dead,
+            DrawMode::Fill => {
+                let cell = cell.clamp(vec2(0, 0), self.sim_size - 1);
+                let target = self.get_cell(cell).mat;
+                self.flood_fill(cell, target, self.state.brush);
+                return;
+            }
+        };
+
+        self.state
+            .draw_shape
+            .draw(self.state.draw_size, |off_x: i32, off_y: i32| {
+                let mut off_pos = cell + vec2(off_x, off_y);
+                off_pos = off_pos.clamp(vec2(0, 0), self.sim_size - 1);
+
+                let cell = self.get_cell_mut(off_pos);
+                cell.updated = true;
+                cell.mat_to = mat;
+            });
+    }
+
+    fn draw_held(&mut self, pos: Vec2<f64, WindowSpace>) {
+        // filling once per click is enough.
+        if self.state.draw_mode != DrawMode::Fill {
+            self.draw_pressed(pos);
+        }
+    }
+
+    // Replaces the region of target_mat connected to pos, edge neighbours only.
+    pub fn flood_fill(
+        &mut self,
+        pos: Vec2<i32, TextureSpace>,
+        target_mat: Material,
+        fill_mat: Material,
+    ) {
+        if target_mat == fill_mat {
+            return;
+        }
+
+        let mut worklist = vec![pos];
+        while let Some(pos) = worklist.pop() {
+            if self.out_of_bounds(pos) || self.get_cell(pos).mat != target_mat {
+                continue;
+            }
+            // filled cells no longer match target_mat, so each is only visited once.
+            self.update_cell(pos, fill_mat);
+            match self.state.topology {
+                GridTopology::Square => worklist.extend([
+                    pos + vec2(1, 0),
+                    pos - vec2(1, 0),
+                    pos + vec2(0, 1),
+                    pos - vec2(0, 1),
+                ]),
+                GridTopology::Hexagonal => worklist.extend(
+                    hex_neighbours(pos.x, pos.y)
+                        .into_iter()
+                        .map(|(x, y)| vec2(x, y)),
+                ),
+            }
+        }
+    }
+
+    fn draw_released(&mut self, pressed: Vec2<f64, WindowSpace>, released: Vec2<f64, WindowSpace>) {
         trace!("not used.");
     }
     // endregion
@@ -270,11 +1260,20 @@ impl CellSim {
         // }
 
         assert!(
-            (inputs.was_mouse_held() && inputs.was_mouse_pressed()) == false,
+            !(inputs.was_mouse_held() && inputs.was_mouse_pressed()),
             "Mouse state error {inputs:#?}"
         );
 
-        if inputs.is_mouse_held() {
+        let ctrl_held = inputs.is_held(KeyCode::ControlLeft);
+        if ctrl_held
+            || !matches!(
+                self.selection,
+                SelectionMode::None | SelectionMode::Selected { .. }
+            )
+        {
+            // selecting & pasting take over the mouse from drawing.
+            self.handle_selection_inputs(inputs, ctrl_held);
+        } else if inputs.is_mouse_held() {
             // TODO(TOM): draw indicator arrow for direction of particle.
             self.draw_held(self.state.mouse);
         } else if inputs.was_mouse_pressed() {
@@ -287,10 +1286,84 @@ impl CellSim {
             self.state.running = !self.state.running;
             info!("Sim running: {}", self.state.running);
         }
-        self.state.step_sim = inputs.is_pressed(KeyCode::ArrowRight) && !self.state.running;
+        // kept until the next physics_step.
+        self.state.step_sim |= inputs.is_pressed(KeyCode::ArrowRight) && !self.state.running;
+
+        // Toggle grid topology on Ctrl+T
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyT) {
+            let topology = match self.state.topology {
+                GridTopology::Square => GridTopology::Hexagonal,
+                GridTopology::Hexagonal => GridTopology::Square,
+            };
+            self.set_topology(topology);
+        }
+
+        // Toggle erasing on KeyE, flood filling on KeyF
+        if inputs.is_pressed(KeyCode::KeyE) || inputs.is_pressed(KeyCode::KeyF) {
+            let mode = if inputs.is_pressed(KeyCode::KeyE) {
+                DrawMode::Erase
+            } else {
+                DrawMode::Fill
+            };
+            self.state.draw_mode = if self.state.draw_mode == mode {
+                DrawMode::Place
+            } else {
+                mode
+            };
+            info!("Draw mode: {:?}", self.state.draw_mode);
+        }
+
+        // Toggle age colouring on KeyA (Ctrl+A locks the aspect ratio)
+        if !ctrl_held && inputs.is_pressed(KeyCode::KeyA) {
+            self.state.age_colour = !self.state.age_colour;
+            self.redraw_cells();
+            info!("Age colouring: {}", self.state.age_colour);
+        }
+
+        // Toggle wrap around edges on KeyW
+        if !inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyW) {
+            self.state.wrap = !self.state.wrap;
+            info!("Wrap mode: {}", self.state.wrap);
+        }
+
+        // Cycle simulation mode on Ctrl+W, cells are kept but may mean nothing to the new rules.
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyW) {
+            self.state.mode = self.state.mode.next();
+            self.state.brush = self.state.mode.draw_material();
+            info!("Simulation mode: {:?}", self.state.mode);
+        }
+
+        // Pick the brush on Digit1/2 (Ctrl+Digit sets the sim speed)
+        if !ctrl_held && (inputs.is_pressed(KeyCode::Digit1) || inputs.is_pressed(KeyCode::Digit2))
+        {
+            self.state.brush = if inputs.is_pressed(KeyCode::Digit2) {
+                Material::Sand
+            } else {
+                self.state.mode.draw_material()
+            };
+            info!("Brush: {:?}", self.state.brush);
+        }
+
+        // Cycle rule set on KeyN
+        if inputs.is_pressed(KeyCode::KeyN) {
+            self.state.rule_set = self.state.rule_set.next();
+            match self.state.rule_set.name() {
+                Some(name) => info!("Rule set: {name}"),
+                None => info!("Rule set: {:?}", self.state.rule_set),
+            }
+        }
 
-        // Clear Sim on KeyC
-        if inputs.is_pressed(KeyCode::KeyC) {
+        // Paste a glider at the mouse on KeyP
+        if inputs.is_pressed(KeyCode::KeyP) {
+            let (name, rle) = BUILT_IN_PATTERNS[0];
+            match Pattern::from_rle(rle) {
+                Ok(cells) => self.paste_cells(&cells, self.mouse_cell()),
+                Err(err) => warn!("Failed to parse {name}: {err:?}"),
+            }
+        }
+
+        // Clear Sim on KeyC (Ctrl+C copies)
+        if inputs.is_pressed(KeyCode::KeyC) && !ctrl_held {
             self.clear_sim();
         } else if inputs.is_pressed(KeyCode::KeyR) {
             self.reset_sim();
@@ -303,48 +1376,187 @@ impl CellSim {
 
         // Cycle shape on Tab
         if inputs.is_pressed(KeyCode::Tab) {
-            unsafe {
-                let shape = transmute::<u8, Shape>((self.state.draw_shape as u8 + 1) % 3 as u8);
-                self.state.draw_shape = shape;
-            }
+            self.state.draw_shape = self.state.draw_shape.next();
         }
+
+        self.handle_png_inputs(inputs);
+        self.handle_rle_inputs(inputs);
     }
     // endregion
     // region: Update
     // TODO(TOM): convert to a delta checker/updater (check all alive cells and their neighbours)
     fn update_gol(&mut self) {
-        for y in 1..self.sim_size.y - 1 {
-            for x in 1..self.sim_size.x - 1 {
-                let mut neighbours: u32 = 0;
-                if x == 0 || y == 0 || x == self.sim_size.x - 1 || y == self.sim_size.y - 1 {
-                    return;
-                }
+        let rule_set = self.state.rule_set;
+        let age_colour = self.state.age_colour;
+        let width = self.sim_size.x;
+        // Double buffered, every cell's neighbours are counted before any cell is written.
+        // SAFETY: this pass only reads sim_buf through SyncCell::get, from every thread at once,
+        // & nothing writes to it until collect has returned.
+        let neighbours: Vec<u32> = (0..self.sim_buf.len() as i32)
+            .into_par_iter()
+            .map(|i| self.alive_neighbours(vec2(i % width, i / width)))
+            .collect();
 
-                neighbours += (self.get_cell(vec2(x - 1, y - 1)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x, y - 1)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x + 1, y - 1)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x - 1, y)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x + 1, y)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x - 1, y + 1)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x, y + 1)).mat == Material::Alive) as u32;
-                neighbours += (self.get_cell(vec2(x + 1, y + 1)).mat == Material::Alive) as u32;
-
-                let origin_pos = vec2(x, y);
-                let c = self.get_cell_mut(origin_pos);
-                if c.mat == Material::Alive && neighbours != 2 && neighbours != 3 {
-                    c.mat_to = Material::Dead;
-                    c.updated = true;
-                } else if c.mat == Material::Dead && neighbours == 3 {
-                    c.mat_to = Material::Alive;
+        // SAFETY: par_iter_mut hands each cell to exactly one task, which only writes that
+        // cell's own mat_to, updated & age, so no two threads touch the same cell.
+        let alive = self
+            .sim_buf
+            .par_iter_mut()
+            .zip(neighbours)
+            .map(|(c, neighbours)| {
+                let c = c.get_mut();
+                if let Some(mat_to) = rule_set.apply(c.mat, neighbours) {
+                    c.mat_to = mat_to;
                     c.updated = true;
+                } else if c.mat == Material::Alive {
+                    // survived, repainted while the colour is still changing.
+                    c.age = c.age.saturating_add(1);
+                    if age_colour && c.age <= MAX_COLOUR_AGE {
+                        c.mat_to = c.mat;
+                        c.updated = true;
+                    }
                 }
+
+                let mat = if c.updated { c.mat_to } else { c.mat };
+                u32::from(mat == Material::Alive)
+            })
+            .sum();
+        self.pop_history.record(alive);
+    }
+
+    // Off the grid reads as dead through get_cell_safe, or as the opposite edge when wrapping.
+    fn alive_neighbours(&self, pos: Vec2<i32, TextureSpace>) -> u32 {
+        let is_alive = |(x, y): (i32, i32)| {
+            let pos = if self.state.wrap {
+                vec2(x.rem_euclid(self.sim_size.x), y.rem_euclid(self.sim_size.y))
+            } else {
+                vec2(x, y)
+            };
+            u32::from(self.get_cell_safe(pos).mat == Material::Alive)
+        };
+        match self.state.topology {
+            GridTopology::Square => (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (pos.x + dx, pos.y + dy)))
+                .filter(|&neighbour| neighbour != (pos.x, pos.y))
+                .map(is_alive)
+                .sum(),
+            GridTopology::Hexagonal => hex_neighbours(pos.x, pos.y).into_iter().map(is_alive).sum(),
+        }
+    }
+
+    // Same double buffering as update_gol: Empty stays, Head -> Tail, Tail -> Wire,
+    // Wire -> Head with exactly 1 or 2 Head neighbours.
+    fn update_wire_world(&mut self) {
+        let sim_size = self.sim_size;
+        let topology = self.state.topology;
+        let prev: Vec<Material> = self.sim_buf.iter().map(|c| c.get().mat).collect();
+        // only called on interior cells, so neighbours are never out of bounds.
+        let is_head =
+            |x: i32, y: i32| (prev[(y * sim_size.x + x) as usize] == Material::ElectronHead) as u32;
+
+        self.sim_buf
+            .par_chunks_mut(sim_size.x as usize)
+            .enumerate()
+            .skip(1)
+            .take((sim_size.y - 2).max(0) as usize)
+            .flat_map(|(y, row)| {
+                row[1..(sim_size.x - 1).max(1) as usize]
+                    .par_iter_mut()
+                    .enumerate()
+                    .map(move |(x, c)| (x as i32 + 1, y as i32, c))
+            })
+            .for_each(|(x, y, c)| {
+                let c = c.get_mut();
+                let mat_to = match c.mat {
+                    Material::ElectronHead => Material::ElectronTail,
+                    Material::ElectronTail => Material::Wire,
+                    Material::Wire => {
+                        let heads: u32 = match topology {
+                            GridTopology::Square => (-1..=1)
+                                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                                .filter(|&offset| offset != (0, 0))
+                                .map(|(dx, dy)| is_head(x + dx, y + dy))
+                                .sum(),
+                            GridTopology::Hexagonal => hex_neighbours(x, y)
+                                .into_iter()
+                                .map(|(x, y)| is_head(x, y))
+                                .sum(),
+                        };
+                        match heads {
+                            1 | 2 => Material::ElectronHead,
+                            _ => return,
+                        }
+                    }
+                    _ => return,
+                };
+                c.mat_to = mat_to;
+                c.updated = true;
+            });
+    }
+
+    // One cell per step, falling materials move down & rising ones up, spreading ones try
+    // diagonally then sideways. Falling rows are visited bottom to top & rising rows top to
+    // bottom, so a moved cell is never visited again & a column moves down as one.
+    fn update_falling_sand(&mut self) {
+        let mut rng = rand::thread_rng();
+        let rows = 1..self.sim_size.y - 1;
+        for y in rows.clone().rev() {
+            self.move_sand_row(y, true, &mut rng);
+        }
+        for y in rows {
+            self.move_sand_row(y, false, &mut rng);
+        }
+    }
+
+    // Sequential, a cell that has been moved into can't be claimed twice.
+    fn move_sand_row(&mut self, y: i32, falling: bool, rng: &mut impl Rng) {
+        for x in 1..self.sim_size.x - 1 {
+            let pos = vec2(x, y);
+            let cell = *self.get_cell(pos);
+            let props = cell.mat.properties();
+            let moves = if falling { props.falls } else { props.rises };
+            if cell.updated || !moves {
+                continue;
+            }
+
+            let dy = if falling { 1 } else { -1 };
+            let side = if rng.gen::<bool>() { 1 } else { -1 };
+            // straight, then diagonally, then sideways if it spreads.
+            let targets = [
+                vec2(x, y + dy),
+                vec2(x + side, y + dy),
+                vec2(x - side, y + dy),
+                vec2(x + side, y),
+                vec2(x - side, y),
+            ];
+            let reach = if props.spreads { 5 } else { 3 };
+
+            let free = targets[..reach].iter().copied().find(|&target| {
+                // a cell vacated earlier this step is free again.
+                let target = self.get_cell_safe(target);
+                let mat = if target.updated {
+                    target.mat_to
+                } else {
+                    target.mat
+                };
+                mat == Material::Dead
+            });
+            if let Some(target) = free {
+                // border cells aren't moved themselves, so sand piles up on the bottom row.
+                if let Some(target) = self.get_cell_mut_safe(target) {
+                    target.mat_to = cell.mat;
+                    target.updated = true;
+                }
+                let cell = self.get_cell_mut(pos);
+                cell.mat_to = Material::Dead;
+                cell.updated = true;
             }
         }
     }
 
     fn render_mouse_outline(&mut self, colour: Rgba) {
         optick::event!("Rendering Mouse Outline");
-        let mouse = self.state.mouse.scale(self.state.scale);
+        let mouse = self.state.screen_to_cell(self.state.mouse);
 
         self.state
             .draw_shape
@@ -352,30 +1564,38 @@ impl CellSim {
                 // avoids u32 underflow
                 // let x = (mouse.x as i32 + off_x).clamp(0, self.sim_size.width - 1);
                 // let y = (mouse.y as i32 + off_y).clamp(0, self.sim_size.height - 1);
-                let mut pos = mouse.cast::<i32>() + vec2(off_x, off_y);
+                let mut pos = mouse + vec2(off_x, off_y);
                 pos = pos.clamp(vec2(0, 0), self.sim_size - 1);
 
-                let index = 4 * (pos.y * self.sim_size.x + pos.x) as usize;
+                let index = self.get_index_texture(pos);
 
                 self.buf[index + 0] = colour.r;
                 self.buf[index + 1] = colour.g;
                 self.buf[index + 2] = colour.b;
                 self.buf[index + 3] = colour.a;
             });
+
+        // the brush bounds, rather than per texel, the closure already borrows self.
+        let reach = vec2(self.state.draw_size, self.state.draw_size);
+        let sim_max = self.sim_size - 1;
+        let min = self.get_texel((mouse - reach).clamp(vec2(0, 0), sim_max));
+        let max = self.get_texel((mouse + reach).clamp(vec2(0, 0), sim_max));
+        // hex cells are 2 texels wide & odd rows are shoved right.
+        self.dirty.get_mut().extend(min, max + vec2(2, 0));
     }
 
     fn clear_last_mouse_outline(&mut self, colour: Rgba) {
         optick::event!("Clearing Mouse Outline");
-        let mouse = self.prev_state.mouse.scale(self.prev_state.scale);
+        let mouse = self.prev_state.screen_to_cell(self.prev_state.mouse);
 
         self.prev_state
             .draw_shape
             .draw(self.prev_state.draw_size, |off_x: i32, off_y: i32| {
                 // avoids u32 underflow
-                let mut pos = mouse.cast::<i32>() + vec2(off_x, off_y);
+                let mut pos = mouse + vec2(off_x, off_y);
                 pos = pos.clamp(vec2(0, 0), self.sim_size - 1);
 
-                let index = 4 * (pos.y * self.sim_size.x + pos.x) as usize;
+                let index = self.get_index_texture(pos);
                 if self.buf[index + 0] == colour.r
                     && self.buf[index + 1] == colour.g
                     && self.buf[index + 2] == colour.b
@@ -389,51 +1609,852 @@ impl CellSim {
     }
 
     // endregion
-    pub fn new(window: Vec2<u32, ScreenSpace>, scale: u32) -> Self {
-        let scale = Scale::new(scale as i32);
-        let window = window.cast::<i32>();
+    // region: Selection
+    fn handle_selection_inputs(&mut self, inputs: &InputData, ctrl_held: bool) {
+        let mouse = self.mouse_cell();
+
+        match &self.selection {
+            SelectionMode::Pasting { .. } => {
+                if inputs.was_mouse_pressed() {
+                    self.paste_at(mouse);
+                    self.selection = SelectionMode::None;
+                }
+                return;
+            }
+            SelectionMode::Selecting { start } => {
+                if inputs.mouse_released.state {
+                    let rect = rect_from_corners(*start, mouse);
+                    self.selection = SelectionMode::Selected {
+                        rect,
+                        clipboard: vec![],
+                    };
+                    self.copy_region();
+                }
+                return;
+            }
+            SelectionMode::None | SelectionMode::Selected { .. } => {}
+        }
 
-        assert!(window.x > 0 && window.y > 0 && scale.get() > 0);
+        if !ctrl_held {
+            return;
+        }
+        if inputs.is_mouse_pressed() {
+            self.selection = SelectionMode::Selecting { start: mouse };
+        } else if inputs.is_pressed(KeyCode::KeyC) {
+            self.copy_region();
+        } else if inputs.is_pressed(KeyCode::KeyX) {
+            self.cut_region();
+        } else if inputs.is_pressed(KeyCode::KeyV) {
+            if let SelectionMode::Selected { clipboard, .. } = &self.selection {
+                self.selection = SelectionMode::Pasting {
+                    clipboard: clipboard.clone(),
+                };
+            }
+        }
+    }
 
-        let sim_size = window.scale(scale);
-        let cell_count = (sim_size.x * sim_size.y) as usize;
+    // Sets the cells alive, offset by top_left & clipped to the grid.
+    pub fn paste_pattern(&mut self, cells: &[(i32, i32)], top_left: Vec2<i32, TextureSpace>) {
+        for &(x, y) in cells {
+            let pos = top_left + vec2(x, y);
+            if !self.out_of_bounds(pos) {
+                self.update_cell(pos, Material::Alive);
+            }
+        }
+    }
 
-        let sim_buf = vec![
-            Cell {
-                mat: Material::Dead,
-                updated: false,
-                mat_to: Material::Alive,
+    // Pattern::from_rle's cells, offset by top_left & clipped to the grid.
+    pub fn paste_cells(
+        &mut self,
+        cells: &[((i32, i32), Material)],
+        top_left: Vec2<i32, TextureSpace>,
+    ) {
+        for &((x, y), mat) in cells {
+            let pos = top_left + vec2(x, y);
+            if !self.out_of_bounds(pos) {
+                self.update_cell(pos, mat);
+            }
+        }
+    }
+
+    fn mouse_cell(&self) -> Vec2<i32, TextureSpace> {
+        let mouse = self.state.screen_to_cell(self.state.mouse);
+        mouse.clamp(vec2(0, 0), self.sim_size - 1)
+    }
+
+    // Snapshots the selected rect into the clipboard.
+    pub fn copy_region(&mut self) {
+        let SelectionMode::Selected {
+            rect: (min, max), ..
+        } = self.selection
+        else {
+            return;
+        };
+
+        let mut copied = vec![];
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let mat = self.get_cell_safe(vec2(x, y)).mat;
+                copied.push((vec2(x, y) - min, mat));
+            }
+        }
+        info!("Copied {}x{} region", max.x - min.x + 1, max.y - min.y + 1);
+
+        if let SelectionMode::Selected { clipboard, .. } = &mut self.selection {
+            *clipboard = copied;
+        }
+    }
+
+    pub fn cut_region(&mut self) {
+        self.copy_region();
+        let SelectionMode::Selected {
+            rect: (min, max), ..
+        } = self.selection
+        else {
+            return;
+        };
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.update_cell(vec2(x, y), Material::Dead);
+            }
+        }
+    }
+
+    // Merges (ORs) the clipboard into the sim, origin is the clipboard's top left.
+    pub fn paste_at(&mut self, origin: Vec2<i32, TextureSpace>) {
+        let clipboard = match &self.selection {
+            SelectionMode::Selected { clipboard, .. } | SelectionMode::Pasting { clipboard } => {
+                clipboard.clone()
+            }
+            SelectionMode::None | SelectionMode::Selecting { .. } => return,
+        };
+
+        for (offset, mat) in clipboard {
+            let pos = origin + offset;
+            if mat != Material::Dead && !self.out_of_bounds(pos) {
+                self.update_cell(pos, mat);
+            }
+        }
+    }
+
+    fn clear_selection_overlay(&mut self) {
+        for pos in std::mem::take(&mut self.selection_overlay) {
+            if !self.out_of_bounds(pos) {
+                self.update_rgba(pos, self.get_cell(pos).mat);
+            }
+        }
+    }
+
+    fn render_selection_overlay(&mut self) {
+        optick::event!("Rendering Selection Overlay");
+
+        let mut overlay = std::mem::take(&mut self.selection_overlay);
+        let mut plot = |this: &mut Self, pos: Vec2<i32, TextureSpace>| {
+            if this.out_of_bounds(pos) {
+                return;
+            }
+            let index = this.get_index_texture(pos);
+            this.buf[index + 0] = SELECTION_COLOUR.r;
+            this.buf[index + 1] = SELECTION_COLOUR.g;
+            this.buf[index + 2] = SELECTION_COLOUR.b;
+            // only uploaded if it's in the dirty region.
+            let texel = this.get_texel(pos);
+            this.dirty.get_mut().extend(texel, texel);
+            overlay.push(pos);
+        };
+
+        let rect = match &self.selection {
+            SelectionMode::None => None,
+            SelectionMode::Selecting { start } => {
+                Some(rect_from_corners(*start, self.mouse_cell()))
+            }
+            SelectionMode::Selected { rect, .. } => Some(*rect),
+            SelectionMode::Pasting { clipboard } => {
+                let origin = self.mouse_cell();
+                let cells: Vec<_> = clipboard
+                    .iter()
+                    .filter(|(_, mat)| *mat != Material::Dead)
+                    .map(|(offset, _)| origin + *offset)
+                    .collect();
+                for pos in cells {
+                    plot(self, pos);
+                }
+                None
+            }
+        };
+
+        // Dashed outline, every other run of SELECTION_DASH_LEN cells.
+        if let Some((min, max)) = rect {
+            let is_dash = |n: i32| (n / SELECTION_DASH_LEN) % 2 == 0;
+            for x in min.x..=max.x {
+                if is_dash(x - min.x) {
+                    plot(self, vec2(x, min.y));
+                    plot(self, vec2(x, max.y));
+                }
+            }
+            for y in min.y..=max.y {
+                if is_dash(y - min.y) {
+                    plot(self, vec2(min.x, y));
+                    plot(self, vec2(max.x, y));
+                }
+            }
+        }
+
+        self.selection_overlay = overlay;
+    }
+    // endregion
+    // region: Save/Load
+    pub fn export_rle(&self, path: &Path) -> io::Result<()> {
+        let mut rle = format!("x = {}, y = {}", self.sim_size.x, self.sim_size.y);
+        // stochastic rules have no B/S notation, so load as Conway.
+        if let Some(rule) = self.state.rule_set.to_bs_notation() {
+            write!(rle, ", rule = {rule}").unwrap();
+        }
+        rle.push('\n');
+
+        for y in 0..self.sim_size.y {
+            let mut runs: Vec<(i32, char)> = Vec::new();
+            for x in 0..self.sim_size.x {
+                let c = self.get_cell(vec2(x, y)).mat.rle_char();
+                match runs.last_mut() {
+                    Some((count, last)) if *last == c => *count += 1,
+                    _ => runs.push((1, c)),
+                }
+            }
+            // trailing dead cells are implicit.
+            if runs
+                .last()
+                .is_some_and(|(_, c)| *c == Material::Dead.rle_char())
+            {
+                runs.pop();
+            }
+
+            for (count, c) in runs {
+                if count > 1 {
+                    write!(rle, "{count}").unwrap();
+                }
+                rle.push(c);
+            }
+            rle.push(if y == self.sim_size.y - 1 { '!' } else { '$' });
+        }
+        rle.push('\n');
+
+        fs::write(path, rle)
+    }
+
+    pub fn import_rle(path: &Path) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines().filter(|line| !line.starts_with('#'));
+        let header = lines
+            .next()
+            .ok_or_else(|| invalid("missing rle header".to_string()))?;
+
+        // header: "x = W, y = H, rule = B3/S23", rule is optional.
+        let mut size = vec2(0, 0);
+        let mut rule_set = RuleSet::CONWAY;
+        for field in header.split(',') {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
             };
-            cell_count
-        ];
-        let mut buf = Vec::with_capacity(cell_count * 4);
-        for cell in &sim_buf {
-            let rgb = cell.mat.get_rgb();
-            buf.push(rgb.r);
-            buf.push(rgb.g);
-            buf.push(rgb.b);
-            buf.push(255);
+            let parse = |value: &str| {
+                value
+                    .trim()
+                    .parse::<i32>()
+                    .map_err(|e| invalid(format!("bad rle header '{header}': {e}")))
+            };
+            match key.trim() {
+                "x" => size.x = parse(value)?,
+                "y" => size.y = parse(value)?,
+                "rule" => {
+                    rule_set = RuleSet::from_bs_notation(value)
+                        .map_err(|e| invalid(format!("bad rle rule '{value}': {e:?}")))?;
+                }
+                _ => {}
+            }
+        }
+        if size.x <= 0 || size.y <= 0 {
+            return Err(invalid(format!("bad rle size: {size:?}")));
         }
-        info!("Sim rgba buf len: {}", buf.len());
 
-        let state = State {
-            frame: 0,
-            draw_shape: Shape::CircleFill,
-            draw_size: INIT_DRAW_SIZE,
-            running: false,
-            step_sim: false,
-            scale,
-            mouse: vec2(0.0, 0.0),
+        let mut sim = Self::new(size.cast(), 1);
+        sim.state.rule_set = rule_set;
+        let cells =
+            Pattern::from_rle(&contents).map_err(|e| invalid(format!("bad rle body: {e:?}")))?;
+        // cells past the header's size are dropped.
+        sim.paste_cells(&cells, vec2(0, 0));
+
+        Ok(sim)
+    }
+
+    // One pixel per cell, regardless of topology.
+    pub fn export_png(&self, path: &Path) -> image::ImageResult<()> {
+        let size = self.sim_size.cast::<u32>();
+        image::RgbImage::from_fn(size.x, size.y, |x, y| {
+            let rgb = self.get_cell(vec2(x as i32, y as i32)).mat.get_rgb();
+            image::Rgb([rgb.r, rgb.g, rgb.b])
+        })
+        .save(path)
+    }
+
+    // Pixels are matched to each material's colour, anything else is Alive.
+    // The sim is resized to fit the image.
+    pub fn import_png(&mut self, path: &Path) -> image::ImageResult<()> {
+        let image = image::open(path)?.to_rgb8();
+        let size = vec2(image.width() as i32, image.height() as i32);
+        if size != self.sim_size {
+            let texture_size = match self.state.topology {
+                GridTopology::Square => size,
+                GridTopology::Hexagonal => vec2(size.x * 2 + 1, size.y),
+            };
+            self.resize_texture(
+                texture_size
+                    .to_window_space(self.state.texture_scale)
+                    .cast(),
+            );
+        }
+
+        self.clear_sim();
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let pos = vec2(x as i32, y as i32);
+            let mat = Material::from_rgb(pixel.0).unwrap_or(Material::Alive);
+            if mat != Material::Dead && !self.out_of_bounds(pos) {
+                self.update_cell(pos, mat);
+            }
+        }
+        Ok(())
+    }
+
+    // Save the grid to RLE_PATH on Ctrl+S, load it over the current grid on Ctrl+L
+    fn handle_rle_inputs(&mut self, inputs: &InputData) {
+        if !inputs.is_held(KeyCode::ControlLeft) {
+            return;
+        }
+        if inputs.is_pressed(KeyCode::KeyS) {
+            match self.export_rle(Path::new(RLE_PATH)) {
+                Ok(()) => info!("Saved sim to {RLE_PATH}"),
+                Err(e) => error!("Failed to save sim: {e}"),
+            }
+        } else if inputs.is_pressed(KeyCode::KeyL) {
+            match Self::import_rle(Path::new(RLE_PATH)) {
+                Ok(loaded) => {
+                    // the window & scale stay, a larger grid is cropped.
+                    self.clear_sim();
+                    let size = vec2(
+                        self.sim_size.x.min(loaded.sim_size.x),
+                        self.sim_size.y.min(loaded.sim_size.y),
+                    );
+                    for y in 0..size.y {
+                        for x in 0..size.x {
+                            self.update_cell(vec2(x, y), loaded.get_cell(vec2(x, y)).mat);
+                        }
+                    }
+                    info!("Loaded sim from {RLE_PATH}");
+                }
+                Err(e) => error!("Failed to load sim: {e}"),
+            }
+        }
+    }
+
+    // Save a timestamped png on F2, load the newest one on F3
+    fn handle_png_inputs(&mut self, inputs: &InputData) {
+        if inputs.is_pressed(KeyCode::F2) {
+            let secs = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            let path = format!("{PNG_EXPORT_PREFIX}{secs}.png");
+            match self.export_png(Path::new(&path)) {
+                Ok(()) => info!("Saved sim to {path}"),
+                Err(e) => error!("Failed to save sim: {e}"),
+            }
+        } else if inputs.is_pressed(KeyCode::F3) {
+            // same digit count until 2286, so the newest sorts last.
+            let newest = fs::read_dir(".").ok().and_then(|dir| {
+                dir.filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                    .filter(|name| name.starts_with(PNG_EXPORT_PREFIX) && name.ends_with(".png"))
+                    .max()
+            });
+            let Some(path) = newest else {
+                warn!("No {PNG_EXPORT_PREFIX}*.png to load");
+                return;
+            };
+            match self.import_png(Path::new(&path)) {
+                Ok(()) => info!("Loaded sim from {path}"),
+                Err(e) => error!("Failed to load sim: {e}"),
+            }
+        }
+    }
+    // endregion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alive_cells(sim: &CellSim) -> Vec<(i32, i32)> {
+        let mut cells = vec![];
+        for y in 0..sim.sim_size.y {
+            for x in 0..sim.sim_size.x {
+                if sim.get_cell(vec2(x, y)).mat == Material::Alive {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut sim = CellSim::new(vec2(8, 8), 1);
+        sim.paste_pattern(&[(0, 0), (1, 0), (2, 0)], vec2(3, 4));
+
+        sim.state.step_sim = true;
+        sim.physics_step(Duration::ZERO);
+        assert_eq!(alive_cells(&sim), [(4, 3), (4, 4), (4, 5)]);
+
+        sim.state.step_sim = true;
+        sim.physics_step(Duration::ZERO);
+        assert_eq!(alive_cells(&sim), [(3, 4), (4, 4), (5, 4)]);
+        assert_eq!(sim.pop_history.current_count(), Some(3));
+    }
+
+    #[test]
+    fn electron_travels_along_wire() {
+        let mut sim = CellSim::new(vec2(8, 5), 1);
+        sim.state.mode = SimulationMode::WireWorld;
+        sim.update_cell(vec2(1, 2), Material::ElectronHead);
+        for x in 2..7 {
+            sim.update_cell(vec2(x, 2), Material::Wire);
+        }
+
+        for head in 2..5 {
+            sim.state.step_sim = true;
+            sim.physics_step(Duration::ZERO);
+            assert_eq!(sim.get_cell(vec2(head, 2)).mat, Material::ElectronHead);
+            assert_eq!(sim.get_cell(vec2(head - 1, 2)).mat, Material::ElectronTail);
+            assert_eq!(sim.get_cell(vec2(head + 1, 2)).mat, Material::Wire);
+        }
+    }
+
+    #[test]
+    fn glider_wraps_around_edges() {
+        let mut sim = CellSim::new(vec2(8, 8), 1);
+        sim.state.wrap = true;
+        let (_, rle) = BUILT_IN_PATTERNS[0];
+        sim.paste_cells(&Pattern::from_rle(rle).unwrap(), vec2(0, 0));
+        let start = alive_cells(&sim);
+
+        // a glider moves one cell diagonally every 4 generations, 32 brings it back round.
+        for _ in 0..32 {
+            sim.state.step_sim = true;
+            sim.physics_step(Duration::ZERO);
+        }
+        assert_eq!(alive_cells(&sim), start);
+    }
+
+    #[test]
+    fn sand_column_falls() {
+        let mut sim = CellSim::new(vec2(10, 12), 1);
+        sim.clear_sim();
+        sim.state.mode = SimulationMode::FallingSand;
+        for y in 1..=3 {
+            sim.update_cell(vec2(5, y), Material::Sand);
+        }
+
+        for _ in 0..5 {
+            sim.state.step_sim = true;
+            sim.physics_step(Duration::ZERO);
+        }
+        for y in 0..12 {
+            let expected = if (6..=8).contains(&y) {
+                Material::Sand
+            } else {
+                Material::Dead
+            };
+            assert_eq!(sim.get_cell(vec2(5, y)).mat, expected, "y = {y}");
+        }
+    }
+
+    #[test]
+    fn rules_leave_other_materials_alone() {
+        // a dead cell with 3 live neighbours is born, sand isn't.
+        assert_eq!(
+            RuleSet::CONWAY.apply(Material::Dead, 3),
+            Some(Material::Alive)
+        );
+        assert_eq!(RuleSet::CONWAY.apply(Material::Sand, 3), None);
+        assert_eq!(RuleSet::CONWAY.apply(Material::Wire, 0), None);
+    }
+
+    #[test]
+    fn selection_outline_is_uploaded() {
+        let mut sim = CellSim::new(vec2(40, 30), 1);
+        sim.take_dirty_region();
+        sim.selection = SelectionMode::Selected {
+            rect: (vec2(5, 6), vec2(12, 9)),
+            clipboard: vec![],
         };
 
-        Self {
-            state,
-            prev_state: state,
+        sim.render_selection_overlay();
+        let region = sim.take_dirty_region().unwrap();
+        assert!(region.min.x <= 5 && region.min.y <= 6);
+        assert!(region.max.x >= 12 && region.max.y >= 9);
+    }
 
-            window_size: window,
-            sim_size,
-            sim_buf,
-            buf,
+    #[test]
+    fn pop_history_wraps_around() {
+        let mut history = PopHistory::default();
+        assert_eq!(history.current_count(), None);
+
+        // 200 > POP_HISTORY_LEN, so the buffer wraps. 0..=100 shuffled, 100 & 0 both come
+        // round again within the last POP_HISTORY_LEN.
+        for i in 0..200 {
+            let count = (i * 37) % 101;
+            history.record(count);
+            assert_eq!(history.current_count(), Some(count));
+        }
+        assert_eq!(history.peak(), Some(100));
+        assert_eq!(history.min(), Some(0));
+        assert_eq!(history.sparkline().chars().count(), SPARKLINE_LEN);
+    }
+
+    // Single threaded reference, every cell from a snapshot of the last generation.
+    fn sequential_step(sim: &CellSim) -> Vec<Material> {
+        let mut next = vec![];
+        for y in 0..sim.sim_size.y {
+            for x in 0..sim.sim_size.x {
+                let pos = vec2(x, y);
+                let mat = sim.get_cell(pos).mat;
+                let neighbours = sim.alive_neighbours(pos);
+                next.push(sim.state.rule_set.apply(mat, neighbours).unwrap_or(mat));
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn parallel_step_matches_sequential() {
+        let mut sim = CellSim::new(vec2(60, 40), 1);
+        sim.clear_sim();
+        let gun = "x = 36, y = 9, rule = B3/S23\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$\
+                   2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!";
+        sim.paste_cells(&Pattern::from_rle(gun).unwrap(), vec2(2, 2));
+
+        for generation in 0..50 {
+            let expected = sequential_step(&sim);
+            sim.state.step_sim = true;
+            sim.physics_step(Duration::ZERO);
+            let mats: Vec<_> = sim.sim_buf.iter().map(|c| c.get().mat).collect();
+            assert_eq!(mats, expected, "generation {generation}");
+        }
+    }
+
+    #[test]
+    fn out_of_bounds_reads_as_dead() {
+        let sim = CellSim::new(vec2(8, 8), 1);
+        assert_eq!(sim.get_cell_safe(vec2(-1, 0)).mat, Material::Dead);
+        assert_eq!(sim.get_cell_safe(vec2(0, 8)).mat, Material::Dead);
+    }
+
+    #[test]
+    fn edge_cells_are_updated() {
+        let mut sim = CellSim::new(vec2(8, 8), 1);
+        sim.clear_sim();
+        // (0, 0) has 3 live neighbours & nothing beyond the corner, so it's born into a block.
+        sim.paste_pattern(&[(1, 0), (0, 1), (1, 1)], vec2(0, 0));
+
+        sim.state.step_sim = true;
+        sim.physics_step(Duration::ZERO);
+        assert_eq!(alive_cells(&sim), [(0, 0), (1, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        let mut sim = CellSim::new(vec2(200, 150), 1);
+        let gun = "x = 36, y = 9, rule = B3/S23\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$\
+                   2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!";
+        sim.paste_cells(&Pattern::from_rle(gun).unwrap(), vec2(20, 30));
+        for x in 100..110 {
+            sim.update_cell(vec2(x, 149), Material::Sand);
         }
+
+        let path = std::env::temp_dir().join("cell_sim_rle_round_trip.rle");
+        sim.export_rle(&path).unwrap();
+        let loaded = CellSim::import_rle(&path).unwrap();
+        let rle_len = fs::metadata(&path).unwrap().len();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.sim_size, sim.sim_size);
+        for y in 0..sim.sim_size.y {
+            for x in 0..sim.sim_size.x {
+                let pos = vec2(x, y);
+                assert_eq!(loaded.get_cell(pos).mat, sim.get_cell(pos).mat, "{pos:?}");
+            }
+        }
+        // vs 1 byte per cell.
+        assert!(rle_len < (200 * 150) as u64);
+    }
+
+    #[test]
+    fn rle_keeps_the_rule_set() {
+        let mut sim = CellSim::new(vec2(20, 10), 1);
+        sim.state.rule_set = RuleSet::SEEDS;
+        sim.paste_pattern(&[(3, 3), (4, 3)], vec2(0, 0));
+
+        let path = std::env::temp_dir().join("cell_sim_rle_rule_set.rle");
+        sim.export_rle(&path).unwrap();
+        let header = fs::read_to_string(&path).unwrap();
+        let loaded = CellSim::import_rle(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(
+            header.starts_with("x = 20, y = 10, rule = B2/S\n"),
+            "{header}"
+        );
+        assert_eq!(loaded.state.rule_set, RuleSet::SEEDS);
+    }
+
+    #[test]
+    fn bs_notation_round_trips() {
+        for (_, rule) in RuleSet::NAMED_RULES {
+            let notation = rule.to_bs_notation().unwrap();
+            assert_eq!(RuleSet::from_bs_notation(&notation), Ok(rule), "{notation}");
+        }
+        assert_eq!(RuleSet::NOISY_CONWAY.to_bs_notation(), None);
+    }
+
+    #[test]
+    fn parses_glider_rle() {
+        let (_, rle) = BUILT_IN_PATTERNS[0];
+        let alive = |x, y| ((x, y), Material::Alive);
+        assert_eq!(
+            Pattern::from_rle(rle),
+            Ok(vec![
+                alive(1, 0),
+                alive(2, 1),
+                alive(0, 2),
+                alive(1, 2),
+                alive(2, 2)
+            ])
+        );
+        assert_eq!(
+            Pattern::from_rle("2s$bo!"),
+            Ok(vec![
+                ((0, 0), Material::Sand),
+                ((1, 0), Material::Sand),
+                ((1, 1), Material::Alive),
+            ])
+        );
+        assert_eq!(
+            Pattern::from_rle("bqo!"),
+            Err(RleParseError::UnexpectedChar('q'))
+        );
+        assert_eq!(
+            Pattern::from_rle("3o"),
+            Err(RleParseError::MissingTerminator)
+        );
+    }
+
+    #[test]
+    fn glider_rle_round_trip() {
+        let (_, rle) = BUILT_IN_PATTERNS[0];
+        let glider = Pattern::from_rle(rle).unwrap();
+        let mut sim = CellSim::new(vec2(3, 3), 1);
+        sim.clear_sim();
+        sim.paste_cells(&glider, vec2(0, 0));
+
+        let path = std::env::temp_dir().join("cell_sim_glider_round_trip.rle");
+        sim.export_rle(&path).unwrap();
+        let exported = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Pattern::from_rle(&exported), Ok(glider));
+    }
+
+    #[test]
+    fn parses_bs_notation() {
+        assert_eq!(RuleSet::from_bs_notation("B3/S23"), Ok(RuleSet::CONWAY));
+        assert_eq!(RuleSet::from_bs_notation("B36/S23"), Ok(RuleSet::HIGH_LIFE));
+        assert_eq!(RuleSet::from_bs_notation("B2/S"), Ok(RuleSet::SEEDS));
+        assert_eq!(RuleSet::from_bs_notation(" b3/s23 "), Ok(RuleSet::CONWAY));
+        assert_eq!(
+            RuleSet::from_bs_notation("B3678/S34678"),
+            Ok(RuleSet::DAY_AND_NIGHT)
+        );
+    }
+
+    #[test]
+    fn rejects_bad_bs_notation() {
+        use RuleParseError::*;
+        assert_eq!(RuleSet::from_bs_notation("B3S23"), Err(MissingSlash));
+        assert_eq!(RuleSet::from_bs_notation(""), Err(MissingSlash));
+        assert_eq!(RuleSet::from_bs_notation("3/S23"), Err(MissingPrefix('B')));
+        assert_eq!(RuleSet::from_bs_notation("B3/23"), Err(MissingPrefix('S')));
+        assert_eq!(
+            RuleSet::from_bs_notation("B39/S23"),
+            Err(InvalidNeighbourCount('9'))
+        );
+        assert_eq!(
+            RuleSet::from_bs_notation("B3/S2x"),
+            Err(InvalidNeighbourCount('x'))
+        );
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let mut sim = CellSim::new(vec2(10, 10), 1);
+        sim.paste_pattern(Pattern::Glider.cells(), vec2(2, 2));
+        for (x, mat) in [
+            (5, Material::Sand),
+            (6, Material::Wire),
+            (7, Material::ElectronHead),
+            (8, Material::ElectronTail),
+        ] {
+            sim.update_cell(vec2(x, 8), mat);
+        }
+
+        let path = std::env::temp_dir().join("cell_sim_png_round_trip.png");
+        sim.export_png(&path).unwrap();
+        let mut loaded = CellSim::new(vec2(10, 10), 1);
+        loaded.import_png(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        for y in 0..10 {
+            for x in 0..10 {
+                let pos = vec2(x, y);
+                assert_eq!(loaded.get_cell(pos).mat, sim.get_cell(pos).mat, "{pos:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn head_surrounded_by_wire_spreads() {
+        let mut sim = CellSim::new(vec2(7, 7), 1);
+        sim.state.mode = SimulationMode::WireWorld;
+        for y in 2..=4 {
+            for x in 2..=4 {
+                sim.update_cell(vec2(x, y), Material::Wire);
+            }
+        }
+        sim.update_cell(vec2(3, 3), Material::ElectronHead);
+
+        sim.state.step_sim = true;
+        sim.physics_step(Duration::ZERO);
+        // every wire touches exactly the one head.
+        for y in 2..=4 {
+            for x in 2..=4 {
+                let expected = if (x, y) == (3, 3) {
+                    Material::ElectronTail
+                } else {
+                    Material::ElectronHead
+                };
+                assert_eq!(sim.get_cell(vec2(x, y)).mat, expected, "({x}, {y})");
+            }
+        }
+        assert_eq!(sim.get_cell(vec2(1, 3)).mat, Material::Dead);
+    }
+
+    #[test]
+    fn blinker_centre_ages() {
+        let mut sim = CellSim::new(vec2(9, 9), 1);
+        sim.paste_pattern(&[(0, 0), (1, 0), (2, 0)], vec2(3, 4));
+
+        for _ in 0..5 {
+            sim.state.step_sim = true;
+            sim.physics_step(Duration::ZERO);
+        }
+        // the centre survives every generation, the ends are reborn each time.
+        assert_eq!(sim.get_cell(vec2(4, 4)).age, 5);
+        assert_eq!(sim.get_cell(vec2(4, 3)).age, 0);
+        assert_eq!(sim.get_cell(vec2(4, 5)).age, 0);
+    }
+
+    #[test]
+    fn flood_fill_fills_empty_grid() {
+        let mut sim = CellSim::new(vec2(10, 10), 1);
+        sim.flood_fill(vec2(5, 5), Material::Dead, Material::Alive);
+        assert_eq!(alive_cells(&sim).len(), 100);
+    }
+
+    #[test]
+    fn registry_says_what_falls() {
+        assert!(!Material::Dead.properties().falls);
+        assert!(Material::Sand.properties().falls);
+        // fire & steam go the other way.
+        assert!(Material::Fire.properties().rises && !Material::Fire.properties().falls);
+        assert!(Material::Steam.properties().rises && !Material::Steam.properties().flammable);
+    }
+
+    #[test]
+    fn stochastic_birth_happens_at_its_probability() {
+        let mut born_prob = [0.0; 9];
+        born_prob[3] = 0.5;
+        let rule = RuleSet::Stochastic {
+            born_prob,
+            survive_prob: [0.0; 9],
+        };
+
+        let births = (0..1000)
+            .filter(|_| rule.apply(Material::Dead, 3) == Some(Material::Alive))
+            .count();
+        // ~16 standard deviations wide, a fair rng won't fail it.
+        assert!((400..=600).contains(&births), "{births} births");
+        assert_eq!(rule.apply(Material::Dead, 2), None);
+        assert_eq!(RuleSet::Static.apply(Material::Dead, 3), None);
+        assert_eq!(RuleSet::Static.apply(Material::Alive, 0), None);
+    }
+
+    #[test]
+    fn pasted_glider_lands_at_the_offset() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut sim = CellSim::new(vec2(40, 20), 1);
+        sim.paste_pattern(&glider, vec2(2, 2));
+        sim.selection = SelectionMode::Selected {
+            rect: (vec2(2, 2), vec2(4, 4)),
+            clipboard: vec![],
+        };
+
+        // 20 cells right of the original.
+        sim.copy_region();
+        sim.paste_at(vec2(22, 2));
+        let mut expected: Vec<_> = [2, 22]
+            .into_iter()
+            .flat_map(|left| glider.map(|(x, y)| (left + x, 2 + y)))
+            .collect();
+        expected.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(alive_cells(&sim), expected);
+    }
+
+    #[test]
+    fn hex_cell_with_six_live_neighbours_is_born_on_b6() {
+        let mut sim = CellSim::new(vec2(20, 10), 1);
+        sim.set_topology(GridTopology::Hexagonal);
+        sim.state.rule_set = RuleSet::Deterministic {
+            born: 1 << 6,
+            survive: 0,
+        };
+        // an odd row, so the neighbours above & below are shoved right.
+        let centre = vec2(4, 5);
+        for (x, y) in hex_neighbours(centre.x, centre.y) {
+            sim.update_cell(vec2(x, y), Material::Alive);
+        }
+        assert_eq!(sim.alive_neighbours(centre), 6);
+
+        sim.state.step_sim = true;
+        sim.physics_step(Duration::ZERO);
+        assert_eq!(sim.get_cell(centre).mat, Material::Alive);
+    }
+
+    #[test]
+    fn reset_to_blank_and_full_density() {
+        let mut sim = CellSim::new(vec2(12, 8), 1);
+        sim.paste_pattern(&[(0, 0), (1, 0), (2, 0)], vec2(3, 4));
+        sim.set_init_pattern(InitialPattern::Blank);
+        sim.reset_sim();
+        assert!(alive_cells(&sim).is_empty());
+
+        sim.set_init_pattern(InitialPattern::RandomDensity(1.0));
+        sim.reset_sim();
+        assert_eq!(alive_cells(&sim).len(), 12 * 8);
     }
 }
@@ -2,32 +2,75 @@ use rayon::ThreadPoolBuildError;
 use winit::keyboard::KeyCode;
 
 use crate::{
-    frontend::{Frontend, TextureData},
+    demo::ScenarioPreset,
+    frontend::{FrameTimingStats, Frontend, TextureData},
     utils::{
+        aabb::Aabb,
+        colour::{heat_map, Rgba},
         // canvas::Canvas,
         consts::{
-            CAMERA_RESISTANCE, CAMERA_SPEED, GRAY, GREEN, LIGHT_GRAY, MOUSE_DRAWBACK_MULTIPLIER,
-            RED, SIM_MAX_SCALE, WHITE,
+            BLUE, BODY_DENSITY, CAMERA_RESISTANCE, CAMERA_SPEED, CCD_STEPS, CIRCLE_OUTLINE_SIDES,
+            COLLISION_FLASH_FRAMES, DARK_GRAY, DEFAULT_FRICTION, GRAVITY, GRAY,
+            INIT_PHYSICS_SUBSTEPS, JOINT_PICK_RADIUS, LIGHT_GRAY, MAX_BODY_VELOCITY,
+            MAX_PHYSICS_SUBSTEPS, MOTOR_ANGULAR_ACCEL, MOUSE_DRAWBACK_MULTIPLIER, RAINBOW, RED,
+            SCENE_PATH, SIM_MAX_SCALE, SPRING_DAMPING, SPRING_STIFFNESS, STATIC_WALL_THICKNESS,
+            TARGET_FPS, TORQUE_ARC_RADIUS, TORQUE_ARC_SCALE, WALL_RESTITUTION, WALL_THICKNESS,
+            WHITE, WIND_ARROW_COLOUR, WIND_DRAG, WIND_SCALE_STEP, WIND_STEP, WORLD_HALF_SIZE,
+            YELLOW,
         },
+        font::GLYPH_HEIGHT,
         input_data::InputData,
-        vec2::{vec2, TextureSpace, Vec2, WindowSpace, WorldSpace},
+        vec2::{vec2, vec2_from_angle, TextureSpace, Vec2, WindowSpace, WorldSpace},
         world::World,
     },
 };
 use core::f32;
+use log::{error, info, trace};
 use std::{
     clone,
+    fmt::Write as _,
+    io,
     ops::{Add, Div, Mul, Sub},
+    path::Path,
     task::Wake,
     time::Duration,
 };
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameState {
     frame: u32,
     texture_scale: u32,
     window_size: Vec2<u32, WindowSpace>,
     is_running: bool,
+    step_sim: bool,
+    // bodies leaving the bounds are removed, takes priority over reflect_on_exit.
+    destroy_on_exit: bool,
+    // bodies leaving the bounds have their outward velocity reflected.
+    reflect_on_exit: bool,
+    world_bounds: Option<Aabb<f64, WorldSpace>>,
+    // arc at each body's centre showing its spin, see draw_torque_arcs.
+    show_torque_arcs: bool,
+    angular_momentum_label: bool,
+    // speed at which bodies are coloured the hot end of heat_map.
+    max_velocity: f32,
+    // sides of bodies spawned by clicking, cycled 3..=8 on KeyN.
+    ngon_sides: u32,
+    // shape of bodies spawned by clicking, cycled on Tab.
+    shape: ShapeVariant,
+    // air velocity, bodies are dragged towards it by drag * (wind - velocity).
+    wind: Vec2<f32, WorldSpace>,
+    drag: f32,
+    // each physics_step is split into this many updates of dt / physics_substeps.
+    physics_substeps: u32,
+}
+
+// Everything needed to restore a FallingEverything, see save_scene & load_scene.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Scene {
+    state: GameState,
+    objects: Vec<RigidBody>,
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +79,19 @@ pub struct FallingEverything {
     prev_state: GameState,
 
     objects: Vec<RigidBody>,
+    // immovable, only the walls lining the viewport so far.
+    static_bodies: Vec<StaticBody>,
+    joints: Vec<SpringJoint>,
+    // first body of a joint being made with Shift+click
+    joint_start: Option<usize>,
+    prev_positions: Vec<Vec2<f32, WorldSpace>>, // centres at the previous physics step, for interpolation
+    collisions: Vec<bool>, // whether each object collided on the last physics step
+    hull_points: Option<Vec<Vec2<f32, WorldSpace>>>, // Some while collecting points for a convex hull (KeyH)
+    // acceleration due to gravity, world y points down the screen.
+    gravity: Vec2<f32, WorldSpace>,
+    kinetic_energy: f32,
+    potential_energy: f32, // relative to y = 0
+    sim_speed: f32,        // App's multiplier on delta time, shown in the HUD
     world: World,
 }
 
@@ -47,6 +103,7 @@ impl Frontend for FallingEverything {
                 .state
                 .window_size
                 .to_texture_space(self.state.texture_scale),
+            dirty_region: None,
         }
     }
 
@@ -56,8 +113,9 @@ impl Frontend for FallingEverything {
 
     fn resize_texture(&mut self, window_size: Vec2<u32, WindowSpace>) {
         self.state.window_size = window_size;
-        self.world
-            .resize(window_size.to_texture_space(self.state.texture_scale));
+        let viewport_size = window_size.to_texture_space(self.state.texture_scale);
+        self.world.resize(viewport_size);
+        self.static_bodies = StaticBody::viewport_walls(viewport_size);
     }
 
     fn rescale_texture(&mut self, scale: u32) {
@@ -65,60 +123,269 @@ impl Frontend for FallingEverything {
         self.resize_texture(self.state.window_size);
     }
 
-    fn update(&mut self, inputs: &mut InputData, delta_time: Duration) {
-        self.world.draw_all(LIGHT_GRAY);
+    fn update(&mut self, inputs: &mut InputData, frame_timing: FrameTimingStats) {
+        let delta_time = frame_timing.avg;
+        self.sim_speed = frame_timing.sim_speed;
         self.handle_inputs(inputs, delta_time.as_secs_f64());
+        // Shift+ArrowRight rotates the wind & Ctrl+ArrowRight points gravity right instead.
+        self.state.step_sim |= inputs.is_pressed(KeyCode::ArrowRight)
+            && !inputs.is_held(KeyCode::ShiftLeft)
+            && !inputs.is_held(KeyCode::ControlLeft);
+
+        if (self.state.is_running || self.state.step_sim)
+            && self.objects.len() > 0
+            && inputs.is_held(KeyCode::AltLeft)
+        {
+            self.objects[0].object.centre = inputs
+                .mouse_pos
+                .to_world_space(self.state.texture_scale, self.world.camera_pos)
+                .cast();
+            self.objects[0]
+                .object
+                .rotate(f32::consts::FRAC_PI_3 * delta_time.as_secs_f32());
+            self.prev_positions[0] = self.objects[0].object.centre;
+        }
 
-        if (self.state.is_running || inputs.is_pressed(KeyCode::ArrowRight)) {
-            if self.objects.len() > 0 && inputs.is_held(KeyCode::AltLeft) {
-                self.objects[0].object.centre = inputs
-                    .mouse_pos
-                    .to_world_space(self.state.texture_scale, self.world.camera_pos)
-                    .cast();
-                self.objects[0]
-                    .object
-                    .rotate(f32::consts::FRAC_PI_3 * delta_time.as_secs_f32());
+        // a motor, spins the first body in place without pushing it anywhere.
+        let spin = inputs.is_held(KeyCode::KeyQ) as i32 - inputs.is_held(KeyCode::KeyE) as i32;
+        if self.state.is_running && spin != 0 {
+            if let Some(body) = self.objects.first_mut() {
+                body.apply_torque(spin as f32 * MOTOR_ANGULAR_ACCEL * body.inertia);
             }
+        }
 
-            let mut collisions_vec = vec![vec![false; self.objects.len()]; self.objects.len()];
-            for i in 0..self.objects.len() {
-                let body = &mut self.objects[i];
-                body.update(delta_time.as_secs_f32());
+        self.update_body_colours();
+
+        self.kinetic_energy = self
+            .objects
+            .iter()
+            .map(RigidBody::total_kinetic_energy)
+            .sum();
+        self.potential_energy = self
+            .objects
+            .iter()
+            .map(|b| -b.mass * self.gravity.dot_product(b.object.centre))
+            .sum();
+        if self.state.frame % TARGET_FPS as u32 == 0 {
+            info!(
+                "Bodies: {} | Kinetic: {:.2} | Potential: {:.2} | Total: {:.2}",
+                self.objects.len(),
+                self.kinetic_energy,
+                self.potential_energy,
+                self.kinetic_energy + self.potential_energy
+            );
+        }
 
-                for j in 0..self.objects.len() {
-                    if i == j || collisions_vec[i][j] {
-                        continue;
-                    }
+        self.prev_state = self.state;
+        self.state.frame += 1;
+    }
 
-                    let body = &self.objects[i];
-                    let other = &self.objects[j];
-                    if let Some(collision) = body.object.does_collide(&other.object) {
-                        println!("collision between {i}, {j} .. {collision:#?}");
-                        let body = &mut self.objects[i];
-                        body.apply_force(-collision.normal * 15.0, body.object.centre);
-                        collisions_vec[i][j] = true;
-                    }
-                }
+    fn physics_step(&mut self, dt: Duration) {
+        if !(self.state.is_running || self.state.step_sim) {
+            return;
+        }
+        self.state.step_sim = false;
 
-                let body = &self.objects[i];
-                if collisions_vec[i].iter().any(|x| *x) {
-                    self.world.draw_polygon(&body.object.world_verts(), RED);
+        // interpolation runs between whole steps, not substeps.
+        for (prev, body) in self.prev_positions.iter_mut().zip(&mut self.objects) {
+            *prev = body.object.centre;
+            body.flash_frames = body.flash_frames.saturating_sub(1);
+        }
+
+        let mut collisions_vec = vec![vec![false; self.objects.len()]; self.objects.len()];
+        let substep_dt = dt / self.state.physics_substeps;
+        for _ in 0..self.state.physics_substeps {
+            self.physics_substep(substep_dt, &mut collisions_vec);
+        }
+
+        self.collisions = collisions_vec
+            .iter()
+            .map(|c| c.iter().any(|x| *x))
+            .collect();
+
+        self.apply_world_bounds();
+    }
+
+    fn render_interpolated(&mut self, alpha: f64) {
+        self.world.clear(LIGHT_GRAY);
+        self.draw_world_walls();
+        for wall in &self.static_bodies {
+            let bounds = wall.bounds.cast::<i32>();
+            self.world
+                .draw_filled_rect(bounds.min, bounds.max, DARK_GRAY);
+        }
+
+        let alpha = alpha as f32;
+        for (i, body) in self.objects.iter().enumerate() {
+            // offset from the current centre to the interpolated centre.
+            let prev = self.prev_positions[i];
+            let offset = prev.lerp(body.object.centre, alpha) - body.object.centre;
+
+            // compound bodies always draw their shapes, a dot doesn't show the layout.
+            let flashing = body.flash_frames > 0;
+            if self.collisions[i] || flashing || body.compound.is_some() {
+                let colour = if flashing {
+                    WHITE
+                } else if self.collisions[i] {
+                    RED
                 } else {
-                    self.world
-                        .draw_circle_fill(body.object.centre.cast(), 4, GREEN);
+                    body.last_colour
+                };
+                if let Some(radius) = body.radius {
+                    // spoke from the centre, so the spin is visible.
+                    let centre = body.object.centre + offset;
+                    let spoke = vec2(radius, 0.0).rotate(body.rotation);
+                    if self.collisions[i] {
+                        self.world
+                            .draw_circle_fill_fast(centre.cast(), radius as u32, colour);
+                    } else {
+                        self.world
+                            .draw_circle_outline(centre.cast(), radius as u32, colour);
+                    }
+                    self.world.draw_line(centre, centre + spoke, colour);
+                } else {
+                    for shape in body.world_shapes() {
+                        let verts: Vec<_> = shape
+                            .world_verts()
+                            .into_iter()
+                            .map(|v| v + offset)
+                            .collect();
+                        // filled while colliding, so contacts stand out.
+                        if self.collisions[i] {
+                            self.world.draw_filled_polygon(&verts, colour);
+                        } else {
+                            self.world.draw_polygon(&verts, colour);
+                        }
+                    }
                 }
+            } else {
+                self.world.draw_circle_fill_fast(
+                    (body.object.centre + offset).cast(),
+                    4,
+                    body.last_colour,
+                );
             }
-        } else {
-            for body in &self.objects {
-                self.world
-                    .draw_circle_fill(body.object.centre.cast(), 4, GREEN);
+
+            if let (true, Some(point)) = (flashing, body.flash_point) {
+                self.world.draw_cell((point + offset).cast(), YELLOW);
             }
         }
 
+        // a distinct hue per joint, so overlapping springs can be told apart.
+        for (k, joint) in self.joints.iter().enumerate() {
+            let centre = |i: usize| {
+                let prev = self.prev_positions[i];
+                prev.lerp(self.objects[i].object.centre, alpha)
+            };
+            let (a, b) = (centre(joint.body_a), centre(joint.body_b));
+            let colour = RAINBOW[k % RAINBOW.len()];
+            #[cfg(feature = "antialiased")]
+            self.world.draw_line_aa(a, b, colour);
+            #[cfg(not(feature = "antialiased"))]
+            self.world.draw_line(a, b, colour);
+        }
+
+        for point in self.hull_points.iter().flatten() {
+            self.world.draw_circle_fill_fast(point.cast(), 1, WHITE);
+        }
+
+        if self.state.show_torque_arcs {
+            self.draw_torque_arcs(alpha);
+        }
+        self.draw_wind_arrow();
+        self.draw_hud();
+
         self.world.draw_grid();
+    }
 
-        self.prev_state = self.state;
-        self.state.frame += 1;
+    fn load_scenario(&mut self, preset: ScenarioPreset) {
+        self.objects.clear();
+        self.joints.clear();
+        self.joint_start = None;
+        self.prev_positions.clear();
+        self.collisions.clear();
+        self.world.reset_viewport();
+
+        match preset {
+            ScenarioPreset::SolarSystem => {
+                // a dense sun with bodies on tangential velocities around it.
+                self.spawn_rigidbody(
+                    vec2(0.0, 0.0),
+                    self.state.shape,
+                    BODY_DENSITY * 10.0,
+                    DEFAULT_FRICTION,
+                    vec2(0.0, 0.0),
+                    vec2(0.0, 0.0),
+                );
+                for (i, radius) in [60.0, 100.0, 140.0, 180.0_f32].into_iter().enumerate() {
+                    let angle = i as f32 * f32::consts::FRAC_PI_2;
+                    let position = vec2_from_angle(angle) * radius;
+                    // Keplerian falloff, v ~ 1/sqrt(r).
+                    let velocity = vec2_from_angle(angle + f32::consts::FRAC_PI_2)
+                        * (20.0 * (60.0 / radius).sqrt());
+                    self.spawn_rigidbody(
+                        position,
+                        self.state.shape,
+                        BODY_DENSITY,
+                        DEFAULT_FRICTION,
+                        velocity,
+                        vec2(0.0, 0.0),
+                    );
+                }
+            }
+        }
+        info!("Loaded scenario {preset:?}, {} bodies", self.objects.len());
+    }
+
+    fn inspector_text(&self) -> String {
+        let mut text = format!(
+            "Bodies: {} | Running: {}\n",
+            self.objects.len(),
+            self.state.is_running
+        );
+        for (i, (body, colliding)) in self.objects.iter().zip(&self.collisions).enumerate() {
+            writeln!(
+                text,
+                "{i}: pos {} vel {} mass {:.1}{}",
+                body.object.centre,
+                body.velocity,
+                body.mass,
+                if *colliding { " colliding" } else { "" }
+            )
+            .unwrap();
+        }
+        text
+    }
+
+    fn clear_colour(&self) -> Option<Rgba> {
+        Some(LIGHT_GRAY)
+    }
+
+    fn help_text() -> &'static [(KeyCode, &'static str)] {
+        &[
+            (KeyCode::Space, "Toggle simulation"),
+            (KeyCode::ArrowRight, "Step simulation"),
+            (KeyCode::KeyW, "Camera up"),
+            (KeyCode::KeyA, "Camera left"),
+            (KeyCode::KeyS, "Camera down"),
+            (KeyCode::KeyD, "Camera right"),
+            (KeyCode::KeyR, "Reset camera"),
+            (KeyCode::KeyC, "Clear bodies"),
+            (KeyCode::KeyH, "Toggle hull mode"),
+            (KeyCode::Enter, "Spawn hull"),
+            (KeyCode::KeyB, "Spawn T-shaped body"),
+            (KeyCode::KeyN, "Cycle body sides 3..8"),
+            (KeyCode::Tab, "Cycle body shape"),
+            (KeyCode::ShiftLeft, "+Left/Right rotate wind, +=/- scale it"),
+            (KeyCode::KeyG, "Gravity down, 0 off"),
+            (KeyCode::ControlLeft, "+Left/Right/Up gravity direction"),
+            (KeyCode::AltLeft, "Drag first body"),
+            (KeyCode::KeyQ, "Spin first body, E the other way"),
+            (KeyCode::ShiftLeft, "+Click two bodies to join them"),
+            (KeyCode::KeyV, "+Shift cycle torque arcs/labels"),
+            (KeyCode::ControlLeft, "+S/L save/load scene"),
+            (KeyCode::ControlLeft, "+=/- physics substeps"),
+        ]
     }
 
     fn new(window_size: Vec2<u32, WindowSpace>, init_scale_factor: u32) -> Self {
@@ -127,29 +394,422 @@ impl Frontend for FallingEverything {
             texture_scale: init_scale_factor,
             window_size,
             is_running: false,
+            step_sim: false,
+            destroy_on_exit: false,
+            reflect_on_exit: true,
+            world_bounds: None,
+            show_torque_arcs: false,
+            angular_momentum_label: false,
+            max_velocity: MAX_BODY_VELOCITY,
+            ngon_sides: 4,
+            shape: ShapeVariant::Polygon,
+            wind: vec2(0.0, 0.0),
+            drag: WIND_DRAG,
+            physics_substeps: INIT_PHYSICS_SUBSTEPS,
         };
         let prev_state = state.clone();
         let viewport_size = window_size.to_texture_space(init_scale_factor);
 
-        Self {
+        let mut frontend = Self {
             state,
             prev_state,
             objects: vec![],
+            static_bodies: StaticBody::viewport_walls(viewport_size),
+            joints: vec![],
+            joint_start: None,
+            prev_positions: vec![],
+            collisions: vec![],
+            hull_points: None,
+            gravity: vec2(0.0, GRAVITY),
+            kinetic_energy: 0.0,
+            potential_energy: 0.0,
+            sim_speed: 1.0,
             world: World::new(viewport_size),
-        }
+        };
+        frontend.set_world_bounds(Aabb::new(
+            vec2(-WORLD_HALF_SIZE, -WORLD_HALF_SIZE),
+            vec2(WORLD_HALF_SIZE, WORLD_HALF_SIZE),
+        ));
+        frontend
     }
 }
 
 impl FallingEverything {
+    // One dt update of every body, resolving each colliding pair once.
+    // collided is shared across the step's substeps, so it ends up recording any contact.
+    fn physics_substep(&mut self, dt: Duration, collided: &mut [Vec<bool>]) {
+        let mut resolved = vec![vec![false; self.objects.len()]; self.objects.len()];
+        for joint in &self.joints {
+            joint.apply(&mut self.objects);
+        }
+
+        for i in 0..self.objects.len() {
+            let body = &mut self.objects[i];
+            body.apply_force(self.gravity * body.mass, body.object.centre);
+            // in still air this is plain drag, bodies moving with the wind feel nothing.
+            let wind_force = (self.state.wind - body.velocity) * self.state.drag;
+            body.apply_force(wind_force, body.object.centre);
+            body.update(dt.as_secs_f32(), &self.static_bodies);
+
+            for j in 0..self.objects.len() {
+                if i == j || resolved[i][j] {
+                    continue;
+                }
+
+                let body = &self.objects[i];
+                let other = &self.objects[j];
+                let (other_velocity, other_friction) = (other.velocity, other.friction_coeff);
+                if let Some(collision) = body.does_collide(other) {
+                    trace!("collision between {i}, {j} .. {collision:#?}");
+                    // midpoint of the centres, projected onto the normal through body's centre.
+                    let centre = body.object.centre;
+                    let midpoint = (centre + other.object.centre) / 2.0;
+                    let contact = centre + (midpoint - centre).project_onto(collision.normal);
+
+                    let body = &mut self.objects[i];
+                    let rel_velocity = body.velocity - other_velocity;
+                    let impulse = -collision.normal * 15.0 * dt.as_secs_f32();
+                    body.apply_impulse_at_point(impulse, body.object.centre);
+                    let friction = (body.friction_coeff * other_friction).sqrt();
+                    body.apply_friction(
+                        collision.normal,
+                        impulse.length(),
+                        rel_velocity,
+                        friction,
+                        contact,
+                    );
+                    body.flash(contact);
+
+                    // equal & opposite, so the pair is resolved once per substep.
+                    let other = &mut self.objects[j];
+                    other.apply_impulse_at_point(-impulse, other.object.centre);
+                    other.apply_friction(
+                        -collision.normal,
+                        impulse.length(),
+                        -rel_velocity,
+                        friction,
+                        contact,
+                    );
+                    other.flash(contact);
+                    resolved[i][j] = true;
+                    resolved[j][i] = true;
+                    collided[i][j] = true;
+                    collided[j][i] = true;
+                }
+            }
+
+            for wall in &self.static_bodies {
+                let body = &mut self.objects[i];
+                if let Some(collision) = body.collide_static(wall, vec2(0.0, 0.0)) {
+                    body.resolve_static_collision(&collision);
+                    // the diagonal is otherwise unused, walls aren't bodies.
+                    collided[i][i] = true;
+                }
+            }
+        }
+    }
+
     fn handle_inputs(&mut self, inputs: &mut InputData, delta_time: f64) {
         if inputs.is_pressed(KeyCode::Space) {
             self.state.is_running = !self.state.is_running;
         }
 
+        #[cfg(feature = "serde")]
+        self.handle_scene_inputs(inputs);
+
+        // Shift+V cycles: off -> arcs -> arcs & labels -> off
+        if inputs.is_held(KeyCode::ShiftLeft) && inputs.is_pressed(KeyCode::KeyV) {
+            let state = &mut self.state;
+            (state.show_torque_arcs, state.angular_momentum_label) =
+                match (state.show_torque_arcs, state.angular_momentum_label) {
+                    (false, _) => (true, false),
+                    (true, false) => (true, true),
+                    (true, true) => (false, false),
+                };
+        }
+
+        // Physics substeps on Ctrl+= / Ctrl+- (unmodified they scale the texture)
+        if inputs.is_held(KeyCode::ControlLeft) {
+            let substeps = self.state.physics_substeps;
+            self.state.physics_substeps += inputs.is_pressed(KeyCode::Equal) as u32;
+            self.state.physics_substeps -= inputs.is_pressed(KeyCode::Minus) as u32;
+            self.state.physics_substeps =
+                self.state.physics_substeps.clamp(1, MAX_PHYSICS_SUBSTEPS);
+            if self.state.physics_substeps != substeps {
+                info!("Physics substeps: {}", self.state.physics_substeps);
+            }
+        }
+
+        self.handle_gravity_inputs(inputs);
+        self.handle_wind_inputs(inputs);
         self.handle_camera_inputs(inputs, delta_time);
         self.handle_object_spawning(inputs);
     }
 
+    // Digit0 turns gravity off, KeyG points it down & Ctrl+Left/Right/Up point it that way.
+    fn handle_gravity_inputs(&mut self, inputs: &InputData) {
+        let ctrl_held = inputs.is_held(KeyCode::ControlLeft);
+        let directions = [
+            // Ctrl+Digit0 is the app's sim speed.
+            (
+                !ctrl_held && inputs.is_pressed(KeyCode::Digit0),
+                vec2(0.0, 0.0),
+            ),
+            (inputs.is_pressed(KeyCode::KeyG), vec2(0.0, GRAVITY)),
+            (
+                ctrl_held && inputs.is_pressed(KeyCode::ArrowLeft),
+                vec2(-GRAVITY, 0.0),
+            ),
+            (
+                ctrl_held && inputs.is_pressed(KeyCode::ArrowRight),
+                vec2(GRAVITY, 0.0),
+            ),
+            (
+                ctrl_held && inputs.is_pressed(KeyCode::ArrowUp),
+                vec2(0.0, -GRAVITY),
+            ),
+        ];
+        if let Some((_, gravity)) = directions.into_iter().find(|(pressed, _)| *pressed) {
+            if gravity != self.gravity {
+                self.set_gravity(gravity);
+            }
+        }
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vec2<f32, WorldSpace>) {
+        self.gravity = gravity;
+        info!("Gravity: {gravity}");
+    }
+
+    // Shift+Left/Right rotate the wind by 45°, Shift+Plus/Minus scale its speed.
+    fn handle_wind_inputs(&mut self, inputs: &InputData) {
+        if !inputs.is_held(KeyCode::ShiftLeft) {
+            return;
+        }
+
+        let wind = self.state.wind;
+        let turns = inputs.is_pressed(KeyCode::ArrowLeft) as i32
+            - inputs.is_pressed(KeyCode::ArrowRight) as i32;
+        let mut new_wind = wind.rotate(turns as f32 * f32::consts::FRAC_PI_4);
+
+        if inputs.is_pressed(KeyCode::Equal) {
+            new_wind = if new_wind.length() < WIND_STEP {
+                vec2(WIND_STEP, 0.0)
+            } else {
+                new_wind * WIND_SCALE_STEP
+            };
+        } else if inputs.is_pressed(KeyCode::Minus) {
+            new_wind /= WIND_SCALE_STEP;
+            if new_wind.length() < WIND_STEP {
+                new_wind = vec2(0.0, 0.0);
+            }
+        }
+
+        if new_wind != wind {
+            self.set_wind(new_wind);
+        }
+    }
+
+    pub fn set_wind(&mut self, wind: Vec2<f32, WorldSpace>) {
+        self.state.wind = wind;
+        info!("Wind: {wind} ({:.1} units/s)", wind.length());
+    }
+
+    // Semi-transparent arrow in the top right corner, pointing downwind.
+    fn draw_wind_arrow(&mut self) {
+        let wind = self.state.wind;
+        let speed = wind.length();
+        if speed <= 0.0 {
+            return;
+        }
+
+        // texture space to world space, the inverse of Vec2::to_texture_space.
+        let camera = self.world.camera_pos.cast::<i32>();
+        let corner: Vec2<i32, TextureSpace> = vec2(self.world.viewport_size.x as i32 - 24, 24);
+        let centre = vec2(corner.x + camera.x, corner.y - camera.y);
+        let half = (wind / speed * 16.0).cast::<i32>();
+        self.world
+            .draw_arrow_blend(centre - half, centre + half, 8.0, WIND_ARROW_COLOUR);
+    }
+
+    // Top left corner, same texture -> world conversion as draw_wind_arrow.
+    fn draw_hud(&mut self) {
+        let camera = self.world.camera_pos.cast::<i32>();
+        let lines = [
+            format!("speed: {}x", self.sim_speed),
+            format!("substeps: {}", self.state.physics_substeps),
+            format!("bodies: {}", self.objects.len()),
+            format!(
+                "ke: {:.1} pe: {:.1} total: {:.1}",
+                self.kinetic_energy,
+                self.potential_energy,
+                self.kinetic_energy + self.potential_energy
+            ),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            let corner = vec2(2 + camera.x, 2 + i as i32 * (GLYPH_HEIGHT + 1) - camera.y);
+            self.world.draw_text(corner, line, WHITE);
+        }
+    }
+
+    // Save scene on Ctrl+S, load on Ctrl+L
+    #[cfg(feature = "serde")]
+    fn handle_scene_inputs(&mut self, inputs: &InputData) {
+        if !inputs.is_held(KeyCode::ControlLeft) {
+            return;
+        }
+
+        if inputs.is_pressed(KeyCode::KeyS) {
+            match self.save_scene(Path::new(SCENE_PATH)) {
+                Ok(()) => info!("Saved scene to {SCENE_PATH}"),
+                Err(e) => error!("Failed to save scene: {e}"),
+            }
+        } else if inputs.is_pressed(KeyCode::KeyL) {
+            match Self::load_scene(Path::new(SCENE_PATH)) {
+                Ok(mut scene) => {
+                    // the window & texture scale belong to the app, not the scene.
+                    scene.state.texture_scale = self.state.texture_scale;
+                    scene.resize_texture(self.state.window_size);
+                    *self = scene;
+                    info!("Loaded scene from {SCENE_PATH}");
+                }
+                Err(e) => error!("Failed to load scene: {e}"),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn save_scene(&self, path: &Path) -> io::Result<()> {
+        let scene = Scene {
+            state: self.state,
+            objects: self.objects.clone(),
+        };
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(io::BufWriter::new(file), &scene)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn load_scene(path: &Path) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let scene: Scene = serde_json::from_reader(io::BufReader::new(file))?;
+
+        // new() sizes the world to window_size.to_texture_space(texture_scale).
+        let mut frontend = Self::new(scene.state.window_size, scene.state.texture_scale);
+        frontend.state = scene.state;
+        frontend.prev_state = scene.state;
+        if let Some(bounds) = scene.state.world_bounds {
+            frontend.set_world_bounds(bounds);
+        }
+        for body in scene.objects {
+            frontend.push_rigidbody(body);
+        }
+        Ok(frontend)
+    }
+
+    pub fn set_world_bounds(&mut self, bounds: Aabb<f64, WorldSpace>) {
+        self.state.world_bounds = Some(bounds);
+        // camera_pos is the viewport's top left, with y negated (see Vec2::to_texture_space).
+        self.world.camera_bounds = Some(Aabb::new(
+            vec2(bounds.min.x, -bounds.max.y),
+            vec2(bounds.max.x, -bounds.min.y),
+        ));
+    }
+
+    // Heat mapped from speed, cold when still & hot at max_velocity.
+    fn update_body_colours(&mut self) {
+        for body in &mut self.objects {
+            body.last_colour =
+                heat_map(f64::from(body.velocity.length() / self.state.max_velocity));
+        }
+    }
+
+    fn apply_world_bounds(&mut self) {
+        let Some(bounds) = self.state.world_bounds else {
+            return;
+        };
+        let bounds = bounds.cast::<f32>();
+
+        if self.state.destroy_on_exit {
+            // reverse, so removals don't shift the bodies still to check.
+            for i in (0..self.objects.len()).rev() {
+                if !bounds.contains_point(self.objects[i].object.centre) {
+                    self.objects.remove(i);
+                    self.prev_positions.remove(i);
+                    self.collisions.remove(i);
+                    self.remove_joints_to(i);
+                }
+            }
+        } else if self.state.reflect_on_exit {
+            for body in &mut self.objects {
+                let centre = body.object.centre;
+                for axis in 0..2 {
+                    let outward = (centre[axis] < bounds.min[axis] && body.velocity[axis] < 0.0)
+                        || (centre[axis] > bounds.max[axis] && body.velocity[axis] > 0.0);
+                    if outward {
+                        body.velocity[axis] = -body.velocity[axis];
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_world_walls(&mut self) {
+        let Some(bounds) = self.state.world_bounds else {
+            return;
+        };
+        if !(self.state.destroy_on_exit || self.state.reflect_on_exit) {
+            return;
+        }
+
+        let min = bounds.min.cast::<i32>() - vec2(WALL_THICKNESS, WALL_THICKNESS);
+        let max = bounds.max.cast::<i32>() + vec2(WALL_THICKNESS, WALL_THICKNESS);
+        let inner_min = bounds.min.cast::<i32>();
+        let inner_max = bounds.max.cast::<i32>();
+        self.world
+            .draw_filled_rect(min, vec2(max.x, inner_min.y), GRAY);
+        self.world
+            .draw_filled_rect(vec2(min.x, inner_max.y), max, GRAY);
+        self.world.draw_filled_rect(
+            vec2(min.x, inner_min.y),
+            vec2(inner_min.x, inner_max.y),
+            GRAY,
+        );
+        self.world.draw_filled_rect(
+            vec2(inner_max.x, inner_min.y),
+            vec2(max.x, inner_max.y),
+            GRAY,
+        );
+    }
+
+    // Arc sweeping from each body's forward direction, RED clockwise & BLUE counter-clockwise.
+    fn draw_torque_arcs(&mut self, alpha: f32) {
+        for (i, body) in self.objects.iter().enumerate() {
+            let prev = self.prev_positions[i];
+            let centre = prev.lerp(body.object.centre, alpha).cast::<i32>();
+            let sweep = body.angular_velocity * TORQUE_ARC_SCALE;
+            let colour = if body.angular_velocity < 0.0 {
+                RED
+            } else {
+                BLUE
+            };
+            self.world.draw_arc(
+                centre,
+                TORQUE_ARC_RADIUS,
+                body.rotation,
+                body.rotation + sweep,
+                colour,
+            );
+
+            if self.state.angular_momentum_label && body.angular_velocity.abs() > f32::EPSILON {
+                let text = format!("w = {:.2} rad/s", body.angular_velocity);
+                let offset = TORQUE_ARC_RADIUS as i32 + 2;
+                self.world
+                    .draw_text(centre + vec2(offset, -2), &text, WHITE);
+            }
+        }
+    }
+
     fn handle_camera_inputs(&mut self, inputs: &InputData, delta_time: f64) {
         // Branchless Camera Movement
         let mut camera_accel = vec2(0.0, 0.0);
@@ -169,10 +829,80 @@ impl FallingEverything {
     pub fn handle_object_spawning(&mut self, inputs: &InputData) {
         if inputs.is_pressed(KeyCode::KeyC) {
             self.objects.clear();
+            self.prev_positions.clear();
+            self.collisions.clear();
+            self.joints.clear();
+            self.joint_start = None;
+            return;
+        }
+
+        let density = BODY_DENSITY;
+
+        if inputs.is_pressed(KeyCode::KeyN) {
+            self.state.ngon_sides = match self.state.ngon_sides {
+                8.. => 3,
+                n => n + 1,
+            };
+            info!("Spawning {}-gons", self.state.ngon_sides);
+        }
+
+        if inputs.is_pressed(KeyCode::Tab) {
+            self.state.shape = self.state.shape.next();
+            info!("Spawning {:?}s", self.state.shape);
+        }
+
+        // T-shaped compound body at the cursor on KeyB, Ctrl+B is the app's bloom toggle.
+        if inputs.is_pressed(KeyCode::KeyB) && !inputs.is_held(KeyCode::ControlLeft) {
+            let centre: Vec2<f32, WorldSpace> = inputs
+                .mouse_pos
+                .to_world_space(self.state.texture_scale, self.world.camera_pos)
+                .cast();
+            let square = ShapeType::Square { size: 18.0 };
+            let shapes = [
+                (square, centre + vec2(-18.0, 0.0)),
+                (square, centre),
+                (square, centre + vec2(18.0, 0.0)),
+                (square, centre + vec2(0.0, 18.0)),
+            ];
+            let mass = density * 4.0 * 18.0 * 18.0;
+            self.spawn_compound(&shapes, mass, vec2(0.0, 0.0), vec2(0.0, 0.0));
+        }
+
+        // Convex hull mode on KeyH, clicks collect points and Enter spawns the hull.
+        if inputs.is_pressed(KeyCode::KeyH) {
+            self.hull_points = match self.hull_points {
+                Some(_) => None,
+                None => Some(vec![]),
+            };
+        }
+        if let Some(points) = &mut self.hull_points {
+            if inputs.was_mouse_pressed() {
+                points.push(
+                    inputs
+                        .mouse_pos
+                        .to_world_space(self.state.texture_scale, self.world.camera_pos)
+                        .cast(),
+                );
+            }
+            if inputs.is_pressed(KeyCode::Enter) {
+                let points = self.hull_points.take().unwrap();
+                self.spawn_convex_hull(&points, density, vec2(0.0, 0.0), vec2(0.0, 0.0));
+            }
+            return;
+        }
+
+        // Shift+click two bodies to join them with a spring.
+        if inputs.is_held(KeyCode::ShiftLeft) {
+            if inputs.was_mouse_pressed() {
+                let mouse = inputs
+                    .mouse_pos
+                    .to_world_space(self.state.texture_scale, self.world.camera_pos)
+                    .cast();
+                self.pick_joint_body(mouse);
+            }
             return;
         }
 
-        let mass = 0.3;
         if inputs.was_mouse_dragging() {
             let released_pos = inputs
                 .mouse_released
@@ -185,17 +915,27 @@ impl FallingEverything {
                 .to_world_space(self.state.texture_scale, self.world.camera_pos)
                 .cast::<f32>();
 
+            // longer holds launch harder, up to 1 second.
+            let hold = (inputs.mouse_released.time - inputs.mouse_pressed.time)
+                .min(Duration::from_secs(1));
             let force = pressed_pos
                 .sub(released_pos)
-                .mul(MOUSE_DRAWBACK_MULTIPLIER as f32);
+                .mul(MOUSE_DRAWBACK_MULTIPLIER as f32 * hold.as_secs_f32());
 
-            self.spawn_rigidbody(pressed_pos, mass, vec2(0.0, 0.0), force);
+            self.spawn_rigidbody(
+                pressed_pos,
+                self.state.shape,
+                density,
+                DEFAULT_FRICTION,
+                vec2(0.0, 0.0),
+                force,
+            );
         } else if inputs.was_mouse_pressed() {
             let velocity = vec2(0.0, 0.0);
             let force = vec2(0.0, 0.0);
 
-            println!(
-                "spawning rigidbody at {:?}, {:?}",
+            trace!(
+                "spawning rigidbody at {}, camera: {}",
                 inputs
                     .mouse_pos
                     .to_world_space(self.state.texture_scale, self.world.camera_pos)
@@ -208,7 +948,9 @@ impl FallingEverything {
                     .mouse_pos
                     .to_world_space(self.state.texture_scale, self.world.camera_pos)
                     .cast(),
-                mass,
+                self.state.shape,
+                density,
+                DEFAULT_FRICTION,
                 velocity,
                 force,
             );
@@ -218,20 +960,306 @@ impl FallingEverything {
     fn spawn_rigidbody(
         &mut self,
         position: Vec2<f32, WorldSpace>,
+        shape: ShapeVariant,
+        density: f32,
+        friction_coeff: f32,
+        velocity: Vec2<f32, WorldSpace>,
+        force: Vec2<f32, WorldSpace>,
+    ) -> &RigidBody {
+        // circumradius of an 18x18 square, so the default 4 sides spawn one.
+        let radius = 18.0 * f32::consts::FRAC_1_SQRT_2;
+        let object = match shape {
+            ShapeVariant::Polygon => {
+                let n_sides = self.state.ngon_sides;
+                let area = ConvexPolygon::regular_polygon(position, radius, n_sides).area();
+                self.spawn_ngon(position, radius, n_sides, density * area, velocity, force);
+                let body = self.objects.last_mut().unwrap();
+                body.friction_coeff = friction_coeff;
+                return body;
+            }
+            // inscribed in the same circle as the polygons.
+            ShapeVariant::Triangle => {
+                ConvexPolygon::equilateral_triangle(position, radius * 3.0f32.sqrt())
+            }
+            ShapeVariant::Circle => {
+                let mut body = RigidBody::circle(position, radius, density, velocity, force);
+                body.friction_coeff = friction_coeff;
+                return self.push_rigidbody(body);
+            }
+        };
+        let mut body = RigidBody::from_density(object, density, velocity, force);
+        body.friction_coeff = friction_coeff;
+        self.push_rigidbody(body)
+    }
+
+    pub fn spawn_ngon(
+        &mut self,
+        centre: Vec2<f32, WorldSpace>,
+        radius: f32,
+        n_sides: u32,
         mass: f32,
         velocity: Vec2<f32, WorldSpace>,
         force: Vec2<f32, WorldSpace>,
     ) -> &RigidBody {
-        let object = Square::new(position, 18.0);
-        let rigid_body = RigidBody::new(object, mass, 1.0, velocity, force);
+        let object = ConvexPolygon::regular_polygon(centre, radius, n_sides);
+        let inertia = mass * object.moment_of_inertia_factor();
+        self.push_rigidbody(RigidBody::new(object, mass, inertia, velocity, force))
+    }
+
+    pub fn spawn_convex_hull(
+        &mut self,
+        points: &[Vec2<f32, WorldSpace>],
+        density: f32,
+        velocity: Vec2<f32, WorldSpace>,
+        force: Vec2<f32, WorldSpace>,
+    ) -> Option<&RigidBody> {
+        let object = ConvexPolygon::from_points(points);
+        if object.local_vertices.len() < 3 {
+            return None; // degenerate hull, e.g. collinear points.
+        }
+        Some(self.push_rigidbody(RigidBody::from_density(object, density, velocity, force)))
+    }
+
+    // shapes are (shape, world centre) pairs, mass is split between them by area.
+    pub fn spawn_compound(
+        &mut self,
+        shapes: &[(ShapeType, Vec2<f32, WorldSpace>)],
+        mass: f32,
+        velocity: Vec2<f32, WorldSpace>,
+        force: Vec2<f32, WorldSpace>,
+    ) -> Option<&RigidBody> {
+        let polygons: Vec<_> = shapes
+            .iter()
+            .map(|(shape, centre)| shape.polygon(*centre))
+            .collect();
+        let total_area: f32 = polygons.iter().map(ConvexPolygon::area).sum();
+        if polygons.is_empty() || total_area <= 0.0 {
+            return None;
+        }
+
+        let masses: Vec<_> = polygons
+            .iter()
+            .map(|p| mass * p.area() / total_area)
+            .collect();
+        let centre_of_mass = polygons
+            .iter()
+            .zip(&masses)
+            .fold(vec2(0.0, 0.0), |acc, (p, m)| acc + p.centre * *m)
+            / mass;
+
+        // Parallel axis theorem, I = Σ(I_local + m * |offset|²).
+        let inertia = polygons
+            .iter()
+            .zip(&masses)
+            .map(|(p, m)| {
+                m * p.moment_of_inertia_factor() + m * (p.centre - centre_of_mass).length_squared()
+            })
+            .sum();
+
+        // the hull of every sub-shape stands in as the body's object, centred on the centre of mass.
+        let all_verts: Vec<_> = polygons
+            .iter()
+            .flat_map(ConvexPolygon::world_verts)
+            .collect();
+        let hull = ConvexPolygon::from_points(&all_verts);
+        let object = ConvexPolygon {
+            local_vertices: hull
+                .local_vertices
+                .iter()
+                .map(|v| *v + hull.centre - centre_of_mass)
+                .collect(),
+            centre: centre_of_mass,
+        };
+
+        let compound = CompoundBody {
+            shapes: polygons
+                .into_iter()
+                .map(|p| {
+                    let offset = p.centre - centre_of_mass;
+                    (p, offset)
+                })
+                .collect(),
+        };
+
+        let mut body = RigidBody::new(object, mass, inertia, velocity, force);
+        body.compound = Some(compound);
+        Some(self.push_rigidbody(body))
+    }
+
+    // The first pick starts a joint, the second finishes it at the bodies' current distance.
+    fn pick_joint_body(&mut self, point: Vec2<f32, WorldSpace>) {
+        let closest = (0..self.objects.len())
+            .map(|i| (i, (self.objects[i].object.centre - point).length()))
+            .filter(|(_, distance)| *distance <= JOINT_PICK_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i);
+        let Some(body) = closest else {
+            return;
+        };
+
+        match self.joint_start.take() {
+            Some(start) if start != body => {
+                let rest_length =
+                    (self.objects[body].object.centre - self.objects[start].object.centre).length();
+                self.joints.push(SpringJoint {
+                    body_a: start,
+                    body_b: body,
+                    rest_length,
+                    stiffness: SPRING_STIFFNESS,
+                    damping: SPRING_DAMPING,
+                });
+                info!("Joined bodies {start} & {body}, rest length {rest_length:.1}");
+            }
+            Some(_) => {} // clicked the same body twice, cancel.
+            None => self.joint_start = Some(body),
+        }
+    }
+
+    // Drops joints attached to a removed body & shifts the indices above it down.
+    fn remove_joints_to(&mut self, removed: usize) {
+        self.joints
+            .retain(|joint| joint.body_a != removed && joint.body_b != removed);
+        for joint in &mut self.joints {
+            joint.body_a -= usize::from(joint.body_a > removed);
+            joint.body_b -= usize::from(joint.body_b > removed);
+        }
+        self.joint_start = None;
+    }
+
+    fn push_rigidbody(&mut self, rigid_body: RigidBody) -> &RigidBody {
+        self.prev_positions.push(rigid_body.object.centre);
+        self.collisions.push(false);
         self.objects.push(rigid_body);
         self.objects.last().unwrap()
     }
 }
 
+// What clicking spawns, cycled on Tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShapeVariant {
+    Polygon, // GameState::ngon_sides
+    Circle,
+    Triangle,
+}
+
+impl ShapeVariant {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Polygon => Self::Circle,
+            Self::Circle => Self::Triangle,
+            Self::Triangle => Self::Polygon,
+        }
+    }
+}
+
+// Shapes that can be spawned as (part of) a body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeType {
+    Square { size: f32 },
+    RegularPolygon { radius: f32, n_sides: u32 },
+}
+
+impl ShapeType {
+    pub fn polygon(self, centre: Vec2<f32, WorldSpace>) -> ConvexPolygon {
+        match self {
+            Self::Square { size } => ConvexPolygon::square(centre, size),
+            Self::RegularPolygon { radius, n_sides } => {
+                ConvexPolygon::regular_polygon(centre, radius, n_sides)
+            }
+        }
+    }
+}
+
+// Damped spring between two bodies' centres, indices into FallingEverything::objects.
+#[derive(Debug, Clone, Copy)]
+pub struct SpringJoint {
+    body_a: usize,
+    body_b: usize,
+    rest_length: f32,
+    stiffness: f32,
+    damping: f32,
+}
+
+impl SpringJoint {
+    // Hooke's law plus damping along the spring, equal & opposite on each body.
+    fn apply(&self, objects: &mut [RigidBody]) {
+        let (a, b) = (&objects[self.body_a], &objects[self.body_b]);
+        let delta = b.object.centre - a.object.centre;
+        let length = delta.length();
+        if length <= f32::EPSILON {
+            return;
+        }
+
+        let unit = delta / length;
+        let extension = length - self.rest_length;
+        let closing_speed = (b.velocity - a.velocity).dot_product(unit);
+        let force = unit * (-self.stiffness * extension - self.damping * closing_speed);
+
+        let b = &mut objects[self.body_b];
+        b.apply_force(force, b.object.centre);
+        let a = &mut objects[self.body_a];
+        a.apply_force(-force, a.object.centre);
+    }
+}
+
+// Infinite mass, collided with but never moved.
 #[derive(Debug, Clone)]
+pub struct StaticBody {
+    bounds: Aabb<f32, WorldSpace>,
+    object: ConvexPolygon,
+}
+
+impl StaticBody {
+    pub fn from_bounds(bounds: Aabb<f32, WorldSpace>) -> Self {
+        let size = bounds.size();
+        let object = ConvexPolygon::rectangle(bounds.min + size / 2.0, size);
+        Self { bounds, object }
+    }
+
+    // STATIC_WALL_THICKNESS walls along the inside of the viewport, at the camera's reset position.
+    pub fn viewport_walls(viewport_size: Vec2<u32, TextureSpace>) -> Vec<Self> {
+        let size = viewport_size.cast::<f32>().cast_unit::<WorldSpace>();
+        let t = STATIC_WALL_THICKNESS;
+        [
+            (vec2(0.0, 0.0), vec2(size.x, t)),
+            (vec2(0.0, size.y - t), size),
+            (vec2(0.0, 0.0), vec2(t, size.y)),
+            (vec2(size.x - t, 0.0), size),
+        ]
+        .into_iter()
+        .map(|(min, max)| Self::from_bounds(Aabb::new(min, max)))
+        .collect()
+    }
+}
+
+// Several convex shapes moving as one rigid body, e.g. T or L shapes.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompoundBody {
+    // offset is from the body's centre of mass, unrotated. Vertices are unrotated too.
+    shapes: Vec<(ConvexPolygon, Vec2<f32, WorldSpace>)>,
+}
+
+impl CompoundBody {
+    // Each sub-shape placed in the world, for a body at centre rotated by rotation.
+    pub fn world_shapes(&self, centre: Vec2<f32, WorldSpace>, rotation: f32) -> Vec<ConvexPolygon> {
+        self.shapes
+            .iter()
+            .map(|(shape, offset)| {
+                let mut shape = shape.clone();
+                shape.rotate(rotation);
+                shape.centre = centre + offset.rotate(rotation);
+                shape
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RigidBody {
-    object: Square,
+    object: ConvexPolygon,
     force: Vec2<f32, WorldSpace>,
     velocity: Vec2<f32, WorldSpace>,
     angular_velocity: f32,
@@ -241,22 +1269,234 @@ pub struct RigidBody {
     inv_mass: f32,
     inv_inertia: f32,
     torque: f32,
+    // None for single shape bodies, whose shape is object.
+    compound: Option<CompoundBody>,
+    // Some for circle bodies, which collide as a circle around object.centre.
+    radius: Option<f32>,
+    // Coulomb coefficient, pairs of bodies use the geometric mean of theirs.
+    friction_coeff: f32,
+    // heat mapped from speed each frame, see FallingEverything::update.
+    last_colour: Rgba,
+    // > 0 for a few steps after a collision, drawn with a white outline & the contact point.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    flash_frames: u8,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    flash_point: Option<Vec2<f32, WorldSpace>>,
 }
 
 impl RigidBody {
-    fn apply_force(&mut self, impulse: Vec2<f32, WorldSpace>, point: Vec2<f32, WorldSpace>) {
+    pub fn world_shapes(&self) -> Vec<ConvexPolygon> {
+        match &self.compound {
+            Some(compound) => compound.world_shapes(self.object.centre, self.rotation),
+            None => vec![self.object.clone()],
+        }
+    }
+
+    // What the body collides as, its circle or each of its world shapes.
+    fn colliders(&self) -> Vec<Box<dyn Shape>> {
+        match self.radius {
+            Some(radius) => vec![Box::new(Circle {
+                centre: self.object.centre,
+                radius,
+            })],
+            None => self
+                .world_shapes()
+                .into_iter()
+                .map(|shape| Box::new(shape) as Box<dyn Shape>)
+                .collect(),
+        }
+    }
+
+    fn bounds(&self) -> Aabb<f32, WorldSpace> {
+        self.colliders()
+            .iter()
+            .map(|shape| shape.bounds())
+            .reduce(|a, b| a.union(&b))
+            .unwrap_or(Aabb::from_centre_radius(self.object.centre, 0.0))
+    }
+
+    // Deepest collision between any pair of sub-shapes.
+    fn does_collide(&self, other: &Self) -> Option<Collision> {
+        // broad phase, SAT is only worth running when the boxes overlap.
+        if !self.bounds().intersects(&other.bounds()) {
+            return None;
+        }
+
+        let other_colliders = other.colliders();
+        self.colliders()
+            .iter()
+            .flat_map(|a| {
+                other_colliders
+                    .iter()
+                    .filter_map(|b| a.does_collide(b.as_ref()))
+            })
+            .max_by(|a, b| a.penetration.total_cmp(&b.penetration))
+    }
+
+    // Deepest collision between the body, moved by offset, & a wall. The normal points into the wall.
+    fn collide_static(
+        &self,
+        wall: &StaticBody,
+        offset: Vec2<f32, WorldSpace>,
+    ) -> Option<Collision> {
+        self.colliders()
+            .into_iter()
+            .filter_map(|mut shape| {
+                shape.translate(offset);
+                shape.does_collide(&wall.object)
+            })
+            .max_by(|a, b| a.penetration.total_cmp(&b.penetration))
+    }
+
+    // Fraction of translation the body can move before first touching a wall it isn't already
+    // touching, & that collision. Sampled every half wall thickness so none can be skipped over.
+    fn sweep_static(
+        &self,
+        translation: Vec2<f32, WorldSpace>,
+        walls: &[StaticBody],
+    ) -> Option<(f32, Collision)> {
+        let walls: Vec<_> = walls
+            .iter()
+            .filter(|wall| self.collide_static(wall, vec2(0.0, 0.0)).is_none())
+            .collect();
+        let hit = |t: f32| {
+            walls
+                .iter()
+                .filter_map(|wall| self.collide_static(wall, translation * t))
+                .max_by(|a, b| a.penetration.total_cmp(&b.penetration))
+        };
+
+        let samples = (translation.length() / (STATIC_WALL_THICKNESS / 2.0)).ceil() as u32;
+        let mut clear = 0.0;
+        for i in 1..=samples {
+            let t = i as f32 / samples as f32;
+            let Some(mut collision) = hit(t) else {
+                clear = t;
+                continue;
+            };
+
+            // bisect between the last clear & first touching fraction.
+            let mut touching = t;
+            for _ in 0..CCD_STEPS {
+                let mid = (clear + touching) / 2.0;
+                match hit(mid) {
+                    Some(c) => (touching, collision) = (mid, c),
+                    None => clear = mid,
+                }
+            }
+            return Some((clear, collision));
+        }
+        None
+    }
+
+    // Pushes the body out of the wall & reflects the velocity into it, the wall has infinite mass.
+    fn resolve_static_collision(&mut self, collision: &Collision) {
+        self.object
+            .translate(-collision.normal * collision.penetration);
+
+        let into_wall = self.velocity.dot_product(collision.normal);
+        if into_wall > 0.0 {
+            let velocity = self.velocity;
+            let normal_impulse = (1.0 + WALL_RESTITUTION) * into_wall * self.mass;
+            self.apply_impulse_at_point(-collision.normal * normal_impulse, self.object.centre);
+            let contact = self.support_point(collision.normal);
+            self.apply_friction(
+                collision.normal,
+                normal_impulse,
+                velocity,
+                self.friction_coeff,
+                contact,
+            );
+        }
+    }
+
+    // Coulomb friction against the sliding part of rel_velocity, at most friction * normal_impulse
+    // & never more than it takes to stop the sliding.
+    fn apply_friction(
+        &mut self,
+        normal: Vec2<f32, WorldSpace>,
+        normal_impulse: f32,
+        rel_velocity: Vec2<f32, WorldSpace>,
+        friction: f32,
+        point: Vec2<f32, WorldSpace>,
+    ) {
+        let sliding = rel_velocity.reject_from(normal);
+        let speed = sliding.length();
+        if speed <= f32::EPSILON {
+            return;
+        }
+
+        let tangent = sliding / speed;
+        let magnitude = (friction * normal_impulse.abs()).min(speed * self.mass);
+        self.apply_impulse_at_point(-tangent * magnitude, point);
+    }
+
+    // Furthest point of the body along direction, on the line through its centre.
+    fn support_point(&self, direction: Vec2<f32, WorldSpace>) -> Vec2<f32, WorldSpace> {
+        let reach = self
+            .colliders()
+            .iter()
+            .map(|shape| shape.project(direction).1)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let centre = self.object.centre;
+        centre + direction * (reach - centre.dot_product(direction))
+    }
+
+    pub fn translational_kinetic_energy(&self) -> f32 {
+        0.5 * self.mass * self.velocity.length_squared()
+    }
+
+    pub fn rotational_kinetic_energy(&self) -> f32 {
+        0.5 * self.inertia * self.angular_velocity.powi(2)
+    }
+
+    pub fn total_kinetic_energy(&self) -> f32 {
+        self.translational_kinetic_energy() + self.rotational_kinetic_energy()
+    }
+
+    // Accumulates a force at a world point, integrated on the next update then reset.
+    fn apply_force(&mut self, force: Vec2<f32, WorldSpace>, point: Vec2<f32, WorldSpace>) {
         let r = point - self.object.centre;
-        self.force += impulse;
-        self.torque = r.cross_product(impulse);
+        self.force += force;
+        self.torque += r.cross_product(force);
     }
 
-    fn update(&mut self, delta_time: f32) {
+    // Accumulates torque without any linear force (e.g. a motor), integrated on the next update.
+    fn apply_torque(&mut self, torque: f32) {
+        self.torque += torque;
+    }
+
+    // Instantly changes linear & angular velocity, bypassing the force accumulators.
+    fn apply_impulse_at_point(
+        &mut self,
+        impulse: Vec2<f32, WorldSpace>,
+        world_point: Vec2<f32, WorldSpace>,
+    ) {
+        let r = world_point - self.object.centre;
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += r.cross_product(impulse) * self.inv_inertia;
+    }
+
+    fn update(&mut self, delta_time: f32, walls: &[StaticBody]) {
         let mut prev_pos = self.object.centre;
 
         // Linear
         let acceleration = self.force * self.inv_mass;
         self.velocity += acceleration * delta_time;
-        self.object.translate(self.velocity * delta_time);
+        let translation = self.velocity * delta_time;
+        // slower bodies can't skip a wall, the discrete check after the update catches them.
+        let swept = if translation.length() > STATIC_WALL_THICKNESS / 2.0 {
+            self.sweep_static(translation, walls)
+        } else {
+            None
+        };
+        match swept {
+            Some((t, collision)) => {
+                self.object.translate(translation * t);
+                self.resolve_static_collision(&collision);
+            }
+            None => self.object.translate(translation),
+        }
 
         // Angular
         let angular_acceleration = self.torque * self.inv_inertia;
@@ -269,19 +1509,41 @@ impl RigidBody {
         self.torque = 0.0;
     }
 
-    fn new_rect(
-        shape: Square,
-        mass: f32,
-        size: Vec2<f32, WorldSpace>,
+    fn flash(&mut self, point: Vec2<f32, WorldSpace>) {
+        self.flash_frames = COLLISION_FLASH_FRAMES;
+        self.flash_point = Some(point);
+    }
+
+    // Mass & moment of inertia from the shape's geometry.
+    fn from_density(
+        object: ConvexPolygon,
+        density: f32,
+        velocity: Vec2<f32, WorldSpace>,
+        force: Vec2<f32, WorldSpace>,
+    ) -> Self {
+        let mass = density * object.area();
+        let inertia = mass * object.moment_of_inertia_factor();
+        RigidBody::new(object, mass, inertia, velocity, force)
+    }
+
+    // Solid disc, object is a CIRCLE_OUTLINE_SIDES polygon standing in for it.
+    fn circle(
+        centre: Vec2<f32, WorldSpace>,
+        radius: f32,
+        density: f32,
         velocity: Vec2<f32, WorldSpace>,
         force: Vec2<f32, WorldSpace>,
     ) -> Self {
-        let inertia = (1.0 / 12.0) * mass * size.x * size.y; // Moment of inertia for a square
-        RigidBody::new(shape, mass, inertia, velocity, force)
+        let mass = density * f32::consts::PI * radius * radius;
+        let inertia = mass * radius * radius / 2.0;
+        let object = ConvexPolygon::regular_polygon(centre, radius, CIRCLE_OUTLINE_SIDES);
+        let mut body = RigidBody::new(object, mass, inertia, velocity, force);
+        body.radius = Some(radius);
+        body
     }
 
     fn new(
-        object: Square,
+        object: ConvexPolygon,
         mass: f32,
         inertia: f32,
         velocity: Vec2<f32, WorldSpace>,
@@ -304,67 +1566,180 @@ impl RigidBody {
             angular_velocity: 0.0,
             torque: 0.0,
             force,
+            compound: None,
+            radius: None,
+            friction_coeff: DEFAULT_FRICTION,
+            last_colour: heat_map(0.0),
+            flash_frames: 0,
+            flash_point: None,
         }
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Collision {
     normal: Vec2<f32, WorldSpace>,
     penetration: f32,
 }
 
+// Anything bodies can collide as, tested against each other with the separating axis theorem.
+pub trait Shape {
+    fn centre(&self) -> Vec2<f32, WorldSpace>;
+    fn world_verts(&self) -> Vec<Vec2<f32, WorldSpace>>;
+    fn translate(&mut self, offset: Vec2<f32, WorldSpace>);
+    fn rotate(&mut self, angle_radians: f32);
+
+    // Candidate separating axes against other, needn't be normalised.
+    fn axes(&self, other: &dyn Shape) -> Vec<Vec2<f32, WorldSpace>>;
+    // (min, max) along a unit axis.
+    fn project(&self, axis: Vec2<f32, WorldSpace>) -> (f32, f32);
+    // Closest vertex to point, or the centre of shapes without any.
+    fn closest_feature(&self, point: Vec2<f32, WorldSpace>) -> Vec2<f32, WorldSpace>;
+
+    fn does_collide(&self, other: &dyn Shape) -> Option<Collision>;
+
+    fn bounds(&self) -> Aabb<f32, WorldSpace> {
+        let (min_x, max_x) = self.project(vec2(1.0, 0.0));
+        let (min_y, max_y) = self.project(vec2(0.0, 1.0));
+        Aabb::new(vec2(min_x, min_y), vec2(max_x, max_y))
+    }
+}
+
+// Shared by every Shape::does_collide, the normal points from a to b.
+fn separating_axis_collision(a: &dyn Shape, b: &dyn Shape) -> Option<Collision> {
+    let mut min_overlap = f32::INFINITY;
+    let mut best_axis = vec2(0.0, 0.0);
+
+    let ab = b.centre() - a.centre();
+
+    for axis in a.axes(b).into_iter().chain(b.axes(a)) {
+        let axis = axis.normalise();
+        if axis.x == 0.0 && axis.y == 0.0 {
+            continue;
+        }
+
+        // Ensure axis points from a to b
+        let axis = if axis.dot_product(ab) < 0.0 {
+            axis * -1.0
+        } else {
+            axis
+        };
+
+        let overlap = ConvexPolygon::internal_overlap(a.project(axis), b.project(axis));
+
+        if overlap <= 0.0 {
+            return None; // Found a separating axis, no collision
+        }
+
+        if overlap < min_overlap {
+            min_overlap = overlap;
+            best_axis = axis;
+        }
+    }
+
+    // no usable axis, e.g. two circles on the same centre.
+    min_overlap.is_finite().then_some(Collision {
+        normal: best_axis,
+        penetration: min_overlap,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Circle {
+    pub centre: Vec2<f32, WorldSpace>,
+    pub radius: f32,
+}
+
+impl Shape for Circle {
+    fn centre(&self) -> Vec2<f32, WorldSpace> {
+        self.centre
+    }
+
+    fn world_verts(&self) -> Vec<Vec2<f32, WorldSpace>> {
+        ConvexPolygon::regular_polygon(self.centre, self.radius, CIRCLE_OUTLINE_SIDES).world_verts()
+    }
+
+    fn translate(&mut self, offset: Vec2<f32, WorldSpace>) {
+        self.centre += offset;
+    }
+
+    // rotationally symmetric, nothing to do.
+    fn rotate(&mut self, _angle_radians: f32) {}
+
+    // Towards other's closest feature, against another circle that's the line between
+    // centres, so this is just distance < sum of radii.
+    fn axes(&self, other: &dyn Shape) -> Vec<Vec2<f32, WorldSpace>> {
+        vec![other.closest_feature(self.centre) - self.centre]
+    }
+
+    fn project(&self, axis: Vec2<f32, WorldSpace>) -> (f32, f32) {
+        let centre = self.centre.dot_product(axis);
+        (centre - self.radius, centre + self.radius)
+    }
+
+    fn closest_feature(&self, _point: Vec2<f32, WorldSpace>) -> Vec2<f32, WorldSpace> {
+        self.centre
+    }
+
+    fn does_collide(&self, other: &dyn Shape) -> Option<Collision> {
+        separating_axis_collision(self, other)
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct Square {
-    local_vertices: [Vec2<f32, WorldSpace>; 4],
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConvexPolygon {
+    local_vertices: Vec<Vec2<f32, WorldSpace>>, // counter-clockwise, relative to centre
     pub centre: Vec2<f32, WorldSpace>,
 }
 
-impl Square {
+impl ConvexPolygon {
     pub fn transform(&mut self, translation: Vec2<f32, WorldSpace>, rotation: f32) {
         self.translate(translation);
         self.rotate(rotation);
     }
 
-    pub fn translate(&mut self, offset: Vec2<f32, WorldSpace>) {
-        self.centre += offset;
+    // Shoelace formula
+    pub fn area(&self) -> f32 {
+        let verts = &self.local_vertices;
+        let twice_area: f32 = (0..verts.len())
+            .map(|i| verts[i].cross_product(verts[(i + 1) % verts.len()]))
+            .sum();
+        twice_area.abs() / 2.0
     }
 
-    pub fn rotate(&mut self, angle_radians: f32) {
-        let (s, c) = angle_radians.sin_cos();
-        for v in &mut self.local_vertices {
-            // Rotate each vertex around the centre
-            *v = vec2(c * v.x - s * v.y, s * v.x + c * v.y);
+    // Moment of inertia about the centre per unit mass, i.e. inertia = mass * factor.
+    // rectangle: (w² + h²) / 12, isosceles triangle: b² / 24 + h² / 18 (about its centroid).
+    pub fn moment_of_inertia_factor(&self) -> f32 {
+        let verts = &self.local_vertices;
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+        for i in 0..verts.len() {
+            let a = verts[i];
+            let b = verts[(i + 1) % verts.len()];
+            let cross = a.cross_product(b).abs();
+            numerator += cross * (a.dot_product(a) + a.dot_product(b) + b.dot_product(b));
+            denominator += cross;
         }
+        numerator / (6.0 * denominator)
     }
 
     // region: Polygon Collision Detection
-    pub fn world_verts(&self) -> [Vec2<f32, WorldSpace>; 4] {
-        let mut world_verts = self.local_vertices;
-        for v in &mut world_verts {
-            *v += self.centre;
-        }
-        world_verts
-    }
-
     // Compute Unit normals (axes) from polygon edges
-    fn get_polygon_axes(world_verts: &[Vec2<f32, WorldSpace>; 4]) -> [Vec2<f32, WorldSpace>; 4] {
-        let mut axes = [vec2(0.0, 0.0); 4];
-        for i in 0..4 {
-            let a = world_verts[i];
-            let b = world_verts[(i + 1) % 4];
-
-            let edge = b - a;
-            let n = edge.perpendicular().normalise();
-            axes[i] = n;
-        }
-        axes
+    fn get_polygon_axes(world_verts: &[Vec2<f32, WorldSpace>]) -> Vec<Vec2<f32, WorldSpace>> {
+        (0..world_verts.len())
+            .map(|i| {
+                let a = world_verts[i];
+                let b = world_verts[(i + 1) % world_verts.len()];
+
+                let edge = b - a;
+                edge.perpendicular().normalise()
+            })
+            .collect()
     }
 
-    fn project_axis(
-        vertices: &[Vec2<f32, WorldSpace>; 4],
-        axis: Vec2<f32, WorldSpace>,
-    ) -> (f32, f32) {
+    fn project_axis(vertices: &[Vec2<f32, WorldSpace>], axis: Vec2<f32, WorldSpace>) -> (f32, f32) {
         let mut min = f32::INFINITY;
         let mut max = f32::NEG_INFINITY;
 
@@ -383,64 +1758,665 @@ impl Square {
         f32::min(a_max, b_max) - f32::max(a_min, b_min)
     }
 
-    fn does_collide(&self, other: &Self) -> Option<Collision> {
-        let world_verts_a = self.world_verts();
-        let world_verts_b = other.world_verts();
+    // endregion
 
-        let mut polygon_axes_a = Self::get_polygon_axes(&world_verts_a);
-        let mut polygon_axes_b = Self::get_polygon_axes(&world_verts_b);
+    pub fn square(centre: Vec2<f32, WorldSpace>, size: f32) -> Self {
+        Self::rectangle(centre, vec2(size, size))
+    }
 
-        let mut min_overlap = f32::INFINITY;
-        let mut best_axis = vec2(0.0, 0.0);
+    pub fn rectangle(centre: Vec2<f32, WorldSpace>, size: Vec2<f32, WorldSpace>) -> Self {
+        let half = size / 2.0;
+        let local_vertices = vec![
+            vec2(-half.x, -half.y),
+            vec2(half.x, -half.y),
+            vec2(half.x, half.y),
+            vec2(-half.x, half.y),
+        ];
+        ConvexPolygon {
+            local_vertices,
+            centre,
+        }
+    }
 
-        let ab = other.centre - self.centre;
+    // Counter-clockwise, centred on the centroid with the bottom edge flat.
+    pub fn equilateral_triangle(centre: Vec2<f32, WorldSpace>, side: f32) -> Self {
+        let height = side * 3.0f32.sqrt() / 2.0;
+        let local_vertices = vec![
+            vec2(-side / 2.0, -height / 3.0),
+            vec2(side / 2.0, -height / 3.0),
+            vec2(0.0, height * 2.0 / 3.0),
+        ];
+        ConvexPolygon {
+            local_vertices,
+            centre,
+        }
+    }
 
-        for axis in polygon_axes_a.iter_mut().chain(polygon_axes_b.iter_mut()) {
-            let axis = axis.normalise();
-            if axis.x == 0.0 && axis.y == 0.0 {
-                continue;
-            }
+    // n_sides (at least 3) vertices evenly spaced around a circle of radius, bottom edge flat.
+    pub fn regular_polygon(centre: Vec2<f32, WorldSpace>, radius: f32, n_sides: u32) -> Self {
+        let n_sides = n_sides.max(3);
+        let step = f32::consts::TAU / n_sides as f32;
+        let start = step / 2.0 - f32::consts::FRAC_PI_2;
+        let local_vertices = (0..n_sides)
+            .map(|i| vec2_from_angle(start + step * i as f32) * radius)
+            .collect();
+        ConvexPolygon {
+            local_vertices,
+            centre,
+        }
+    }
 
-            // Ensure axis points from a to b
-            let axis = if axis.dot_product(ab) < 0.0 {
-                axis * -1.0
-            } else {
-                axis
+    // Convex hull of a point cloud (Graham scan), centred on the mean of the hull's vertices.
+    // Fewer than 3 non-collinear points produces a degenerate polygon.
+    pub fn from_points(points: &[Vec2<f32, WorldSpace>]) -> Self {
+        let mut points = points.to_vec();
+        if points.is_empty() {
+            return ConvexPolygon {
+                local_vertices: vec![],
+                centre: vec2(0.0, 0.0),
             };
+        }
+
+        // Pivot is the lowest point (then leftmost), guaranteed to be on the hull.
+        let pivot_index = (0..points.len())
+            .min_by(|&a, &b| {
+                let (a, b) = (points[a], points[b]);
+                a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+            })
+            .unwrap();
+        let pivot = points.swap_remove(pivot_index);
+
+        // Sort by polar angle around the pivot, closest first when collinear.
+        points.sort_by(|&a, &b| {
+            let cross = (a - pivot).cross_product(b - pivot);
+            0.0f32.total_cmp(&cross).then(
+                (a - pivot)
+                    .length_squared()
+                    .total_cmp(&(b - pivot).length_squared()),
+            )
+        });
+
+        let mut hull = vec![pivot];
+        for p in points {
+            // pop until the last two hull points & p make a counter-clockwise (left) turn.
+            while hull.len() >= 2 {
+                let a = hull[hull.len() - 2];
+                let b = hull[hull.len() - 1];
+                if (b - a).cross_product(p - b) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(p);
+        }
+
+        let centre = hull.iter().fold(vec2(0.0, 0.0), |acc, v| acc + *v) / hull.len() as f32;
+        ConvexPolygon {
+            local_vertices: hull.into_iter().map(|v| v - centre).collect(),
+            centre,
+        }
+    }
+}
+
+impl Shape for ConvexPolygon {
+    fn centre(&self) -> Vec2<f32, WorldSpace> {
+        self.centre
+    }
 
-            let pa = Self::project_axis(&world_verts_a, axis);
-            let pb = Self::project_axis(&world_verts_b, axis);
+    fn world_verts(&self) -> Vec<Vec2<f32, WorldSpace>> {
+        self.local_vertices
+            .iter()
+            .map(|v| *v + self.centre)
+            .collect()
+    }
 
-            let overlap = Self::internal_overlap(pa, pb);
+    fn translate(&mut self, offset: Vec2<f32, WorldSpace>) {
+        self.centre += offset;
+    }
 
-            if overlap <= 0.0 {
-                return None; // Found a separating axis, no collision
+    fn rotate(&mut self, angle_radians: f32) {
+        for v in &mut self.local_vertices {
+            // Rotate each vertex around the centre
+            *v = v.rotate(angle_radians);
+        }
+    }
+
+    fn axes(&self, _other: &dyn Shape) -> Vec<Vec2<f32, WorldSpace>> {
+        Self::get_polygon_axes(&self.world_verts())
+    }
+
+    fn project(&self, axis: Vec2<f32, WorldSpace>) -> (f32, f32) {
+        Self::project_axis(&self.world_verts(), axis)
+    }
+
+    fn closest_feature(&self, point: Vec2<f32, WorldSpace>) -> Vec2<f32, WorldSpace> {
+        self.world_verts()
+            .into_iter()
+            .min_by(|a, b| {
+                (*a - point)
+                    .length_squared()
+                    .total_cmp(&(*b - point).length_squared())
+            })
+            .unwrap_or(self.centre)
+    }
+
+    fn does_collide(&self, other: &dyn Shape) -> Option<Collision> {
+        separating_axis_collision(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::consts::PHYSICS_DT;
+
+    #[test]
+    fn free_fall_gains_kinetic_energy_of_height_dropped() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.drag = 0.0;
+        sim.state.is_running = true;
+        sim.spawn_rigidbody(
+            vec2(200.0, 150.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+
+        for _ in 0..60 {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        let body = &sim.objects[0];
+        let height = body.object.centre.y - 150.0;
+        let expected = body.mass * GRAVITY * height;
+        let kinetic = body.total_kinetic_energy();
+        assert!(
+            (kinetic - expected).abs() <= 0.02 * expected,
+            "kinetic {kinetic} vs m*g*h {expected}"
+        );
+    }
+
+    #[test]
+    fn colliding_bodies_stay_finite_with_substeps() {
+        let dt = Duration::from_secs_f64(PHYSICS_DT);
+        for substeps in [1, 8] {
+            // splitting the step doesn't change the simulated time, past nanosecond rounding.
+            let substep_dt = dt / substeps;
+            assert!((substep_dt * substeps).abs_diff(dt) < Duration::from_nanos(substeps.into()));
+
+            let mut sim = FallingEverything::new(vec2(800, 600), 2);
+            sim.state.is_running = true;
+            sim.state.physics_substeps = substeps;
+            for (x, vel) in [(200.0, 50.0), (205.0, -50.0)] {
+                sim.spawn_rigidbody(
+                    vec2(x, 150.0),
+                    ShapeVariant::Polygon,
+                    BODY_DENSITY,
+                    DEFAULT_FRICTION,
+                    vec2(vel, 0.0),
+                    vec2(0.0, 0.0),
+                );
             }
 
-            if overlap < min_overlap {
-                min_overlap = overlap;
-                best_axis = axis;
+            for _ in 0..10 {
+                sim.physics_step(dt);
             }
+            for body in &sim.objects {
+                assert!(body.velocity.x.is_finite() && body.velocity.y.is_finite());
+                assert!(body.angular_velocity.is_finite());
+                assert!(
+                    body.velocity.length() < 1000.0,
+                    "{substeps}: {:?}",
+                    body.velocity
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn torque_only_spins() {
+        let mut body = RigidBody::from_density(
+            ConvexPolygon::square(vec2(0.0, 0.0), 10.0),
+            1.0,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+        body.apply_torque(100.0);
+        body.update(PHYSICS_DT as f32, &[]);
+        assert!(body.angular_velocity > 0.0);
+        assert_eq!(body.velocity, vec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn pentagon_vertices_on_circumcircle() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        let centre = vec2(8.0, 4.0);
+        let body = sim.spawn_ngon(centre, 10.0, 5, 1.0, vec2(0.0, 0.0), vec2(0.0, 0.0));
+
+        let verts = body.object.world_verts();
+        assert_eq!(verts.len(), 5);
+        for vert in verts {
+            assert!(((vert - centre).length() - 10.0).abs() < 1e-5, "{vert:?}");
         }
+    }
 
-        return Some(Collision {
-            normal: best_axis,
-            penetration: min_overlap,
+    // a lone body with no gravity, so drag is the only force on it.
+    fn drifting_body(wind: Vec2<f32, WorldSpace>) -> FallingEverything {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.gravity = vec2(0.0, 0.0);
+        sim.state.drag = 1.0;
+        sim.set_wind(wind);
+        sim.spawn_rigidbody(
+            vec2(200.0, 150.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(50.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+        sim
+    }
+
+    #[test]
+    fn still_air_drag_stops_a_body() {
+        let mut sim = drifting_body(vec2(0.0, 0.0));
+        for _ in 0..300 {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        let speed = sim.objects[0].velocity.length();
+        assert!(speed < 0.01, "{speed}");
+    }
+
+    #[test]
+    fn matching_wind_has_no_drag() {
+        let mut sim = drifting_body(vec2(50.0, 0.0));
+        for _ in 0..10 {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        let velocity = sim.objects[0].velocity;
+        assert!((velocity - vec2(50.0, 0.0)).length() < 1e-4, "{velocity:?}");
+    }
+
+    #[test]
+    fn inertia_from_density() {
+        let square = RigidBody::from_density(
+            ConvexPolygon::square(vec2(0.0, 0.0), 10.0),
+            1.0,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+        assert!((square.mass - 100.0).abs() < 1e-3);
+        assert!((square.inertia - 100.0 * (100.0 + 100.0) / 12.0).abs() < 1e-2);
+
+        // equilateral, b² / 24 + h² / 18 with h = b * √3 / 2 is b² / 12.
+        let triangle = RigidBody::from_density(
+            ConvexPolygon::equilateral_triangle(vec2(0.0, 0.0), 10.0),
+            1.0,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+        let area = 3.0f32.sqrt() / 4.0 * 100.0;
+        assert!((triangle.mass - area).abs() < 1e-3);
+        assert!((triangle.inertia - area * 100.0 / 12.0).abs() < 1e-2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn scene_round_trip() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        for i in 0..5 {
+            sim.spawn_rigidbody(
+                vec2(40.0 + i as f32 * 50.0, 100.0 - i as f32 * 10.0),
+                ShapeVariant::Polygon,
+                BODY_DENSITY,
+                DEFAULT_FRICTION,
+                vec2(i as f32 * 3.0, 0.0),
+                vec2(0.0, 0.0),
+            );
+        }
+
+        let path = std::env::temp_dir().join("falling_everything_scene_round_trip.json");
+        sim.save_scene(&path).unwrap();
+        let mut loaded = FallingEverything::load_scene(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for _ in 0..10 {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+            loaded.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        assert_eq!(loaded.objects.len(), 5);
+        for (a, b) in sim.objects.iter().zip(&loaded.objects) {
+            assert!((a.object.centre - b.object.centre).length() < 1e-5);
+            assert!((a.velocity - b.velocity).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn hull_of_circle_points() {
+        let on_circle = [0.3f32, 1.7, 2.9, 4.1, 5.5].map(|angle| vec2(angle.cos(), angle.sin()));
+        let mut points = on_circle.to_vec();
+        points.push(vec2(0.1, 0.2)); // inside, not part of the hull.
+        let hull = ConvexPolygon::from_points(&points).world_verts();
+
+        assert!(hull.len() <= 5);
+        for vert in &hull {
+            assert!((vert.length() - 1.0).abs() < 1e-5, "{vert:?}");
+        }
+        // counter-clockwise, so every point is on the left of every edge.
+        for (i, &a) in hull.iter().enumerate() {
+            let b = hull[(i + 1) % hull.len()];
+            for &point in &points {
+                assert!((b - a).cross_product(point - a) >= -1e-5, "{point:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn body_leaving_the_world_is_destroyed() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.state.destroy_on_exit = true;
+        sim.state.drag = 0.0;
+        sim.gravity = vec2(0.0, 0.0);
+        sim.set_world_bounds(Aabb::new(vec2(100.0, 100.0), vec2(250.0, 200.0)));
+        sim.spawn_rigidbody(
+            vec2(200.0, 150.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(100.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+
+        // 50 units to the edge, a second is plenty.
+        let dt = Duration::from_secs_f64(PHYSICS_DT);
+        for _ in 0..(1.0 / PHYSICS_DT) as usize {
+            sim.physics_step(dt);
+        }
+        assert!(sim.objects.is_empty());
+        assert!(sim.prev_positions.is_empty());
+    }
+
+    #[test]
+    fn compound_of_two_squares() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        let square = ShapeType::Square { size: 20.0 };
+        let body = sim
+            .spawn_compound(
+                &[(square, vec2(90.0, 100.0)), (square, vec2(110.0, 100.0))],
+                2.0,
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0),
+            )
+            .unwrap();
+
+        assert!((body.object.centre - vec2(100.0, 100.0)).length() < 1e-4);
+        let bounds = body.bounds();
+        assert!(
+            (bounds.min - vec2(80.0, 90.0)).length() < 1e-4,
+            "{bounds:?}"
+        );
+        assert!(
+            (bounds.max - vec2(120.0, 110.0)).length() < 1e-4,
+            "{bounds:?}"
+        );
+    }
+
+    #[test]
+    fn torque_arc_sweeps_half_the_angular_velocity() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.spawn_rigidbody(
+            vec2(200.0, 150.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+        sim.objects[0].angular_velocity = f32::consts::FRAC_PI_4;
+        sim.draw_torque_arcs(1.0);
+
+        // π/4 rad/s sweeps π/8 from the body's forward direction (+x), blue as it's positive.
+        let colour_at = |x: i32, y: i32| {
+            let pos =
+                vec2::<i32, WorldSpace>(200 + x, 150 + y).to_texture_space(sim.world.camera_pos);
+            let index = 4 * (pos.y * sim.world.viewport_size.x as i32 + pos.x) as usize;
+            sim.world.viewport_texture[index..index + 4].to_vec()
+        };
+        let blue = vec![BLUE.r, BLUE.g, BLUE.b, BLUE.a];
+        assert_eq!(colour_at(10, 0), blue);
+        assert_eq!(colour_at(10, 3), blue);
+        // 0.42 rad & behind the forward direction are both outside the arc.
+        assert_ne!(colour_at(9, 4), blue);
+        assert_ne!(colour_at(10, -1), blue);
+    }
+
+    #[test]
+    fn body_colour_follows_speed() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        for (x, speed) in [(100.0, sim.state.max_velocity), (300.0, 0.0)] {
+            sim.spawn_rigidbody(
+                vec2(x, 150.0),
+                ShapeVariant::Polygon,
+                BODY_DENSITY,
+                DEFAULT_FRICTION,
+                vec2(speed, 0.0),
+                vec2(0.0, 0.0),
+            );
+        }
+        sim.update_body_colours();
+
+        assert_eq!(sim.objects[0].last_colour, heat_map(1.0));
+        assert_eq!(sim.objects[1].last_colour, heat_map(0.0));
+        assert_ne!(heat_map(1.0), heat_map(0.0));
+    }
+
+    #[test]
+    fn collision_flash_wears_off() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.gravity = vec2(0.0, 0.0);
+        for x in [200.0, 205.0] {
+            sim.spawn_rigidbody(
+                vec2(x, 150.0),
+                ShapeVariant::Polygon,
+                BODY_DENSITY,
+                DEFAULT_FRICTION,
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0),
+            );
+        }
+
+        let dt = Duration::from_secs_f64(PHYSICS_DT);
+        sim.physics_step(dt);
+        assert!(sim.objects.iter().all(|body| body.flash_frames > 0));
+
+        // parted, so nothing flashes them again.
+        sim.objects[1].object.translate(vec2(100.0, 0.0));
+        for body in &mut sim.objects {
+            body.velocity = vec2(0.0, 0.0);
+        }
+        for _ in 0..COLLISION_FLASH_FRAMES {
+            sim.physics_step(dt);
+        }
+        assert!(sim.objects.iter().all(|body| body.flash_frames == 0));
+    }
+
+    #[test]
+    fn circles_collide_with_circles_and_polygons() {
+        let circle = Circle {
+            centre: vec2(0.0, 0.0),
+            radius: 5.0,
+        };
+        let overlapping = Circle {
+            centre: vec2(8.0, 0.0),
+            radius: 5.0,
+        };
+        let collision = circle.does_collide(&overlapping).unwrap();
+        assert!((collision.penetration - 2.0).abs() < 1e-5);
+        assert!((collision.normal - vec2(1.0, 0.0)).length() < 1e-5);
+        let apart = Circle {
+            centre: vec2(11.0, 0.0),
+            radius: 5.0,
+        };
+        assert!(circle.does_collide(&apart).is_none());
+
+        // the square's left edge is at x = 4, 1 inside the circle.
+        let square = ConvexPolygon::square(vec2(9.0, 0.0), 10.0);
+        let collision = circle.does_collide(&square).unwrap();
+        assert!((collision.penetration - 1.0).abs() < 1e-5);
+        assert!((collision.normal - vec2(1.0, 0.0)).length() < 1e-5);
+        assert!(circle
+            .does_collide(&ConvexPolygon::square(vec2(11.0, 0.0), 10.0))
+            .is_none());
+    }
+
+    #[test]
+    fn body_dropped_on_the_floor_comes_to_rest() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        // at GRAVITY the 100 unit fall alone takes 4.5s.
+        sim.set_gravity(vec2(0.0, GRAVITY * 10.0));
+        // the viewport is 300 tall, an 18x18 square's bottom 100 above the floor.
+        let floor = 300.0 - STATIC_WALL_THICKNESS;
+        sim.spawn_rigidbody(
+            vec2(200.0, floor - 100.0 - 9.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+
+        for _ in 0..(5.0 / PHYSICS_DT) as usize {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        let body = &sim.objects[0];
+        assert!(body.velocity.length() < 1.0, "{:?}", body.velocity);
+        assert!((body.object.centre.y - (floor - 9.0)).abs() < 1.0);
+    }
+
+    #[test]
+    fn spring_oscillates_symmetrically() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.state.drag = 0.0;
+        sim.gravity = vec2(0.0, 0.0);
+        for x in [150.0, 250.0] {
+            sim.spawn_rigidbody(
+                vec2(x, 150.0),
+                ShapeVariant::Polygon,
+                BODY_DENSITY,
+                DEFAULT_FRICTION,
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0),
+            );
+        }
+        // stretched 20 past its rest length, undamped, ω = sqrt(2k / m) = 2 rad/s.
+        sim.joints.push(SpringJoint {
+            body_a: 0,
+            body_b: 1,
+            rest_length: 80.0,
+            stiffness: sim.objects[0].mass * 2.0,
+            damping: 0.0,
         });
+
+        let mut min_separation = f32::INFINITY;
+        for _ in 0..(4.0 / PHYSICS_DT) as usize {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+            let [a, b] = [0, 1].map(|i| sim.objects[i].object.centre);
+            assert!(((a + b) / 2.0 - vec2(200.0, 150.0)).length() < 1e-2);
+            let separation = (b - a).length();
+            assert!(separation < 100.5, "{separation}");
+            min_separation = min_separation.min(separation);
+        }
+        // through the rest length to about as far the other side.
+        assert!(min_separation < 65.0, "{min_separation}");
     }
-    // endregion
 
-    pub fn new(centre: Vec2<f32, WorldSpace>, size: f32) -> Self {
-        let half = size / 2.0;
-        let local_vertices = [
-            vec2(-half, -half),
-            vec2(half, -half),
-            vec2(half, half),
-            vec2(-half, half),
-        ];
-        Square {
-            local_vertices,
-            centre,
+    #[test]
+    fn fast_body_does_not_tunnel_through_a_wall() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.state.drag = 0.0;
+        sim.gravity = vec2(0.0, 0.0);
+        // 500 units a step, far more than the 12 thick right wall 191 away.
+        sim.spawn_rigidbody(
+            vec2(200.0, 150.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(500.0 / PHYSICS_DT as f32, 0.0),
+            vec2(0.0, 0.0),
+        );
+
+        sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        let x = sim.objects[0].object.centre.x;
+        assert!(x - 9.0 >= STATIC_WALL_THICKNESS - 0.5, "{x}");
+        assert!(x + 9.0 <= 400.0 - STATIC_WALL_THICKNESS + 0.5, "{x}");
+    }
+
+    fn slide_along_the_floor(friction_coeff: f32) -> f32 {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.state.drag = 0.0;
+        sim.set_gravity(vec2(0.0, GRAVITY * 10.0));
+        let floor = 300.0 - STATIC_WALL_THICKNESS;
+        sim.spawn_rigidbody(
+            vec2(100.0, floor - 9.5),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            friction_coeff,
+            vec2(50.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+
+        for _ in 0..(2.0 / PHYSICS_DT) as usize {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        sim.objects[0].velocity.x.abs()
+    }
+
+    #[test]
+    fn friction_slows_a_sliding_body() {
+        // nothing but friction acts along the floor, so without it the body keeps sliding.
+        let frictionless = slide_along_the_floor(0.0);
+        assert!((frictionless - 50.0).abs() < 1e-3, "{frictionless}");
+        let slowed = slide_along_the_floor(DEFAULT_FRICTION);
+        assert!(slowed < frictionless / 2.0, "{slowed}");
+    }
+
+    #[test]
+    fn default_gravity_accelerates_bodies_down() {
+        let mut sim = FallingEverything::new(vec2(800, 600), 2);
+        sim.state.is_running = true;
+        sim.state.drag = 0.0;
+        assert_eq!(sim.gravity, vec2(0.0, GRAVITY));
+        sim.spawn_rigidbody(
+            vec2(200.0, 150.0),
+            ShapeVariant::Polygon,
+            BODY_DENSITY,
+            DEFAULT_FRICTION,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+        );
+
+        // world y points down the screen.
+        let steps = 60;
+        for _ in 0..steps {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
         }
+        let body = &sim.objects[0];
+        let expected = GRAVITY * (steps as f64 * PHYSICS_DT) as f32;
+        assert!(
+            (body.velocity.y - expected).abs() < 1e-2 * expected,
+            "{:?}",
+            body.velocity
+        );
+        assert!(body.velocity.x.abs() < 1e-5);
+        assert!(body.object.centre.y > 150.0);
     }
 }
@@ -1,25 +1,39 @@
 use crate::{
-    frontend::{FrameData, Frontend},
+    demo::ScenarioPreset,
+    frontend::{FrameTimingStats, Frontend, TextureData},
     utils::{
-        canvas::Shape,
-        colour::Rgba,
+        colour::{heat_map, Rgba},
         consts::{
-            CAMERA_RESISTANCE, CAMERA_SPEED, DGRAY, EARTH_DENSITY, GRAV_CONST, GREEN,
-            INIT_DRAW_SIZE, MAX_DRAW_SIZE, MOUSE_DRAWBACK_MULTIPLIER, PHYSICS_MULTIPLIER,
-            PHYSICS_RESISTANCE, RED, TARGET_FPS, WHITE,
+            BLACK_HOLE_COLOUR, BLACK_HOLE_MASS, BLACK_HOLE_RADIUS, BLUE, CAMERA_FIT_MARGIN,
+            CAMERA_FIT_MS, CAMERA_RESISTANCE, CAMERA_SPEED, COMET_COLOUR, COMET_DENSITY_RATIO,
+            COMET_RADIUS, COMET_TRAIL_LENGTH, CURSOR_ATTRACT_STRENGTH, DISTANCE_SCALE,
+            EARTH_DENSITY_SI, EVENT_HORIZON_COLOUR, EXPLOSION_MAX_CHARGE_MS, EXPLOSION_STRENGTH,
+            GRAVITY_TUNER_STEP, GRAV_CONST, GRAV_SOFTENING, GRAY, GREEN, INIT_DRAW_SIZE,
+            INIT_SUB_STEPS, INSPECT_ARROW_SCALE, INSPECT_FORCE_SCALE, LENSING_MAX_DISPLACEMENT,
+            LENSING_RADIUS_SCALE, MAX_DRAW_SIZE, MAX_PARTICLES, MAX_PARTICLES_STEP, MAX_SUB_STEPS,
+            MIN_STEP_SAFETY, MOUSE_DRAWBACK_MULTIPLIER, ORBIT_PREDICTION_STEPS, ORBIT_SAMPLE_STEPS,
+            PHYSICS_DT, PHYSICS_MULTIPLIER, PHYSICS_MULTIPLIER_STEP, PHYSICS_RESISTANCE,
+            POTENTIAL_GRID_STEP, POTENTIAL_MAX_SAMPLES, RED, SIMULATION_STATE_PATH, SMALL_VALUE,
+            SOFTENING_RING_COLOUR, SPEED_STATS_INTERVAL, STAR_GLOW_COLOUR, STAR_GLOW_SCALE,
+            STAR_RADIUS, SUN_MASS, SUN_TEMPERATURE, TARGET_FPS, VELOCITY_ARROW_SCALE, WHITE,
+            YELLOW,
         },
+        font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH},
         input_data::InputData,
         sync_cell::SyncCell,
-        vec2::{fmt_limited_precision, vec2, RenderSpace, Scale, ScreenSpace, Vec2, WorldSpace},
+        vec2::{fmt_limited_precision, vec2, TextureSpace, Vec2, WindowSpace, WorldSpace},
+        world::{subdivide_bezier, Shape},
     },
 };
 use core::f64;
 use educe::Educe;
-use log::{info, trace};
+use log::{error, info, trace, warn};
 use num::pow::Pow;
 use rayon::{prelude::*, vec};
 use std::{
-    f32::EPSILON,
+    cell::Cell,
+    collections::VecDeque,
+    fmt::Write as _,
     mem::transmute,
     ops::{Add, Div, Mul, Sub},
     time::{Duration, Instant},
@@ -28,24 +42,363 @@ use winit::keyboard::KeyCode;
 
 #[derive(Educe, Clone, Copy)]
 #[educe(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Particle {
     #[educe(Debug(method(fmt_limited_precision)))]
     pos: Vec2<f64, WorldSpace>,
     #[educe(Debug(method(fmt_limited_precision)))]
     vel: Vec2<f64, WorldSpace>,
     #[educe(Debug(method(fmt_limited_precision)))]
-    acc: Vec2<f64, WorldSpace>,
-    #[educe(Debug(method(fmt_limited_precision)))]
     force: Vec2<f64, WorldSpace>,
+    // last step's force, for the velocity Verlet kick.
+    #[educe(Debug(method(fmt_limited_precision)))]
+    prev_force: Vec2<f64, WorldSpace>,
     #[educe(Debug(method(fmt_limited_precision)))]
     mass: f64,
     #[educe(Debug(method(fmt_limited_precision)))]
     radius: f64,
+    kind: ParticleType,
+}
+
+// Selected with 1-4, each has preset spawn properties & its own look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum ParticleType {
+    Comet,     // light & small, always drawn with a short trail.
+    Planet,    // speed coloured, mass from density like before.
+    Star,      // SUN_MASS at STAR_RADIUS, yellow-white with a glow.
+    BlackHole, // BLACK_HOLE_MASS regardless of size, dark with an event horizon ring.
+}
+
+impl ParticleType {
+    const fn radius(self) -> f64 {
+        match self {
+            Self::Comet => COMET_RADIUS,
+            Self::Planet => INIT_DRAW_SIZE as f64,
+            Self::Star => STAR_RADIUS,
+            Self::BlackHole => BLACK_HOLE_RADIUS,
+        }
+    }
+
+    fn mass(self, radius: f64, density: f64) -> f64 {
+        let volume = f64::consts::PI * 4.0 / 3.0 * radius.pow(3);
+        match self {
+            Self::Comet => volume * density * COMET_DENSITY_RATIO,
+            Self::Planet => volume * density,
+            Self::Star => SUN_MASS * (radius / STAR_RADIUS).pow(3),
+            Self::BlackHole => BLACK_HOLE_MASS,
+        }
+    }
+
+    // None for planets, which are coloured by speed.
+    fn colour(self) -> Option<Rgba> {
+        match self {
+            Self::Comet => Some(COMET_COLOUR),
+            Self::Planet => None,
+            Self::Star => Some(stellar_colour(1.0)),
+            Self::BlackHole => Some(BLACK_HOLE_COLOUR),
+        }
+    }
+}
+
+// Runtime tunable versions of the consts, see the Ctrl+G tuner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct GravityParams {
+    g: f64,
+    distance_scale: f64, // pixel to meters, only affects particles spawned afterwards.
+    earth_density: f64,  // kg/m^3, before distance_scale.
+    softening: f64,      // world units, see GRAV_SOFTENING.
+    physics_multiplier: f64, // scales g, Shift+G/Shift+F jump between galaxy & solar system scales.
+}
+
+impl GravityParams {
+    // density of spawned particles, in sim units.
+    fn density(&self) -> f64 {
+        self.earth_density * self.distance_scale
+    }
+
+    // g as used by the force law.
+    fn scaled_g(&self) -> f64 {
+        self.g * self.physics_multiplier
+    }
+
+    fn get_mut(&mut self, param: GravityParam) -> &mut f64 {
+        match param {
+            GravityParam::G => &mut self.g,
+            GravityParam::DistanceScale => &mut self.distance_scale,
+            GravityParam::EarthDensity => &mut self.earth_density,
+            GravityParam::Softening => &mut self.softening,
+        }
+    }
+}
+
+impl Default for GravityParams {
+    fn default() -> Self {
+        Self {
+            g: GRAV_CONST,
+            distance_scale: DISTANCE_SCALE,
+            earth_density: EARTH_DENSITY_SI,
+            softening: GRAV_SOFTENING,
+            physics_multiplier: PHYSICS_MULTIPLIER,
+        }
+    }
+}
+
+// The GravityParams field adjusted by the tuner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GravityParam {
+    G,
+    DistanceScale,
+    EarthDensity,
+    Softening,
+}
+
+impl GravityParam {
+    const fn next(self) -> Self {
+        match self {
+            Self::G => Self::DistanceScale,
+            Self::DistanceScale => Self::EarthDensity,
+            Self::EarthDensity => Self::Softening,
+            Self::Softening => Self::G,
+        }
+    }
+
+    const fn prev(self) -> Self {
+        match self {
+            Self::G => Self::Softening,
+            Self::DistanceScale => Self::G,
+            Self::EarthDensity => Self::DistanceScale,
+            Self::Softening => Self::EarthDensity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SimulationStats {
+    n_particles: usize,
+    total_mass: f64,
+    total_kinetic_energy: f64,
+    centre_of_mass: Vec2<f64, WorldSpace>,
+    net_momentum: Vec2<f64, WorldSpace>,
+    max_speed: f64,
+    min_speed: f64,
+}
+
+// Everything needed to restore a Simulation, see Simulation::serialize.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedSimulation {
+    params: GravityParams,
+    max_particles: usize,
+    particles: Vec<Particle>,
+}
+
+// Layouts spawned by the Alt+1-3 presets, positions are relative to the centre of the view.
+mod presets {
+    // logarithmic spiral arms, r = GALAXY_INNER_RADIUS * e^(GALAXY_PITCH * θ).
+    pub const GALAXY_PARTICLES: usize = 300;
+    pub const GALAXY_ARMS: usize = 2;
+    pub const GALAXY_INNER_RADIUS: f64 = 40.0;
+    pub const GALAXY_OUTER_RADIUS: f64 = 400.0;
+    pub const GALAXY_PITCH: f64 = 0.25;
+    pub const GALAXY_PARTICLE_RADIUS: f64 = 2.0;
+    pub const GALAXY_CORE_RADIUS: f64 = 12.0; // a black hole, so it sets BLACK_HOLE_MASS.
+
+    pub const BINARY_SEPARATION: f64 = 200.0;
+    pub const BINARY_STAR_RADIUS: f64 = 16.0;
+
+    pub const SOLAR_ORBITS: [f64; 4] = [80.0, 140.0, 210.0, 300.0];
+    pub const SOLAR_PLANET_RADIUS: f64 = 5.0;
+}
+
+// Energy totals after a physics step, a drifting total means the integrator is leaking energy.
+#[cfg(feature = "telemetry")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SimTelemetry {
+    kinetic: f64,
+    potential: f64, // pairwise, same 1 / 1.5 factor as apply_physics, ignores softening.
+    total: f64,
+}
+
+#[cfg(feature = "telemetry")]
+impl SimTelemetry {
+    fn measure(particles: &ParticlePool, g: f64) -> Self {
+        let kinetic = particles.iter_active().map(Particle::kinetic_energy).sum();
+        let potential = particles
+            .iter_active()
+            .enumerate()
+            .flat_map(|(i, p1)| particles.iter_active().skip(i + 1).map(move |p2| (p1, p2)))
+            .map(|(p1, p2)| {
+                let r = (p2.pos - p1.pos).length().max(SMALL_VALUE);
+                -g * p1.mass * p2.mass / (r * 1.5)
+            })
+            .sum();
+        Self {
+            kinetic,
+            potential,
+            total: kinetic + potential,
+        }
+    }
+
+    // Relative to initial, as a percentage.
+    fn drift(&self, initial: &Self) -> f64 {
+        if initial.total.abs() < SMALL_VALUE {
+            return 0.0;
+        }
+        (self.total - initial.total) / initial.total.abs() * 100.0
+    }
+}
+
+// Slots are reused through free_list, so spawning doesn't allocate once the pool has grown to
+// max_particles, which it is preallocated to.
+#[derive(Debug, Clone, Default)]
+struct ParticlePool {
+    slots: Vec<SyncCell<Option<Particle>>>,
+    free_list: Vec<usize>,
+    // active slot indices in spawn order, the front has existed the longest.
+    spawn_order: VecDeque<usize>,
+}
+
+impl ParticlePool {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free_list: Vec::with_capacity(capacity),
+            spawn_order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    fn alloc(&mut self, particle: Particle) -> usize {
+        let idx = match self.free_list.pop() {
+            Some(idx) => {
+                *self.slots[idx].get_mut() = Some(particle);
+                idx
+            }
+            None => {
+                self.slots.push(SyncCell::new(Some(particle)));
+                self.slots.len() - 1
+            }
+        };
+        self.spawn_order.push_back(idx);
+        idx
+    }
+
+    fn free(&mut self, idx: usize) {
+        if self.slots[idx].get_mut().take().is_none() {
+            return;
+        }
+        self.free_list.push(idx);
+        if let Some(order) = self.spawn_order.iter().position(|&i| i == idx) {
+            self.spawn_order.remove(order);
+        }
+    }
+
+    // The freed slot, None if the pool was empty.
+    fn free_oldest(&mut self) -> Option<usize> {
+        let idx = *self.spawn_order.front()?;
+        self.free(idx);
+        Some(idx)
+    }
+
+    fn reserve(&mut self, capacity: usize) {
+        self.slots
+            .reserve(capacity.saturating_sub(self.slots.len()));
+        self.free_list
+            .reserve(capacity.saturating_sub(self.free_list.len()));
+        self.spawn_order
+            .reserve(capacity.saturating_sub(self.spawn_order.len()));
+    }
+
+    // Vec::clear keeps the capacity, refilling up to max_particles still won't allocate.
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.free_list.clear();
+        self.spawn_order.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.spawn_order.len()
+    }
+
+    // None if idx was freed.
+    fn get(&self, idx: usize) -> Option<&Particle> {
+        self.slots.get(idx)?.get().as_ref()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.spawn_order.is_empty()
+    }
+
+    fn iter_active(&self) -> impl Iterator<Item = &Particle> {
+        self.slots.iter().filter_map(|slot| slot.get().as_ref())
+    }
+
+    // SyncCell hands out &mut from &self, callers must not alias a particle.
+    fn iter_active_mut(&self) -> impl Iterator<Item = &mut Particle> {
+        self.slots.iter().filter_map(|slot| slot.get_mut().as_mut())
+    }
+
+    fn par_iter_active(&self) -> impl ParallelIterator<Item = &Particle> {
+        self.slots.par_iter().filter_map(|slot| slot.get().as_ref())
+    }
+
+    // Gravitational potential, -Σ G*m/r. r is clamped to each radius, avoids the singularity.
+    fn potential_at(&self, pos: Vec2<f64, WorldSpace>, g: f64) -> f64 {
+        self.iter_active()
+            .map(|p| {
+                let r = (p.pos - pos).length().max(p.radius);
+                -g * p.mass / r
+            })
+            .sum()
+    }
 }
 
 #[derive(Debug, Clone)]
 struct Simulation {
-    particles: Vec<SyncCell<Particle>>,
+    particles: ParticlePool,
+    max_particles: usize, // spawning past this recycles the oldest particle.
+    params: GravityParams,
+    // None once particles change, recomputed on the next get_stats.
+    cached_stats: Cell<Option<SimulationStats>>,
+    // (latest, at the last reset), None until the first update.
+    #[cfg(feature = "telemetry")]
+    telemetry: Option<(SimTelemetry, SimTelemetry)>,
+}
+
+// How Simulation::update advances particles, cycled on KeyI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntegrationMethod {
+    Euler,       // explicit, cheapest but orbits spiral as energy drifts.
+    Verlet,      // velocity Verlet, symplectic so energy error stays bounded.
+    RungeKutta4, // 4th order, 4 force evaluations per step, collisions only on the first.
+}
+
+impl IntegrationMethod {
+    const fn next(self) -> Self {
+        match self {
+            Self::Euler => Self::Verlet,
+            Self::Verlet => Self::RungeKutta4,
+            Self::RungeKutta4 => Self::Euler,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VizMode {
+    Off,
+    PotentialField, // background shaded by gravitational potential
+    KineticEnergy,  // particles coloured by kinetic energy rather than speed
+}
+
+impl VizMode {
+    const fn next(self) -> Self {
+        match self {
+            Self::Off => Self::PotentialField,
+            Self::PotentialField => Self::KineticEnergy,
+            Self::KineticEnergy => Self::Off,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -53,10 +406,40 @@ struct FrontendState {
     frame: usize,
     draw_size: i32,
     draw_shape: Shape,
-    scale: Scale<i32, ScreenSpace, RenderSpace>,
+    texture_scale: u32,
     running: bool,
     step_sim: bool,
-    mouse: Vec2<f64, ScreenSpace>,
+    mouse: Vec2<f64, WindowSpace>,
+    drag_start: Option<Vec2<f64, WindowSpace>>, // where the current drag was pressed
+    max_speed: f64, // can be stale by up to SPEED_STATS_INTERVAL frames.
+    explosion_charge: Option<Duration>, // how long KeyE has been held for.
+    viz_mode: VizMode,
+    // Some while the Ctrl+G tuner is open, arrow keys then adjust this instead.
+    gravity_tuner: Option<GravityParam>,
+    debug_stats_visible: bool, // toggled on F3
+    attract_mode: bool,        // particles are pulled towards the cursor, toggled on Alt+A
+    spawn_type: ParticleType,  // selected on 1-4
+    softening_visible: bool,   // softening radius rings, toggled on Ctrl+S
+    lensing: bool,             // black holes distort the pixels around them, toggled on KeyL
+    integration: IntegrationMethod,
+    sub_steps: u32, // physics updates per frame, splitting delta_time between them.
+    // sub steps actually run last frame, raised above sub_steps when particles are fast.
+    adaptive_sub_steps: u32,
+    velocity_arrows: bool,    // toggled on KeyV
+    merge_on_collision: bool, // colliding particles combine instead of bouncing, toggled on KeyM
+    // pool slot shown in the inspect overlay, Shift+click selects/deselects.
+    selected_particle: Option<usize>,
+    sim_speed: f32, // App's multiplier on delta time, shown in the stats
+}
+
+// Smooths the jump from fit_camera_to_particles, camera & zoom are lerped over CAMERA_FIT_MS.
+#[derive(Debug, Clone, Copy)]
+struct CameraTransition {
+    from: Vec2<f64, WorldSpace>,
+    to: Vec2<f64, WorldSpace>,
+    from_scale: u32,
+    to_scale: u32,
+    start: Instant,
 }
 
 #[derive(Educe, Clone)]
@@ -66,10 +449,11 @@ pub struct GravitySim {
     #[educe(Debug(ignore))]
     prev_state: FrontendState,
 
-    window_size: Vec2<i32, ScreenSpace>,
-    sim_size: Vec2<i32, RenderSpace>,
+    window_size: Vec2<u32, WindowSpace>,
+    sim_size: Vec2<i32, TextureSpace>,
     camera: Vec2<f64, WorldSpace>, // describes the top left of the viewport.
     camera_vel: Vec2<f64, WorldSpace>,
+    camera_transition: Option<CameraTransition>,
 
     #[educe(Debug(ignore))]
     bufs: [Vec<SyncCell<u8>>; 2],
@@ -82,33 +466,28 @@ pub struct GravitySim {
 
 impl Frontend for GravitySim {
     // region: Utility
-    fn get_frame_data(&self) -> FrameData {
+    fn get_texture_data(&self) -> TextureData {
         let buf = &self.bufs[self.front_buffer];
         let buf_slice = unsafe { std::slice::from_raw_parts(buf.as_ptr().cast(), buf.len()) };
-        FrameData {
-            buf: buf_slice,
-            size: self.sim_size.cast(),
-            frame: self.state.frame,
+        TextureData {
+            texture_buffer: buf_slice,
+            texture_size: self.sim_size.cast(),
+            dirty_region: None,
         }
     }
 
-    fn get_scale(&self) -> u32 {
-        self.state.scale.get() as u32
+    fn get_texture_scale(&self) -> u32 {
+        self.state.texture_scale
     }
     // endregion
     // region: Size Manipultion
-    fn resize_sim(&mut self, window_size: Vec2<u32, ScreenSpace>) {
-        optick::event!("GravitySim::resize_sim");
-
-        let window_size = window_size.cast();
-        let new_sim_size = window_size.scale(self.state.scale);
-
-        assert!(
-            new_sim_size.x == window_size.x / self.state.scale.get(),
-            "{new_sim_size:?} != {window_size:?} / {}",
-            self.state.scale.get()
-        );
+    fn resize_texture(&mut self, window_size: Vec2<u32, WindowSpace>) {
+        optick::event!("GravitySim::resize_texture");
 
+        self.window_size = window_size;
+        let new_sim_size = window_size
+            .to_texture_space(self.state.texture_scale)
+            .cast::<i32>();
         if new_sim_size == self.sim_size {
             trace!("Sim size unchanged, skipping resize. {new_sim_size:?}");
             return;
@@ -123,31 +502,93 @@ impl Frontend for GravitySim {
         }
 
         trace!(
-            "Resizing sim to: {new_sim_size:?} | {window_size:?} | scale: {:?} | {buf_size}",
-            self.state.scale
+            "Resizing sim to: {new_sim_size:?} | {window_size:?} | scale: {} | {buf_size}",
+            self.state.texture_scale
         );
 
-        self.window_size = window_size;
         self.sim_size = new_sim_size;
         self.bufs = [new_buf, new_buf_clone];
         // don't change particle stuff.
     }
 
-    fn rescale_sim(&mut self, new_scale: u32) {
-        self.state.scale = Scale::new(new_scale as i32);
-        self.resize_sim(self.window_size.cast::<u32>());
+    fn rescale_texture(&mut self, scale: u32) {
+        self.state.texture_scale = scale;
+        self.resize_texture(self.window_size);
     }
     // endregion
     // region: Update
-    fn update(&mut self, inputs: &mut InputData, delta_time: Duration) {
+    fn update(&mut self, inputs: &mut InputData, frame_timing: FrameTimingStats) {
         optick::event!("GravitySim::update");
 
+        self.state.sim_speed = frame_timing.sim_speed;
+
         self.handle_input_state(inputs);
+        // inputs may have spawned, cleared or exploded particles.
+        self.simulation.invalidate_stats();
+
+        if self.state.frame % SPEED_STATS_INTERVAL == 0 {
+            self.state.max_speed = self.compute_speed_stats().1;
+        }
+
+        if self.state.frame % TARGET_FPS as usize == 0 {
+            trace!("Particles: {}", self.simulation.get_particles().len());
+            #[cfg(feature = "telemetry")]
+            if let Some((latest, initial)) = self.simulation.telemetry {
+                info!(
+                    "Kinetic: {:.3e} | Potential: {:.3e} | Total: {:.3e} ({:+.3}%)",
+                    latest.kinetic,
+                    latest.potential,
+                    latest.total,
+                    latest.drift(&initial)
+                );
+            }
+        }
+
+        self.state.frame += 1;
+    }
+
+    fn physics_step(&mut self, dt: Duration) {
+        if !(self.state.running || self.state.step_sim) {
+            return;
+        }
+        self.state.step_sim = false;
+
+        if self.state.attract_mode {
+            let mouse_world = self
+                .state
+                .mouse
+                .to_texture_space(self.state.texture_scale)
+                .cast_unit()
+                .add(self.camera);
+            self.simulation
+                .update_cursor_attract(mouse_world, dt.as_secs_f64());
+        }
+        // smaller steps catch fast collisions that a whole step would step over.
+        let sub_steps = self.adaptive_sub_steps(dt.as_secs_f64());
+        if sub_steps > self.state.sub_steps && self.state.adaptive_sub_steps <= self.state.sub_steps
+        {
+            warn!("Fast particles, sub stepping x{sub_steps}");
+        }
+        self.state.adaptive_sub_steps = sub_steps;
+
+        let sub_dt = dt.as_secs_f64() / f64::from(sub_steps);
+        for _ in 0..sub_steps {
+            self.simulation.update(
+                sub_dt,
+                self.state.integration,
+                self.state.merge_on_collision,
+            );
+        }
+    }
+
+    // Particles only keep their latest position, so alpha is unused & the last step is drawn.
+    fn render_interpolated(&mut self, _alpha: f64) {
+        optick::event!("GravitySim::render");
 
         self.clear_buffer(self.front_buffer, 44);
 
-        if self.state.running || self.state.step_sim {
-            self.simulation.update(delta_time.as_secs_f64());
+        if self.state.viz_mode == VizMode::PotentialField {
+            self.render_potential_field();
         }
 
         Self::render_particles(
@@ -155,22 +596,157 @@ impl Frontend for GravitySim {
             self.simulation.get_particles(),
             self.sim_size,
             self.camera,
+            self.state.max_speed,
+            self.state.viz_mode,
+            self.state.velocity_arrows,
         );
 
-        self.handle_input_renders(inputs);
+        if self.state.lensing {
+            self.render_black_hole_lensing();
+        }
 
-        if self.state.frame % TARGET_FPS as usize == 0 {
-            trace!("Particles: {}", self.simulation.get_particles().len());
+        self.handle_input_renders();
+        if self.state.softening_visible {
+            self.render_softening_rings();
+        }
+        self.render_inspect_overlay();
+
+        if self.state.debug_stats_visible {
+            self.render_stats();
         }
 
         self.prev_state = self.state;
-        self.state.step_sim = false;
-        self.state.frame += 1;
 
         //TODO(TOM): sort out & use for multiple frames in flight.
         // self.front_buffer = (self.front_buffer + 1) % 2;
     }
     // endregion
+
+    fn load_scenario(&mut self, preset: ScenarioPreset) {
+        self.state.selected_particle = None;
+        match preset {
+            ScenarioPreset::SolarSystem => self.spawn_solar_system(),
+        }
+    }
+
+    fn explode_at(&mut self, pos: Vec2<f64, WindowSpace>) {
+        self.explode(pos, Duration::from_millis(EXPLOSION_MAX_CHARGE_MS));
+    }
+
+    fn inspector_text(&self) -> String {
+        let particles = self.simulation.get_particles();
+        let mut text = format!(
+            "Particles: {} | Running: {}\n",
+            particles.len(),
+            self.state.running
+        );
+        for (i, p) in particles.iter_active().enumerate() {
+            writeln!(
+                text,
+                "{i}: {:?} pos {} vel {} mass {:.3e}",
+                p.kind, p.pos, p.vel, p.mass
+            )
+            .unwrap();
+        }
+        text
+    }
+
+    // The whole buffer is redrawn every frame.
+    fn clear_colour(&self) -> Option<Rgba> {
+        Some(GRAY)
+    }
+
+    fn help_text() -> &'static [(KeyCode, &'static str)] {
+        &[
+            (KeyCode::Space, "Toggle simulation"),
+            (KeyCode::ArrowRight, "Step simulation"),
+            (KeyCode::KeyW, "Camera up"),
+            (KeyCode::KeyA, "Camera left"),
+            (KeyCode::KeyS, "Camera down"),
+            (KeyCode::KeyD, "Camera right"),
+            (KeyCode::KeyF, "Fit camera to particles"),
+            (KeyCode::ShiftLeft, "+G/F physics multiplier x10 / /10"),
+            (KeyCode::Home, "Reset physics multiplier"),
+            (KeyCode::KeyE, "Hold & release to explode"),
+            (KeyCode::KeyC, "Clear particles"),
+            (KeyCode::KeyR, "Reset particles"),
+            (KeyCode::Tab, "Cycle brush shape"),
+            (KeyCode::Digit1, "Spawn comets"),
+            (KeyCode::Digit2, "Spawn planets"),
+            (KeyCode::Digit3, "Spawn stars"),
+            (KeyCode::Digit4, "Spawn black holes"),
+            (KeyCode::F3, "Toggle stats"),
+            (KeyCode::KeyL, "Toggle black hole lensing"),
+            (KeyCode::KeyI, "Cycle integration method"),
+            (KeyCode::KeyV, "Toggle velocity arrows"),
+            (KeyCode::KeyM, "Toggle merge on collision"),
+            (KeyCode::F5, "Save simulation"),
+            (KeyCode::F9, "Load simulation"),
+            (KeyCode::AltLeft, "+1/2/3 galaxy/binary/solar presets"),
+            (KeyCode::BracketLeft, "Fewer sub steps"),
+            (KeyCode::BracketRight, "More sub steps"),
+            (KeyCode::ShiftLeft, "+Click inspect particle"),
+            (KeyCode::AltLeft, "+A cursor attraction"),
+            (KeyCode::ControlLeft, "+G gravity tuner"),
+            (KeyCode::ControlLeft, "+P cycle visualisation"),
+            (KeyCode::ControlLeft, "+S softening rings"),
+        ]
+    }
+
+    fn new(window_size: Vec2<u32, WindowSpace>, scale: u32) -> Self {
+        let sim_size = window_size.to_texture_space(scale).cast::<i32>();
+        let buf_size = (sim_size.x * sim_size.y * 4) as usize;
+
+        let mut buf = Vec::with_capacity(buf_size);
+        let mut buf_clone = Vec::with_capacity(buf_size);
+        for _ in 0..buf_size {
+            buf.push(SyncCell::new(44));
+            buf_clone.push(SyncCell::new(44));
+        }
+
+        let simulation = Simulation::new();
+
+        let state = FrontendState {
+            frame: 0,
+            draw_size: INIT_DRAW_SIZE,
+            draw_shape: Shape::CircleFill,
+            texture_scale: scale,
+            running: false,
+            step_sim: false,
+            mouse: vec2(0.0, 0.0),
+            drag_start: None,
+            max_speed: 0.0,
+            explosion_charge: None,
+            viz_mode: VizMode::Off,
+            gravity_tuner: None,
+            debug_stats_visible: false,
+            attract_mode: false,
+            softening_visible: false,
+            lensing: true,
+            integration: IntegrationMethod::Verlet,
+            sub_steps: INIT_SUB_STEPS,
+            adaptive_sub_steps: INIT_SUB_STEPS,
+            velocity_arrows: false,
+            merge_on_collision: false,
+            spawn_type: ParticleType::Planet,
+            selected_particle: None,
+            sim_speed: 1.0,
+        };
+
+        Self {
+            state,
+            prev_state: state,
+
+            window_size,
+            sim_size,
+            camera: vec2(0.0, 0.0),
+            camera_vel: vec2(0.0, 0.0),
+            camera_transition: None,
+            bufs: [buf, buf_clone],
+            front_buffer: 0,
+            simulation,
+        }
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////
@@ -183,7 +759,7 @@ impl GravitySim {
         *buf[index + 3].get_mut() = col.a;
     }
 
-    fn write_to_buf(&mut self, pos: Vec2<i32, RenderSpace>, col: Rgba) {
+    fn write_to_buf(&mut self, pos: Vec2<i32, TextureSpace>, col: Rgba) {
         let index = 4 * (pos.y * self.sim_size.x + pos.x) as usize;
         let buf = &mut self.bufs[self.front_buffer];
         Self::write_colour(index, buf, col);
@@ -200,17 +776,43 @@ impl GravitySim {
     // endregion
     // TODO(TOM): re-write this to occur outside the game loop (restricted to 60/120fps)
     // region: Input Handling
+    // The recycled slot now holds the new particle, the selection mustn't follow it.
+    fn deselect_recycled(&mut self, recycled: Option<usize>) {
+        if recycled.is_some() && recycled == self.state.selected_particle {
+            self.state.selected_particle = None;
+        }
+    }
+
+    // Charge is how long KeyE was held, capped at EXPLOSION_MAX_CHARGE_MS.
+    fn explode(&mut self, pos: Vec2<f64, WindowSpace>, charge: Duration) {
+        let centre = pos
+            .to_texture_space(self.state.texture_scale)
+            .cast_unit()
+            .add(self.camera);
+        let charge = charge.min(Duration::from_millis(EXPLOSION_MAX_CHARGE_MS));
+        self.simulation
+            .explode(centre, EXPLOSION_STRENGTH * charge.as_secs_f64());
+    }
+
     fn handle_input_state(&mut self, inputs: &mut InputData) {
         optick::event!("Handling Input State");
 
         let shift_modifier = inputs.is_held(KeyCode::ShiftLeft) as i32;
         let pressed = inputs.mouse_pressed.pos;
         let released = inputs.mouse_released.pos;
-        let mouse_pos_world = pressed.scale(self.state.scale).cast_unit().add(self.camera);
-        if inputs.was_mouse_dragging() {
+        let mouse_pos_world = pressed
+            .to_texture_space(self.state.texture_scale)
+            .cast_unit()
+            .add(self.camera);
+        if shift_modifier == 1 && inputs.was_mouse_pressed() && !inputs.was_mouse_dragging() {
+            // Shift+click inspects the particle under the cursor, or deselects on empty space.
+            self.state.selected_particle = self.simulation.particle_at(mouse_pos_world);
+            info!("Selected particle: {:?}", self.state.selected_particle);
+        } else if inputs.was_mouse_dragging() {
             // Draws particle at initial position, give it velocity based on drag distance.
-            let pressed_world = pressed.scale(self.state.scale).cast_unit().add(self.camera);
-            let game_pos_delta = pressed.sub(released).scale(self.state.scale);
+            let game_pos_delta = pressed
+                .sub(released)
+                .to_texture_space(self.state.texture_scale);
 
             // TODO(TOM): vary with current scale factor.
             let velocity = game_pos_delta
@@ -218,14 +820,66 @@ impl GravitySim {
                 .mul(MOUSE_DRAWBACK_MULTIPLIER)
                 .cast_unit();
 
-            self.simulation
-                .spawn_particle(mouse_pos_world, velocity, self.state.draw_size as f64);
+            let recycled = self.simulation.spawn_particle(
+                self.state.spawn_type,
+                mouse_pos_world,
+                velocity,
+                self.state.draw_size as f64,
+            );
+            self.deselect_recycled(recycled);
         } else if inputs.was_mouse_pressed() {
-            self.simulation.spawn_particle(
+            let recycled = self.simulation.spawn_particle(
+                self.state.spawn_type,
                 mouse_pos_world,
                 vec2(0.0, 0.0),
                 self.state.draw_size as f64,
             );
+            self.deselect_recycled(recycled);
+        }
+
+        // Right click removes the particle under the cursor, if any.
+        if inputs.right_click.state {
+            let pos = inputs
+                .right_click
+                .pos
+                .to_texture_space(self.state.texture_scale)
+                .cast_unit()
+                .add(self.camera);
+            if let Some(idx) = self.simulation.particle_at(pos) {
+                self.simulation.remove_particle(idx);
+                if self.state.selected_particle == Some(idx) {
+                    self.state.selected_particle = None;
+                }
+                trace!("Removed particle {idx}");
+            }
+        }
+
+        // Select spawn type on 1-4, resets the draw size to the type's preset radius.
+        let spawn_keys = [
+            (KeyCode::Digit1, ParticleType::Comet),
+            (KeyCode::Digit2, ParticleType::Planet),
+            (KeyCode::Digit3, ParticleType::Star),
+            (KeyCode::Digit4, ParticleType::BlackHole),
+        ];
+        let alt_held = inputs.is_held(KeyCode::AltLeft);
+        if !inputs.is_held(KeyCode::ControlLeft) && !inputs.is_held(KeyCode::ShiftLeft) && !alt_held
+        {
+            if let Some(&(_, kind)) = spawn_keys.iter().find(|(key, _)| inputs.is_pressed(*key)) {
+                self.state.spawn_type = kind;
+                self.state.draw_size = kind.radius() as i32;
+                info!("Spawn type: {:?}", kind);
+            }
+        }
+
+        // Presets on Alt+1-3, replacing every particle.
+        if alt_held {
+            if inputs.is_pressed(KeyCode::Digit1) {
+                self.spawn_galaxy_disc();
+            } else if inputs.is_pressed(KeyCode::Digit2) {
+                self.spawn_binary_star();
+            } else if inputs.is_pressed(KeyCode::Digit3) {
+                self.spawn_solar_system();
+            }
         }
 
         // Toggle simulation on KeySpace
@@ -233,7 +887,89 @@ impl GravitySim {
             self.state.running = !self.state.running;
             info!("Sim running: {}", self.state.running);
         }
-        self.state.step_sim = inputs.is_pressed(KeyCode::ArrowRight);
+        self.handle_gravity_tuner(inputs);
+        #[cfg(feature = "serde")]
+        self.handle_state_inputs(inputs);
+        let tuning = self.state.gravity_tuner.is_some();
+        // kept until the next physics_step, which may not come this frame.
+        self.state.step_sim |= inputs.is_pressed(KeyCode::ArrowRight) && !tuning;
+
+        // Explode on KeyE release, the longer it's held the stronger the explosion.
+        match inputs.held_duration(KeyCode::KeyE) {
+            Some(held) => self.state.explosion_charge = Some(held),
+            None => {
+                if let Some(charge) = self.state.explosion_charge.take() {
+                    self.explode(inputs.mouse_pos, charge);
+                }
+            }
+        }
+
+        // Particle limit on Ctrl+PageUp/PageDown
+        if inputs.is_held(KeyCode::ControlLeft) {
+            let max_particles = self.simulation.max_particles;
+            if inputs.is_pressed(KeyCode::PageUp) {
+                self.simulation
+                    .set_max_particles(max_particles + MAX_PARTICLES_STEP);
+            } else if inputs.is_pressed(KeyCode::PageDown) {
+                self.simulation
+                    .set_max_particles(max_particles.saturating_sub(MAX_PARTICLES_STEP));
+            }
+        }
+
+        // Toggle cursor attraction on Alt+A (KeyA pans the camera, Ctrl+A locks the aspect ratio), not mid drag.
+        if alt_held && inputs.is_pressed(KeyCode::KeyA) && !inputs.is_mouse_dragging() {
+            self.state.attract_mode = !self.state.attract_mode;
+            info!("Cursor attraction: {}", self.state.attract_mode);
+        }
+
+        // Toggle softening rings on Ctrl+S (KeyS alone pans the camera)
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyS) {
+            self.state.softening_visible = !self.state.softening_visible;
+            info!("Softening rings: {}", self.state.softening_visible);
+        }
+
+        // Sub steps on BracketLeft/BracketRight
+        let sub_steps = self.state.sub_steps;
+        self.state.sub_steps += inputs.is_pressed(KeyCode::BracketRight) as u32;
+        self.state.sub_steps -= inputs.is_pressed(KeyCode::BracketLeft) as u32;
+        self.state.sub_steps = self.state.sub_steps.clamp(1, MAX_SUB_STEPS);
+        if self.state.sub_steps != sub_steps {
+            info!("Sub steps: {}", self.state.sub_steps);
+        }
+
+        // Toggle merging on KeyM
+        if inputs.is_pressed(KeyCode::KeyM) {
+            self.state.merge_on_collision = !self.state.merge_on_collision;
+            info!("Merge on collision: {}", self.state.merge_on_collision);
+        }
+
+        // Toggle velocity arrows on KeyV
+        if inputs.is_pressed(KeyCode::KeyV) {
+            self.state.velocity_arrows = !self.state.velocity_arrows;
+        }
+
+        // Cycle integration method on KeyI (Ctrl+I toggles the inspector)
+        if !inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyI) {
+            self.state.integration = self.state.integration.next();
+            info!("Integration method: {:?}", self.state.integration);
+        }
+
+        // Toggle black hole lensing on KeyL
+        if inputs.is_pressed(KeyCode::KeyL) {
+            self.state.lensing = !self.state.lensing;
+            info!("Black hole lensing: {}", self.state.lensing);
+        }
+
+        // Toggle stats overlay on F3
+        if inputs.is_pressed(KeyCode::F3) {
+            self.state.debug_stats_visible = !self.state.debug_stats_visible;
+        }
+
+        // Cycle visualisation on Ctrl+P
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyP) {
+            self.state.viz_mode = self.state.viz_mode.next();
+            info!("Visualisation: {:?}", self.state.viz_mode);
+        }
 
         // Clear Sim on KeyC
         if inputs.is_pressed(KeyCode::KeyC) {
@@ -248,85 +984,730 @@ impl GravitySim {
         self.camera_vel.x += CAMERA_SPEED * inputs.is_held(KeyCode::KeyD) as i32 as f64;
         self.camera_vel.x -= CAMERA_SPEED * inputs.is_held(KeyCode::KeyA) as i32 as f64;
 
-        // Branchless Draw Size Change
-        self.state.draw_size +=
-            inputs.is_pressed(KeyCode::ArrowUp) as i32 * (1 + (shift_modifier * 5));
-        self.state.draw_size -=
-            inputs.is_pressed(KeyCode::ArrowDown) as i32 * (1 + (shift_modifier * 5));
+        // Branchless Draw Size Change, the arrows belong to the tuner while it's open.
+        let size_step = !tuning as i32 * (1 + (shift_modifier * 5));
+        self.state.draw_size += inputs.is_pressed(KeyCode::ArrowUp) as i32 * size_step;
+        self.state.draw_size -= inputs.is_pressed(KeyCode::ArrowDown) as i32 * size_step;
         self.state.draw_size = self.state.draw_size.clamp(1, MAX_DRAW_SIZE);
 
         // Cycle shape on Tab
         if inputs.is_pressed(KeyCode::Tab) {
-            unsafe {
-                let shape = transmute::<u8, Shape>((self.state.draw_shape as u8 + 1) % 3);
-                self.state.draw_shape = shape;
+            self.state.draw_shape = self.state.draw_shape.next();
+        }
+
+        // Physics multiplier on Shift+G/Shift+F, reset on Home
+        if inputs.is_held(KeyCode::ShiftLeft) {
+            let mut params = self.simulation.params;
+            if inputs.is_pressed(KeyCode::KeyG) {
+                params.physics_multiplier *= PHYSICS_MULTIPLIER_STEP;
+            } else if inputs.is_pressed(KeyCode::KeyF) {
+                params.physics_multiplier /= PHYSICS_MULTIPLIER_STEP;
             }
+            if params != self.simulation.params {
+                self.simulation.set_gravity_params(params);
+                info!("Physics multiplier: {:e}", params.physics_multiplier);
+            }
+        }
+        if inputs.is_pressed(KeyCode::Home) {
+            self.reset_physics_multiplier();
+        }
+
+        // Fit camera to particles on KeyF (Ctrl+F & Shift+F are taken)
+        if inputs.is_pressed(KeyCode::KeyF)
+            && !inputs.is_held(KeyCode::ControlLeft)
+            && !inputs.is_held(KeyCode::ShiftLeft)
+        {
+            self.fit_camera_to_particles();
         }
 
         // velocity is bounded by equilibrium point with resistance
         // TODO(TOM): Change CAMERA_RESISTANCE to an easing function?
         self.camera_vel *= CAMERA_RESISTANCE;
         self.camera += self.camera_vel;
+        self.update_camera_transition();
         self.state.mouse = inputs.mouse_pos;
+        self.state.drag_start = inputs
+            .is_mouse_dragging()
+            .then_some(inputs.mouse_pressed.pos);
+    }
+
+    // Save state on F5, load on F9 (Ctrl+S & KeyL are taken)
+    #[cfg(feature = "serde")]
+    fn handle_state_inputs(&mut self, inputs: &InputData) {
+        if inputs.is_pressed(KeyCode::F5) {
+            match std::fs::write(SIMULATION_STATE_PATH, self.simulation.serialize()) {
+                Ok(()) => info!("Saved simulation to {SIMULATION_STATE_PATH}"),
+                Err(e) => error!("Failed to save simulation: {e}"),
+            }
+        } else if inputs.is_pressed(KeyCode::F9) {
+            let loaded = std::fs::read_to_string(SIMULATION_STATE_PATH)
+                .map_err(|e| e.to_string())
+                .and_then(|json| Simulation::deserialize(&json).map_err(|e| e.to_string()));
+            match loaded {
+                Ok(simulation) => {
+                    self.simulation = simulation;
+                    self.state.selected_particle = None;
+                    info!("Loaded simulation from {SIMULATION_STATE_PATH}");
+                }
+                Err(e) => error!("Failed to load simulation: {e}"),
+            }
+        }
     }
 
-    fn handle_input_renders(&mut self, inputs: &mut InputData) {
+    // Ctrl+G toggles, left/right cycle the parameter & up/down scale it by GRAVITY_TUNER_STEP.
+    fn handle_gravity_tuner(&mut self, inputs: &InputData) {
+        if inputs.is_held(KeyCode::ControlLeft) && inputs.is_pressed(KeyCode::KeyG) {
+            self.state.gravity_tuner = match self.state.gravity_tuner {
+                Some(_) => None,
+                None => Some(GravityParam::G),
+            };
+            info!("Gravity tuner: {:?}", self.state.gravity_tuner);
+        }
+        let Some(mut param) = self.state.gravity_tuner else {
+            return;
+        };
+
+        if inputs.is_pressed(KeyCode::ArrowRight) {
+            param = param.next();
+        } else if inputs.is_pressed(KeyCode::ArrowLeft) {
+            param = param.prev();
+        }
+        self.state.gravity_tuner = Some(param);
+
+        let mut params = self.simulation.params;
+        if inputs.is_pressed(KeyCode::ArrowUp) {
+            *params.get_mut(param) *= GRAVITY_TUNER_STEP;
+        } else if inputs.is_pressed(KeyCode::ArrowDown) {
+            *params.get_mut(param) /= GRAVITY_TUNER_STEP;
+        }
+        if params != self.simulation.params {
+            self.simulation.set_gravity_params(params);
+            info!("Tuning {param:?}: {params:?}");
+        }
+    }
+
+    // World space centre of the view, where the presets are placed.
+    fn view_centre(&self) -> Vec2<f64, WorldSpace> {
+        self.camera + self.sim_size.cast::<f64>().cast_unit() / 2.0
+    }
+
+    // Black hole core with presets::GALAXY_PARTICLES comets in circular orbits along spiral arms.
+    pub fn spawn_galaxy_disc(&mut self) {
+        use presets::*;
+        let centre = self.view_centre();
+        let sim = &mut self.simulation;
+        sim.clear();
+        sim.spawn_particle(
+            ParticleType::BlackHole,
+            centre,
+            vec2(0.0, 0.0),
+            GALAXY_CORE_RADIUS,
+        );
+
+        let central_mass = ParticleType::BlackHole.mass(GALAXY_CORE_RADIUS, sim.params.density());
+        let max_theta = (GALAXY_OUTER_RADIUS / GALAXY_INNER_RADIUS).ln() / GALAXY_PITCH;
+        let per_arm = GALAXY_PARTICLES / GALAXY_ARMS;
+        for i in 0..per_arm * GALAXY_ARMS {
+            let (arm, step) = (i % GALAXY_ARMS, i / GALAXY_ARMS);
+            let theta = max_theta * step as f64 / per_arm as f64;
+            let r = GALAXY_INNER_RADIUS * (GALAXY_PITCH * theta).exp();
+            let angle = theta + f64::consts::TAU * arm as f64 / GALAXY_ARMS as f64;
+            let offset = vec2(angle.cos(), angle.sin()) * r;
+            let vel = sim.circular_velocity(central_mass, offset);
+            sim.spawn_particle(
+                ParticleType::Comet,
+                centre + offset,
+                vel,
+                GALAXY_PARTICLE_RADIUS,
+            );
+        }
+        info!("Spawned galaxy disc, {} particles", sim.particles.len());
+    }
+
+    // Two equal stars orbiting their shared centre of mass.
+    pub fn spawn_binary_star(&mut self) {
+        use presets::*;
+        let centre = self.view_centre();
+        let sim = &mut self.simulation;
+        sim.clear();
+
+        let mass = ParticleType::Star.mass(BINARY_STAR_RADIUS, sim.params.density());
+        let half = vec2(BINARY_SEPARATION / 2.0, 0.0);
+        // each star only feels the other, BINARY_SEPARATION away, but circles at half that.
+        let speed = sim.circular_velocity(mass, half * 2.0).length() / f64::consts::SQRT_2;
+        for side in [-1.0, 1.0] {
+            sim.spawn_particle(
+                ParticleType::Star,
+                centre + half * side,
+                vec2(0.0, speed * side),
+                BINARY_STAR_RADIUS,
+            );
+        }
+        info!("Spawned binary star");
+    }
+
+    // A star with a planet on each of presets::SOLAR_ORBITS, spaced around it.
+    pub fn spawn_solar_system(&mut self) {
+        use presets::*;
+        let centre = self.view_centre();
+        let sim = &mut self.simulation;
+        sim.clear();
+        sim.spawn_particle(ParticleType::Star, centre, vec2(0.0, 0.0), STAR_RADIUS);
+
+        let central_mass = ParticleType::Star.mass(STAR_RADIUS, sim.params.density());
+        for (i, r) in SOLAR_ORBITS.into_iter().enumerate() {
+            let angle = i as f64 * f64::consts::FRAC_PI_2;
+            let offset = vec2(angle.cos(), angle.sin()) * r;
+            let vel = sim.circular_velocity(central_mass, offset);
+            sim.spawn_particle(
+                ParticleType::Planet,
+                centre + offset,
+                vel,
+                SOLAR_PLANET_RADIUS,
+            );
+        }
+        info!("Spawned solar system, {} particles", sim.particles.len());
+    }
+
+    pub fn reset_physics_multiplier(&mut self) {
+        let mut params = self.simulation.params;
+        params.physics_multiplier = PHYSICS_MULTIPLIER;
+        self.simulation.set_gravity_params(params);
+        info!("Physics multiplier: {:e}", params.physics_multiplier);
+    }
+
+    // Centres the view on the bounding box of all particles padded by CAMERA_FIT_MARGIN,
+    // zooming out (lowering the texture scale) until it fits, transitioning over CAMERA_FIT_MS.
+    // NOTE(TOM): zoom is the app's integer texture scale, so at scale 1 particles may still be off screen.
+    pub fn fit_camera_to_particles(&mut self) {
+        let particles = self.simulation.get_particles();
+        if particles.is_empty() {
+            return;
+        }
+
+        let (min, max) = particles.iter_active().map(|p| p.pos).fold(
+            (
+                vec2(f64::INFINITY, f64::INFINITY),
+                vec2(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), pos| {
+                (
+                    vec2(min.x.min(pos.x), min.y.min(pos.y)),
+                    vec2(max.x.max(pos.x), max.y.max(pos.y)),
+                )
+            },
+        );
+        let padding = (max - min) * CAMERA_FIT_MARGIN;
+        let size = (max - min) + padding * 2.0;
+
+        // largest scale the padded bounds fit in, never zooming in past the current scale.
+        let window = self.window_size.cast::<f64>();
+        let fit = (window.x / size.x).min(window.y / size.y).floor();
+        let scale = (fit.max(1.0) as u32).min(self.state.texture_scale);
+
+        // camera is the top left of the viewport.
+        let centre = (min + max) / 2.0;
+        let viewport = window.to_texture_space(scale).cast_unit();
+        let target = centre - viewport / 2.0;
+
+        self.camera_vel = vec2(0.0, 0.0);
+        self.camera_transition = Some(CameraTransition {
+            from: self.camera,
+            to: target,
+            from_scale: self.state.texture_scale,
+            to_scale: scale,
+            start: Instant::now(),
+        });
+    }
+
+    fn update_camera_transition(&mut self) {
+        let Some(transition) = self.camera_transition else {
+            return;
+        };
+
+        let t = (transition.start.elapsed().as_secs_f64() * 1000.0 / CAMERA_FIT_MS as f64).min(1.0);
+        self.camera = transition.from.lerp(transition.to, t);
+        let (from_scale, to_scale) = (
+            f64::from(transition.from_scale),
+            f64::from(transition.to_scale),
+        );
+        let scale = (from_scale + (to_scale - from_scale) * t).round() as u32;
+        if scale != self.state.texture_scale {
+            self.rescale_texture(scale);
+        }
+        if t >= 1.0 {
+            self.camera_transition = None;
+        }
+    }
+
+    fn handle_input_renders(&mut self) {
         optick::event!("Handling Input Renders");
 
-        if inputs.is_mouse_dragging() {
-            Shape::draw_arrow(
-                inputs.mouse_pressed.pos.scale(self.state.scale).cast(),
-                inputs.mouse_pos.scale(self.state.scale).cast(),
-                |x: i32, y: i32| {
-                    let pos = vec2(x, y).clamp(vec2(0, 0), self.sim_size - 1);
-                    self.write_to_buf(pos, RED);
-                },
-            );
-        } else {
-            self.clear_mouse_outline(GREEN);
-            self.render_mouse_outline(GREEN);
+        if let Some(drag_start) = self.state.drag_start {
+            Shape::draw_arrow(
+                drag_start.to_texture_space(self.state.texture_scale).cast(),
+                self.state
+                    .mouse
+                    .to_texture_space(self.state.texture_scale)
+                    .cast(),
+                |x: i32, y: i32| {
+                    let pos = vec2(x, y).clamp(vec2(0, 0), self.sim_size - 1);
+                    self.write_to_buf(pos, RED);
+                },
+            );
+        } else {
+            self.clear_mouse_outline(GREEN);
+            self.render_mouse_outline(GREEN);
+        }
+    }
+    // endregion
+
+    // region: Rendering
+    // (min, max) particle speed, (0.0, 0.0) if there are no particles.
+    // At least sub_steps, more if the fastest particle would move further than MIN_STEP_SAFETY
+    // in one, capped at MAX_SUB_STEPS. Verlet needs the same sub step every call, so it keeps
+    // the fixed sub_steps.
+    fn adaptive_sub_steps(&self, delta_time: f64) -> u32 {
+        if self.state.integration == IntegrationMethod::Verlet {
+            return self.state.sub_steps;
+        }
+
+        let max_speed = self
+            .simulation
+            .get_particles()
+            .par_iter_active()
+            .map(|p| p.vel.length())
+            .reduce(|| 0.0, f64::max);
+        if max_speed < SMALL_VALUE {
+            return self.state.sub_steps;
+        }
+
+        let safe_dt = MIN_STEP_SAFETY / max_speed;
+        let required = (delta_time / safe_dt).ceil().min(f64::from(MAX_SUB_STEPS)) as u32;
+        required.max(self.state.sub_steps)
+    }
+
+    pub fn compute_speed_stats(&self) -> (f64, f64) {
+        let particles = self.simulation.get_particles();
+        if particles.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        particles
+            .par_iter_active()
+            .map(|p| p.vel.length())
+            .map(|speed| (speed, speed))
+            .reduce(
+                || (f64::INFINITY, 0.0),
+                |(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)),
+            )
+    }
+
+    // Maps speed onto the heat map, relative to the fastest particle.
+    fn speed_colour(speed: f64, max_speed: f64) -> Rgba {
+        if max_speed <= 0.0 {
+            return heat_map(0.0);
+        }
+        heat_map(speed / max_speed)
+    }
+
+    // Shades the background by the potential at a grid of samples, deeper wells are hotter.
+    fn render_potential_field(&mut self) {
+        optick::event!("Render Potential Field");
+
+        // subsample more coarsely on large sims, keeps the sample count (& cost) bounded.
+        let area = f64::from(self.sim_size.x * self.sim_size.y);
+        let step =
+            POTENTIAL_GRID_STEP.max((area / f64::from(POTENTIAL_MAX_SAMPLES)).sqrt().ceil() as i32);
+        let cells: Vec2<i32, TextureSpace> = vec2(
+            (self.sim_size.x + step - 1) / step,
+            (self.sim_size.y + step - 1) / step,
+        );
+
+        let camera = self.camera;
+        // the pool rather than the Simulation, its stats cache isn't Sync.
+        let particles = self.simulation.get_particles();
+        let g = self.simulation.params.scaled_g();
+        let potentials: Vec<f64> = (0..cells.x * cells.y)
+            .into_par_iter()
+            .map(|i| {
+                let centre: Vec2<i32, TextureSpace> =
+                    vec2(i % cells.x, i / cells.x) * step + step / 2;
+                particles.potential_at(centre.cast::<f64>().cast_unit() + camera, g)
+            })
+            .collect();
+
+        let (min, max) = potentials
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            });
+        let range = (max - min).max(f64::EPSILON);
+
+        let sim_size = self.sim_size;
+        let buf = &self.bufs[self.front_buffer];
+        potentials
+            .par_iter()
+            .enumerate()
+            .for_each(|(i, &potential)| {
+                let colour = heat_map((max - potential) / range);
+                let corner: Vec2<i32, TextureSpace> =
+                    vec2(i as i32 % cells.x, i as i32 / cells.x) * step;
+                for y in corner.y..(corner.y + step).min(sim_size.y) {
+                    for x in corner.x..(corner.x + step).min(sim_size.x) {
+                        Self::write_colour(4 * (y * sim_size.x + x) as usize, buf, colour);
+                    }
+                }
+            });
+    }
+
+    fn render_particles(
+        texture_buf: &[SyncCell<u8>],
+        particles: &ParticlePool,
+        sim_size: Vec2<i32, TextureSpace>,
+        camera: Vec2<f64, WorldSpace>,
+        max_speed: f64,
+        viz_mode: VizMode,
+        velocity_arrows: bool,
+    ) {
+        optick::event!("Update Texture Buffer");
+
+        // relative to the most energetic particle, like speed_colour.
+        let max_energy = match viz_mode {
+            VizMode::KineticEnergy => particles
+                .par_iter_active()
+                .map(|p| p.kinetic_energy())
+                .reduce(|| 0.0, f64::max),
+            _ => 0.0,
+        };
+
+        let visible: Vec<_> = particles
+            .iter_active()
+            .map(|p| {
+                let colour = match (viz_mode, p.kind.colour()) {
+                    (VizMode::KineticEnergy, _) if max_energy > 0.0 => {
+                        heat_map(p.kinetic_energy() / max_energy)
+                    }
+                    (_, Some(colour)) => colour,
+                    _ => Self::speed_colour(p.vel.length(), max_speed),
+                };
+                (p.pos.sub(camera), p.vel, p.radius, p.kind, colour)
+            })
+            .filter(|(pos, _, radius, _, _)| {
+                // 1.5x covers the glow & comet trail hanging over the edge.
+                let radius = radius * 1.5;
+                !(pos.x + radius < 0.0
+                    || pos.y + radius < 0.0
+                    || pos.x - radius >= f64::from(sim_size.x)
+                    || pos.y - radius >= f64::from(sim_size.y))
+            })
+            .collect();
+
+        let plot = |x: i32, y: i32, colour: Rgba| {
+            if !(x < 0 || y < 0 || x >= sim_size.x || y >= sim_size.y) {
+                let index = 4 * (y * sim_size.x + x) as usize;
+                Self::write_colour(index, texture_buf, colour);
+            }
+        };
+
+        visible
+            .iter()
+            .for_each(|&(pos, vel, radius, kind, colour)| {
+                let centre = pos.map(|n| n as i32);
+
+                match kind {
+                    ParticleType::Comet => {
+                        // Tail points away from the direction of travel, longer the faster it goes.
+                        let speed = vel.length();
+                        if speed > SMALL_VALUE {
+                            let length = COMET_TRAIL_LENGTH * (speed / max_speed.max(speed));
+                            let tail = centre - (vel / speed * length).map(|n| n as i32);
+                            Shape::draw_line(centre, tail, &mut |x, y| plot(x, y, colour));
+                        }
+                    }
+                    ParticleType::Star => {
+                        let glow = (radius * STAR_GLOW_SCALE) as i32;
+                        Shape::CircleFill.draw(glow, |off_x, off_y| {
+                            plot(centre.x + off_x, centre.y + off_y, STAR_GLOW_COLOUR);
+                        });
+                    }
+                    ParticleType::Planet | ParticleType::BlackHole => (),
+                }
+
+                // Black holes are drawn at half size, the ring sits at 1.5x the dark disc.
+                let draw_radius = match kind {
+                    ParticleType::BlackHole => radius * 0.5,
+                    _ => radius,
+                };
+                Shape::CircleFill.draw(draw_radius as i32, |off_x, off_y| {
+                    plot(centre.x + off_x, centre.y + off_y, colour);
+                });
+                if kind == ParticleType::BlackHole {
+                    Shape::CircleOutline.draw((draw_radius * 1.5) as i32, |off_x, off_y| {
+                        plot(centre.x + off_x, centre.y + off_y, EVENT_HORIZON_COLOUR);
+                    });
+                }
+            });
+
+        // A separate pass so no particle's circle covers another's arrow.
+        if velocity_arrows {
+            for &(pos, vel, ..) in &visible {
+                let start = pos.map(|n| n as i32);
+                let end = (pos + vel * VELOCITY_ARROW_SCALE).map(|n| n as i32);
+                Shape::draw_arrow(start, end, |x, y| plot(x, y, YELLOW));
+            }
+        }
+    }
+
+    fn render_black_hole_lensing(&mut self) {
+        optick::event!("Black Hole Lensing");
+        let black_holes: Vec<(Vec2<i32, TextureSpace>, i32)> = self
+            .simulation
+            .get_particles()
+            .iter_active()
+            .filter(|p| p.kind == ParticleType::BlackHole)
+            .map(|p| {
+                let centre = (p.pos - self.camera).map(|n| n as i32).cast_unit();
+                (centre, (p.radius * LENSING_RADIUS_SCALE) as i32)
+            })
+            .collect();
+
+        for (centre, lensing_radius) in black_holes {
+            Self::render_lensing_effect(
+                &self.bufs[self.front_buffer],
+                self.sim_size,
+                centre,
+                lensing_radius,
+            );
+        }
+    }
+
+    // Each pixel within lensing_radius takes the colour from closer to the centre, by
+    // LENSING_MAX_DISPLACEMENT * (1 - dist / lensing_radius) pixels, pulling the view inwards.
+    // Reads come from a scratch copy of the affected square so writes can't feed into later reads.
+    fn render_lensing_effect(
+        buf: &[SyncCell<u8>],
+        sim_size: Vec2<i32, TextureSpace>,
+        black_hole_pos: Vec2<i32, TextureSpace>,
+        lensing_radius: i32,
+    ) {
+        let min = (black_hole_pos - lensing_radius).clamp(vec2(0, 0), sim_size - 1);
+        let max = (black_hole_pos + lensing_radius).clamp(vec2(0, 0), sim_size - 1);
+        if lensing_radius <= 0 || min.x >= max.x || min.y >= max.y {
+            return;
+        }
+
+        let width = max.x - min.x + 1;
+        let scratch: Vec<u8> = (min.y..=max.y)
+            .flat_map(|y| {
+                let row = 4 * (y * sim_size.x + min.x) as usize;
+                buf[row..row + 4 * width as usize].iter().map(|c| *c.get())
+            })
+            .collect();
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let offset = black_hole_pos - vec2(x, y);
+                let dist = offset.cast::<f64>().length();
+                if dist >= f64::from(lensing_radius) || dist < SMALL_VALUE {
+                    continue;
+                }
+
+                let displacement =
+                    LENSING_MAX_DISPLACEMENT * (1.0 - dist / f64::from(lensing_radius));
+                let source = (vec2(x, y).cast::<f64>()
+                    + offset.cast::<f64>() / dist * displacement)
+                    .map(|n| n.round() as i32)
+                    .clamp(min, max);
+
+                let from = 4 * ((source.y - min.y) * width + (source.x - min.x)) as usize;
+                let index = 4 * (y * sim_size.x + x) as usize;
+                for channel in 0..4 {
+                    *buf[index + channel].get_mut() = scratch[from + channel];
+                }
+            }
+        }
+    }
+
+    fn render_stats(&mut self) {
+        let stats = self.simulation.get_stats();
+        let lines = [
+            format!("speed: {}x", self.state.sim_speed),
+            format!("particles: {}", stats.n_particles),
+            format!("mass: {:.3e}", stats.total_mass),
+            format!("kinetic: {:.3e}", stats.total_kinetic_energy),
+            format!(
+                "com: {:.1}, {:.1}",
+                stats.centre_of_mass.x, stats.centre_of_mass.y
+            ),
+            format!(
+                "momentum: {:.2e}, {:.2e}",
+                stats.net_momentum.x, stats.net_momentum.y
+            ),
+            format!("speed: {:.2} - {:.2}", stats.min_speed, stats.max_speed),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_text(vec2(2, 2 + i as i32 * (GLYPH_HEIGHT + 1)), line, WHITE);
+        }
+    }
+
+    // Velocity & net force arrows on the selected particle, with its values listed top right.
+    // Persists until deselected, or the particle is freed.
+    fn render_inspect_overlay(&mut self) {
+        let Some(idx) = self.state.selected_particle else {
+            return;
+        };
+        let Some(p) = self.simulation.get_particles().get(idx).copied() else {
+            self.state.selected_particle = None;
+            return;
+        };
+
+        let sim_size = self.sim_size;
+        let centre: Vec2<i32, TextureSpace> = (p.pos - self.camera).map(|n| n as i32).cast_unit();
+        let arrows = [
+            (p.vel, INSPECT_ARROW_SCALE, WHITE),
+            (p.force, INSPECT_ARROW_SCALE * INSPECT_FORCE_SCALE, BLUE),
+        ];
+        for (vector, scale, colour) in arrows {
+            let end = centre + Self::log_scaled(vector, scale);
+            Shape::draw_arrow(centre, end, |x: i32, y: i32| {
+                if !(x < 0 || y < 0 || x >= sim_size.x || y >= sim_size.y) {
+                    self.write_to_buf(vec2(x, y), colour);
+                }
+            });
+        }
+        self.render_orbit_prediction(idx);
+        Shape::CircleOutline.draw(p.radius as i32 + 2, |off_x, off_y| {
+            let pos = centre + vec2(off_x, off_y);
+            if !(pos.x < 0 || pos.y < 0 || pos.x >= sim_size.x || pos.y >= sim_size.y) {
+                self.write_to_buf(pos, YELLOW);
+            }
+        });
+
+        let lines = [
+            format!("pos: {:.1}, {:.1}", p.pos.x, p.pos.y),
+            format!("vel: {:.3}, {:.3}", p.vel.x, p.vel.y),
+            format!("force: {:.2e}, {:.2e}", p.force.x, p.force.y),
+            format!("mass: {:.3e}", p.mass),
+            format!("radius: {:.1}", p.radius),
+            format!("kinetic: {:.3e}", p.kinetic_energy()),
+            format!("softening: {:.1}", self.simulation.params.softening),
+        ];
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as i32;
+        let left = (sim_size.x - 2 - width * (GLYPH_WIDTH + 1)).max(0);
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_text(vec2(left, 2 + i as i32 * (GLYPH_HEIGHT + 1)), line, YELLOW);
+        }
+    }
+
+    // Where the particle at idx is heading if nothing else moved, a cubic bezier between each
+    // pair of samples with control points along their velocities (the hermite curve).
+    fn render_orbit_prediction(&mut self, idx: usize) {
+        let sim_size = self.sim_size;
+        let camera = self.camera;
+        let span = ORBIT_SAMPLE_STEPS as f64 * PHYSICS_DT;
+        let to_texture = |pos: Vec2<f64, WorldSpace>| -> Vec2<f32, TextureSpace> {
+            (pos - camera).map(|n| n as f32).cast_unit()
+        };
+        // within a screen of the sim, so an escaping particle can't draw enormous lines.
+        let near_screen = |p: Vec2<f32, TextureSpace>| {
+            let size = sim_size.cast::<f32>();
+            p.x > -size.x && p.y > -size.y && p.x < size.x * 2.0 && p.y < size.y * 2.0
+        };
+        let samples = self.simulation.predict_orbit(
+            idx,
+            ORBIT_PREDICTION_STEPS,
+            ORBIT_SAMPLE_STEPS,
+            PHYSICS_DT,
+        );
+
+        let mut plot = |x: i32, y: i32| {
+            if !(x < 0 || y < 0 || x >= sim_size.x || y >= sim_size.y) {
+                self.write_to_buf(vec2(x, y), GRAY);
+            }
+        };
+        for pair in samples.windows(2) {
+            let [(p0, v0), (p3, v3)] = [pair[0], pair[1]];
+            let points = [
+                to_texture(p0),
+                to_texture(p0 + v0 * (span / 3.0)),
+                to_texture(p3 - v3 * (span / 3.0)),
+                to_texture(p3),
+            ];
+            if !points.into_iter().all(near_screen) {
+                continue;
+            }
+            subdivide_bezier(points, 0, &mut |start, end| {
+                Shape::draw_line(start.map(|n| n as i32), end.map(|n| n as i32), &mut plot);
+            });
+        }
+    }
+
+    // A ring at the softening radius around every particle, RED where it overlaps another's.
+    // Also warns in the HUD while any softened pair exists.
+    fn render_softening_rings(&mut self) {
+        let sim_size = self.sim_size;
+        let softening = self.simulation.params.softening;
+        let softened = self.simulation.softened_particles();
+        let any_softened = softened.iter().any(|&(is_softened, _)| is_softened);
+
+        for (is_softened, p) in softened {
+            let centre: Vec2<i32, TextureSpace> =
+                (p.pos - self.camera).map(|n| n as i32).cast_unit();
+            let colour = if is_softened {
+                RED
+            } else {
+                SOFTENING_RING_COLOUR
+            };
+            Shape::CircleOutline.draw(softening as i32, |off_x, off_y| {
+                let pos = centre + vec2(off_x, off_y);
+                if !(pos.x < 0 || pos.y < 0 || pos.x >= sim_size.x || pos.y >= sim_size.y) {
+                    self.write_to_buf(pos, colour);
+                }
+            });
+        }
+
+        if any_softened {
+            let text = "! Softened gravity active";
+            let y = (sim_size.y - 2 - GLYPH_HEIGHT).max(0);
+            self.draw_text(vec2(2, y), text, RED);
         }
     }
-    // endregion
 
-    // region: Rendering
-    fn render_particles(
-        texture_buf: &[SyncCell<u8>],
-        particles: &[SyncCell<Particle>],
-        sim_size: Vec2<i32, RenderSpace>,
-        camera: Vec2<f64, WorldSpace>,
-    ) {
-        optick::event!("Update Texture Buffer");
+    // Same direction, length log10(1 + |v|) * scale so tiny & huge values are both visible.
+    fn log_scaled(vector: Vec2<f64, WorldSpace>, scale: f64) -> Vec2<i32, TextureSpace> {
+        let magnitude = vector.length();
+        if magnitude < SMALL_VALUE {
+            return vec2(0, 0);
+        }
+        let length = (1.0 + magnitude).log10() * scale;
+        (vector / magnitude * length)
+            .map(|n| n.round() as i32)
+            .cast_unit()
+    }
 
-        particles
-            .iter()
-            .map(|p| p.get_mut())
-            .map(|p| (p.pos.sub(camera), p.radius))
-            .filter(|(pos, radius)| {
-                !(pos.x + radius < 0.0
-                    || pos.y + radius < 0.0
-                    || pos.x - radius >= f64::from(sim_size.x)
-                    || pos.y - radius >= f64::from(sim_size.y))
-            })
-            .for_each(|(pos, radius)| {
-                Shape::CircleFill.draw(radius as i32, |off_x, off_y| {
-                    let offset = pos.map(|n| n as i32) + vec2(off_x, off_y);
-                    if !(offset.x < 0
-                        || offset.y < 0
-                        || offset.x >= sim_size.x
-                        || offset.y >= sim_size.y)
+    // Same layout as World::draw_text, clipped to the sim.
+    fn draw_text(&mut self, pos: Vec2<i32, TextureSpace>, text: &str, colour: Rgba) {
+        for (i, c) in text.chars().enumerate() {
+            let origin = pos + vec2(i as i32 * (GLYPH_WIDTH + 1), 0);
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    let pixel = origin + vec2(col, row as i32);
+                    if (bits >> (GLYPH_WIDTH - 1 - col)) & 1 == 1
+                        && pixel.x < self.sim_size.x
+                        && pixel.y < self.sim_size.y
                     {
-                        let index = 4 * (offset.y * sim_size.x + offset.x) as usize;
-                        Self::write_colour(index, texture_buf, WHITE);
+                        self.write_to_buf(pixel, colour);
                     }
-                });
-            });
+                }
+            }
+        }
     }
 
     // TODO(TOM): make this a separate texture layer, overlayed on top of the sim
     fn render_mouse_outline(&mut self, colour: Rgba) {
         optick::event!("Rendering Mouse Outline");
-        let mouse = self.state.mouse.scale(self.state.scale);
+        let mouse = self.state.mouse.to_texture_space(self.state.texture_scale);
 
         self.state
             .draw_shape
@@ -342,7 +1723,10 @@ impl GravitySim {
     // TODO(TOM): this function proper doesn't work with back buffers
     fn clear_mouse_outline(&mut self, colour: Rgba) {
         optick::event!("Clearing Mouse Outline");
-        let mouse = self.prev_state.mouse.scale(self.prev_state.scale);
+        let mouse = self
+            .prev_state
+            .mouse
+            .to_texture_space(self.prev_state.texture_scale);
 
         self.prev_state
             .draw_shape
@@ -358,173 +1742,443 @@ impl GravitySim {
                     && *buf[index + 2].get_mut() == colour.b
                     && *buf[index + 3].get_mut() == colour.a
                 {
-                    Self::write_colour(index, buf, DGRAY);
+                    Self::write_colour(index, buf, GRAY);
                 }
             });
     }
     // endregion
+}
 
-    pub fn new(window_size: Vec2<u32, ScreenSpace>, scale: u32) -> Self {
-        let scale = Scale::new(scale as i32);
-        let window_size = window_size.cast();
+impl Simulation {
+    fn new() -> Self {
+        Self {
+            particles: ParticlePool::with_capacity(MAX_PARTICLES),
+            max_particles: MAX_PARTICLES,
+            params: GravityParams::default(),
+            cached_stats: Cell::new(None),
+            #[cfg(feature = "telemetry")]
+            telemetry: None,
+        }
+    }
 
-        let sim_size = window_size.scale(scale);
-        let buf_size = (sim_size.x * sim_size.y * 4) as usize;
+    // Particles in spawn order & the gravity params, as JSON.
+    #[cfg(feature = "serde")]
+    fn serialize(&self) -> String {
+        let saved = SavedSimulation {
+            params: self.params,
+            max_particles: self.max_particles,
+            particles: self
+                .particles
+                .spawn_order
+                .iter()
+                .filter_map(|&idx| self.particles.get(idx).copied())
+                .collect(),
+        };
+        // only fails on non-string map keys, which SavedSimulation doesn't have.
+        serde_json::to_string_pretty(&saved).expect("SavedSimulation is always serializable")
+    }
 
-        let mut buf = Vec::with_capacity(buf_size);
-        let mut buf_clone = Vec::with_capacity(buf_size);
-        for _ in 0..buf_size {
-            buf.push(SyncCell::new(44));
-            buf_clone.push(SyncCell::new(44));
+    #[cfg(feature = "serde")]
+    fn deserialize(json: &str) -> Result<Self, serde_json::Error> {
+        let saved: SavedSimulation = serde_json::from_str(json)?;
+        let mut simulation = Self::new();
+        simulation.params = saved.params;
+        simulation.set_max_particles(saved.max_particles);
+        for particle in saved.particles {
+            simulation.particles.alloc(particle);
         }
+        Ok(simulation)
+    }
 
-        let simulation = Simulation::new();
+    // Aggregates over every particle in one pass, cached until invalidate_stats.
+    fn get_stats(&self) -> SimulationStats {
+        if let Some(stats) = self.cached_stats.get() {
+            return stats;
+        }
 
-        let state = FrontendState {
-            frame: 0,
-            draw_size: INIT_DRAW_SIZE,
-            draw_shape: Shape::CircleFill,
-            scale,
-            running: false,
-            step_sim: false,
-            mouse: vec2(0.0, 0.0),
+        let mut stats = SimulationStats {
+            n_particles: self.particles.len(),
+            total_mass: 0.0,
+            total_kinetic_energy: 0.0,
+            centre_of_mass: vec2(0.0, 0.0),
+            net_momentum: vec2(0.0, 0.0),
+            max_speed: 0.0,
+            min_speed: if self.particles.is_empty() {
+                0.0
+            } else {
+                f64::INFINITY
+            },
         };
+        for p in self.particles.iter_active() {
+            let speed = p.vel.length();
+            stats.total_mass += p.mass;
+            stats.total_kinetic_energy += 0.5 * p.mass * speed.pow(2);
+            stats.centre_of_mass += p.pos * p.mass;
+            stats.net_momentum += p.vel * p.mass;
+            stats.max_speed = stats.max_speed.max(speed);
+            stats.min_speed = stats.min_speed.min(speed);
+        }
+        if stats.total_mass > 0.0 {
+            stats.centre_of_mass /= stats.total_mass;
+        }
 
-        Self {
-            state,
-            prev_state: state,
+        self.cached_stats.set(Some(stats));
+        stats
+    }
 
-            window_size,
-            sim_size,
-            camera: vec2(0.0, 0.0),
-            camera_vel: vec2(0.0, 0.0),
-            bufs: [buf, buf_clone],
-            front_buffer: 0,
-            simulation,
-        }
+    fn invalidate_stats(&self) {
+        self.cached_stats.set(None);
     }
-}
 
-impl Simulation {
-    fn new() -> Self {
-        Self {
-            particles: Vec::new(),
-            // particles: Vec::from(Self::init_particles()),
-        }
+    fn set_gravity_params(&mut self, params: GravityParams) {
+        self.params = params;
     }
 
-    fn update(&mut self, delta_time: f64) {
+    // Verlet relies on last step's force, delta_time must be the same every call (fixed timestep).
+    // merge combines colliding particles rather than bouncing them apart.
+    fn update(&mut self, delta_time: f64, method: IntegrationMethod, merge: bool) {
         optick::event!("Physics Update");
 
-        for (i, p1) in self.particles.iter().enumerate() {
-            let p1 = p1.get_mut();
+        match method {
+            IntegrationMethod::Euler => self.step_euler(delta_time, merge),
+            IntegrationMethod::Verlet => self.step_verlet(delta_time, merge),
+            IntegrationMethod::RungeKutta4 => self.step_rk4(delta_time, merge),
+        }
+
+        #[cfg(feature = "telemetry")]
+        {
+            let latest = SimTelemetry::measure(&self.particles, self.params.scaled_g());
+            let initial = self.telemetry.map_or(latest, |(_, initial)| initial);
+            self.telemetry = Some((latest, initial));
+        }
+
+        self.invalidate_stats();
+    }
 
+    // Zeroes then accumulates every pairwise force, resolving collisions along the way.
+    // Particles absorbed by a merge are freed afterwards.
+    fn accumulate_forces(&mut self, merge: bool) {
+        for p in self.particles.iter_active_mut() {
+            p.prev_force = p.force;
+            p.force = vec2(0.0, 0.0);
+        }
+        for (i, p1) in self.particles.iter_active_mut().enumerate() {
             // calculates forces from other particles on this particle.
-            for (j, p2) in self.particles.iter().enumerate().skip(i) {
-                let p2 = p2.get_mut();
-                if i == j {
-                    // println!("equal! {i}, {j}");
-                    continue;
-                }
+            for p2 in self.particles.iter_active_mut().skip(i + 1) {
+                p1.apply_physics(p2, self.params.scaled_g(), self.params.softening, merge);
+            }
+        }
 
-                p1.apply_physics(p2);
+        for idx in 0..self.particles.slots.len() {
+            let dead = self.particles.slots[idx]
+                .get()
+                .is_some_and(|p| p.mass == 0.0 || p.radius == 0.0);
+            if dead {
+                self.particles.free(idx);
             }
+        }
+    }
+
+    fn step_euler(&mut self, delta_time: f64, merge: bool) {
+        self.accumulate_forces(merge);
+        for p in self.particles.iter_active_mut() {
+            p.vel += p.force / p.mass * delta_time;
+            p.pos += p.vel * delta_time;
+        }
+    }
+
+    // pos += vel*dt + accel*dt²/2, then vel += (old_accel + new_accel)*dt/2.
+    fn step_verlet(&mut self, delta_time: f64, merge: bool) {
+        // A particle that's never had its force computed would drift & kick on half its real
+        // force, so the first step after spawning runs an extra force pass.
+        let unprimed = self
+            .particles
+            .iter_active()
+            .any(|p| p.force == vec2(0.0, 0.0) && p.prev_force == vec2(0.0, 0.0));
+        if unprimed {
+            self.accumulate_forces(merge);
+        }
+
+        // Drift using last step's force, accumulate_forces moves it to prev_force.
+        for p in self.particles.iter_active_mut() {
+            p.pos += p.vel * delta_time + p.force / p.mass * (delta_time.pow(2) / 2.0);
+        }
+
+        self.accumulate_forces(merge);
+
+        // Kick with the average of the old & new force, symmetric around the step's midpoint.
+        for p in self.particles.iter_active_mut() {
+            p.vel += (p.prev_force + p.force) / p.mass * (delta_time / 2.0);
+        }
+    }
 
-            // Inner loop skips i, therefore skips this particel from now on,
-            // so apply resitances & update position.
-            p1.acc += (p1.force / p1.mass) * delta_time;
-            p1.acc *= PHYSICS_RESISTANCE;
-            p1.vel += p1.acc;
-            p1.pos += p1.vel;
+    // Classic RK4 on (pos, vel). The first stage is the real force pass (collisions included),
+    // the other three only evaluate gravity at the trial positions.
+    fn step_rk4(&mut self, delta_time: f64, merge: bool) {
+        self.accumulate_forces(merge);
+
+        let particles = &self.particles;
+        let pos: Vec<_> = particles.iter_active().map(|p| p.pos).collect();
+        let vel: Vec<_> = particles.iter_active().map(|p| p.vel).collect();
+        let accel: Vec<_> = particles.iter_active().map(|p| p.force / p.mass).collect();
+        let masses: Vec<_> = particles.iter_active().map(|p| p.mass).collect();
+
+        // (dx, dv) for each stage.
+        let mut k = vec![(vel.clone(), accel)];
+        for (stage, scale) in [0.5, 0.5, 1.0].into_iter().enumerate() {
+            let (dx, dv) = &k[stage];
+            let trial_pos: Vec<_> = (0..pos.len())
+                .map(|i| pos[i] + dx[i] * (delta_time * scale))
+                .collect();
+            let trial_vel: Vec<_> = (0..vel.len())
+                .map(|i| vel[i] + dv[i] * (delta_time * scale))
+                .collect();
+            k.push((trial_vel, self.gravity_accelerations(&trial_pos, &masses)));
+        }
 
-            p1.force = vec2(0.0, 0.0);
-            // println!("{p1:#?}");
+        for (i, p) in self.particles.iter_active_mut().enumerate() {
+            let dx = (k[0].0[i] + k[1].0[i] * 2.0 + k[2].0[i] * 2.0 + k[3].0[i]) / 6.0;
+            let dv = (k[0].1[i] + k[1].1[i] * 2.0 + k[2].1[i] * 2.0 + k[3].1[i]) / 6.0;
+            p.pos += dx * delta_time;
+            p.vel += dv * delta_time;
         }
+    }
 
-        // TODO(TOM): ideally cull particles in the same loop, mutability & iterator validity issues.
-        self.particles
-            .retain(|p| p.get().mass != 0.0 && p.get().radius != 0.0);
+    // Gravity only acceleration of each particle at positions, same softened law as apply_physics.
+    fn gravity_accelerations(
+        &self,
+        positions: &[Vec2<f64, WorldSpace>],
+        masses: &[f64],
+    ) -> Vec<Vec2<f64, WorldSpace>> {
+        let g = self.params.scaled_g();
+        let softening = self.params.softening;
+        positions
+            .par_iter()
+            .enumerate()
+            .map(|(i, &pos)| {
+                let sources = positions
+                    .iter()
+                    .zip(masses)
+                    .enumerate()
+                    .filter(|&(j, _)| i != j)
+                    .map(|(_, (&other, &mass))| (other, mass));
+                acceleration_at(pos, sources, g, softening)
+            })
+            .collect()
+    }
+
+    // Path of the particle at idx with every other particle held still, a (position, velocity)
+    // sample every sample_steps steps of delta_time, starting where it is now.
+    fn predict_orbit(
+        &self,
+        idx: usize,
+        steps: usize,
+        sample_steps: usize,
+        delta_time: f64,
+    ) -> Vec<(Vec2<f64, WorldSpace>, Vec2<f64, WorldSpace>)> {
+        let Some(p) = self.particles.get(idx) else {
+            return vec![];
+        };
+        let sources: Vec<_> = (0..self.particles.slots.len())
+            .filter(|&i| i != idx)
+            .filter_map(|i| self.particles.get(i))
+            .map(|other| (other.pos, other.mass))
+            .collect();
+        let g = self.params.scaled_g();
+        let softening = self.params.softening;
+
+        // semi implicit euler, cheap & keeps orbits closed.
+        let (mut pos, mut vel) = (p.pos, p.vel);
+        let mut samples = vec![(pos, vel)];
+        for step in 1..=steps {
+            vel += acceleration_at(pos, sources.iter().copied(), g, softening) * delta_time;
+            pos += vel * delta_time;
+            if step % sample_steps.max(1) == 0 {
+                samples.push((pos, vel));
+            }
+        }
+        samples
     }
 
-    /*
-    fn update_cursor_attract(&mut self, mouse: Vec2<f64, ScreenSpace>) {
+    // Pulls every particle towards the cursor, soft clamped so it can't blow up at the cursor.
+    fn update_cursor_attract(&mut self, mouse_world: Vec2<f64, WorldSpace>, delta_time: f64) {
         optick::event!("Physics Update - Cursor");
-        let mouse = mouse.cast_unit();
-
-        // All particles attract to mouse.
-        self.particles
-            .par_iter_mut()
-            .map(|p| p.get_mut())
-            .for_each(|p| {
-                let dist = p.pos - mouse;
-                let abs_dist = f64::sqrt(dist.x.pow(2) + dist.y.pow(2));
-
-                if abs_dist > 5.0 {
-                    // If collapsing in on cursor, give it some velocity.
-                    let normal = p.pos.sub(mouse).mul(1.0 / abs_dist * PHYSICS_MULTIPLIER);
-                    p.vel -= normal;
-                } else {
-                    // Branchless!
-                    let mut delta = vec2(-1.0, -1.0);
-                    let are_vels_neg = p.vel.map(|n| (n < 0.0) as i32 as f64);
-                    delta += are_vels_neg * 2.0;
-                    p.vel += delta;
-                }
-                p.vel *= PHYSICS_RESISTANCE;
-                p.pos += p.vel;
-            });
+
+        for p in self.particles.iter_active_mut() {
+            let to_mouse = mouse_world - p.pos;
+            let abs_dist = to_mouse.length();
+            if abs_dist < SMALL_VALUE {
+                continue;
+            }
+
+            let force = CURSOR_ATTRACT_STRENGTH * p.mass / (abs_dist + 5.0).pow(2);
+            p.vel += to_mouse / abs_dist * (force / p.mass * delta_time);
+            p.vel *= PHYSICS_RESISTANCE;
+        }
+    }
+
+    // Radial impulse away from centre, falling off with distance.
+    fn explode(&mut self, centre: Vec2<f64, WorldSpace>, strength: f64) {
+        for p in self.particles.iter_active_mut() {
+            let dist = p.pos - centre;
+            let abs_dist = dist.length().max(1.0);
+            p.vel += dist.normalise() * (strength / abs_dist);
+        }
+    }
+
+    pub fn potential_at(&self, pos: Vec2<f64, WorldSpace>) -> f64 {
+        self.particles.potential_at(pos, self.params.scaled_g())
+    }
+
+    // Within each other's softening zones, where the softened force differs noticeably.
+    fn is_softened_pair(&self, p1: &Particle, p2: &Particle) -> bool {
+        (p2.pos - p1.pos).length() < 2.0 * self.params.softening
+    }
+
+    // Every active particle, paired with whether it's part of any softened pair.
+    fn softened_particles(&self) -> Vec<(bool, Particle)> {
+        let particles: Vec<Particle> = self.particles.iter_active().copied().collect();
+        particles
+            .iter()
+            .enumerate()
+            .map(|(i, p1)| {
+                let softened = particles
+                    .iter()
+                    .enumerate()
+                    .any(|(j, p2)| i != j && self.is_softened_pair(p1, p2));
+                (softened, *p1)
+            })
+            .collect()
     }
-    */
 
     fn reset(&mut self) {
         self.clear();
-        self.particles.extend_from_slice(&Self::init_particles());
+        for particle in Self::init_particles(self.params.density()) {
+            self.particles.alloc(particle);
+        }
+        #[cfg(feature = "telemetry")]
+        {
+            let initial = SimTelemetry::measure(&self.particles, self.params.scaled_g());
+            self.telemetry = Some((initial, initial));
+        }
     }
 
     fn clear(&mut self) {
         self.particles.clear();
     }
 
-    fn get_particles(&self) -> &[SyncCell<Particle>] {
-        self.particles.as_slice()
+    fn get_particles(&self) -> &ParticlePool {
+        &self.particles
     }
 
-    fn init_particles() -> [SyncCell<Particle>; 2] {
+    fn init_particles(density: f64) -> [Particle; 2] {
         const RADIUS: f64 = 60.0;
         [
-            create_particle(vec2(120.0, 120.0), vec2(0.0, 0.0), RADIUS),
-            create_particle(vec2(320.0, 320.0), vec2(0.0, 0.0), RADIUS),
+            create_particle(
+                ParticleType::Planet,
+                vec2(120.0, 120.0),
+                vec2(0.0, 0.0),
+                RADIUS,
+                density,
+            ),
+            create_particle(
+                ParticleType::Planet,
+                vec2(320.0, 320.0),
+                vec2(0.0, 0.0),
+                RADIUS,
+                density,
+            ),
         ]
     }
 
+    // Returns the oldest particle's slot if it was recycled to stay under max_particles.
     fn spawn_particle(
         &mut self,
+        kind: ParticleType,
         pos: Vec2<f64, WorldSpace>,
         vel: Vec2<f64, WorldSpace>,
         radius: f64,
-    ) {
-        self.particles.push(create_particle(pos, vel, radius));
+    ) -> Option<usize> {
+        let recycled = if self.particles.len() >= self.max_particles {
+            trace!("Recycling oldest particle to enforce limit");
+            self.particles.free_oldest()
+        } else {
+            None
+        };
+        self.particles.alloc(create_particle(
+            kind,
+            pos,
+            vel,
+            radius,
+            self.params.density(),
+        ));
+        recycled
+    }
+
+    // Frees the slot, so idx may be reused by the next spawn.
+    fn remove_particle(&mut self, idx: usize) {
+        self.particles.free(idx);
+        self.invalidate_stats();
+    }
+
+    // Velocity for a circular orbit at offset from a fixed central_mass, anticlockwise.
+    // Matches apply_physics' softened law, a = g * M / ((r² + softening²) * 1.5).
+    fn circular_velocity(
+        &self,
+        central_mass: f64,
+        offset: Vec2<f64, WorldSpace>,
+    ) -> Vec2<f64, WorldSpace> {
+        let r = offset.length();
+        if r < SMALL_VALUE {
+            return vec2(0.0, 0.0);
+        }
+        let accel: f64 = self.params.scaled_g() * central_mass
+            / ((r.pow(2) + self.params.softening.pow(2)) * 1.5);
+        let speed = (accel * r).sqrt();
+        vec2(-offset.y, offset.x) / r * speed
+    }
+
+    // Slot of the closest particle containing pos.
+    fn particle_at(&self, pos: Vec2<f64, WorldSpace>) -> Option<usize> {
+        (0..self.particles.slots.len())
+            .filter_map(|idx| Some((idx, self.particles.get(idx)?)))
+            .map(|(idx, p)| (idx, (p.pos - pos).length(), p.radius))
+            .filter(|&(_, dist, radius)| dist <= radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _, _)| idx)
+    }
+
+    fn set_max_particles(&mut self, max_particles: usize) {
+        self.max_particles = max_particles.max(1);
+        while self.particles.len() > self.max_particles {
+            self.particles.free_oldest();
+        }
+        self.particles.reserve(self.max_particles);
+        info!("Max particles: {}", self.max_particles);
     }
 }
 
 impl Particle {
-    /*
+    fn kinetic_energy(&self) -> f64 {
+        0.5 * self.mass * self.vel.length_squared()
+    }
+
+    // Perfectly inelastic, self becomes the merged particle & p2 is left dead (mass 0) to be culled.
+    // Mass & momentum are conserved, area too (radius² adds).
     fn combine_particles(&mut self, p2: &mut Particle) {
-        let consumer_pos = if self.mass > p2.mass {
-            self.pos
-        } else {
-            p2.pos
-        };
+        let consumer = if self.mass > p2.mass { *self } else { *p2 };
         let new_mass = self.mass + p2.mass;
         let new_momentum: Vec2<f64, WorldSpace> = self.vel * self.mass + p2.vel * p2.mass;
         let new_radius = f64::sqrt(self.radius.pow(2) + p2.radius.pow(2));
 
         *self = Particle {
-            pos: consumer_pos,
+            pos: consumer.pos,
             vel: new_momentum / new_mass,
-            force: vec2(0.0, 0.0),
+            force: self.force + p2.force,
+            prev_force: self.prev_force + p2.prev_force,
             mass: new_mass,
             radius: new_radius,
+            kind: consumer.kind,
         };
 
         // will be culled later.
@@ -532,11 +2186,13 @@ impl Particle {
             pos: vec2(f64::MIN, f64::MIN), // TODO(TOM): MIN might cause slowdowns? prob not..
             vel: vec2(0.0, 0.0),
             force: vec2(0.0, 0.0),
+            prev_force: vec2(0.0, 0.0),
             mass: 0.0,
             radius: 0.0,
+            kind: p2.kind,
         };
     }
-    */
+
     fn handle_collision(
         &mut self,
         p2: &mut Particle,
@@ -583,7 +2239,7 @@ impl Particle {
         }
     }
 
-    fn apply_physics(&mut self, p2: &mut Particle) {
+    fn apply_physics(&mut self, p2: &mut Particle, g: f64, softening: f64, merge: bool) {
         let dist = p2.pos - self.pos;
 
         // this is the magnituce of distance between p1,p2
@@ -594,13 +2250,18 @@ impl Particle {
         let collision_occurred = abs_dist < min_distance;
 
         if collision_occurred {
-            self.handle_collision(p2, abs_dist, normal);
+            if merge {
+                self.combine_particles(p2);
+            } else {
+                self.handle_collision(p2, abs_dist, normal);
+            }
             return;
         }
 
         // Applying gravity between the particles.
+        // Plummer softening, tends to 1/r² far away but stays finite as r -> 0.
         let abs_force =
-            (GRAV_CONST * PHYSICS_MULTIPLIER * self.mass * p2.mass) / (abs_dist.pow(2.0) * 1.5);
+            (g * self.mass * p2.mass) / ((abs_dist.pow(2.0) + softening.pow(2.0)) * 1.5);
         let force = normal * abs_force;
 
         self.force += force;
@@ -608,17 +2269,489 @@ impl Particle {
     }
 }
 
+// Gravity only acceleration at pos from every (position, mass) in sources, same softened law as
+// apply_physics. Sources on top of pos are skipped.
+fn acceleration_at(
+    pos: Vec2<f64, WorldSpace>,
+    sources: impl Iterator<Item = (Vec2<f64, WorldSpace>, f64)>,
+    g: f64,
+    softening: f64,
+) -> Vec2<f64, WorldSpace> {
+    sources.fold(vec2(0.0, 0.0), |accel, (other, mass)| {
+        let dist = other - pos;
+        let abs_dist = dist.length();
+        if abs_dist < SMALL_VALUE {
+            return accel;
+        }
+        let abs_accel = g * mass / ((abs_dist.pow(2.0) + softening.pow(2.0)) * 1.5);
+        accel + dist / abs_dist * abs_accel
+    })
+}
+
+// Crude main sequence approximation, T ≈ T_sun * (M/M_sun)^0.505
+fn stellar_colour(mass_solar: f64) -> Rgba {
+    let temperature = SUN_TEMPERATURE * mass_solar.max(0.0).powf(0.505);
+    Rgba::from_blackbody(temperature as f32)
+}
+
 fn create_particle(
+    kind: ParticleType,
     pos: Vec2<f64, WorldSpace>,
     vel: Vec2<f64, WorldSpace>,
     radius: f64,
-) -> SyncCell<Particle> {
-    SyncCell::new(Particle {
+    density: f64,
+) -> Particle {
+    Particle {
         radius,
-        mass: f64::consts::PI * 4.0 / 3.0 * radius.pow(3) * EARTH_DENSITY,
+        mass: kind.mass(radius, density),
         pos,
         vel,
-        acc: vec2(0.0, 0.0),
         force: vec2(0.0, 0.0),
-    })
+        prev_force: vec2(0.0, 0.0),
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sim_with_particles(scale: u32, positions: &[Vec2<f64, WorldSpace>]) -> GravitySim {
+        let mut sim = GravitySim::new(vec2(800, 600), scale);
+        for &pos in positions {
+            sim.simulation
+                .spawn_particle(ParticleType::Planet, pos, vec2(0.0, 0.0), 1.0);
+        }
+        sim
+    }
+
+    #[test]
+    fn fit_camera_centres_on_particles() {
+        let mut sim = sim_with_particles(2, &[vec2(0.0, 0.0), vec2(1000.0, 1000.0)]);
+        sim.fit_camera_to_particles();
+
+        // 1200x1200 once padded, too big for any scale so it zooms out to 1.
+        let transition = sim.camera_transition.unwrap();
+        assert_eq!(transition.to_scale, 1);
+        assert_eq!(transition.to + vec2(400.0, 300.0), vec2(500.0, 500.0));
+    }
+
+    #[test]
+    fn fit_camera_leaves_a_margin() {
+        let mut sim = sim_with_particles(10, &[vec2(0.0, 0.0), vec2(100.0, 100.0)]);
+        sim.fit_camera_to_particles();
+
+        // 600 / 100 fits at scale 6, the padded 120 only at 5.
+        assert_eq!(sim.camera_transition.unwrap().to_scale, 5);
+    }
+
+    #[test]
+    fn spawning_past_the_limit_recycles_the_oldest() {
+        let mut sim = sim_with_particles(1, &[]);
+        sim.simulation.set_max_particles(10);
+        for i in 0..15 {
+            let pos = vec2(i as f64 * 10.0, 0.0);
+            sim.simulation
+                .spawn_particle(ParticleType::Planet, pos, vec2(0.0, 0.0), 1.0);
+        }
+
+        let particles = &sim.simulation.particles;
+        assert_eq!(particles.len(), 10);
+        let newest = *particles.spawn_order.back().unwrap();
+        assert_eq!(particles.get(newest).unwrap().pos, vec2(140.0, 0.0));
+        // the first 5 spawned were the ones recycled.
+        assert!(particles.iter_active().all(|p| p.pos.x >= 50.0));
+    }
+
+    #[test]
+    fn recycling_the_selected_particle_deselects_it() {
+        let mut sim = sim_with_particles(1, &[vec2(0.0, 0.0), vec2(10.0, 0.0)]);
+        sim.simulation.set_max_particles(2);
+        sim.state.selected_particle = Some(0);
+
+        let recycled = sim.simulation.spawn_particle(
+            ParticleType::Planet,
+            vec2(20.0, 0.0),
+            vec2(0.0, 0.0),
+            1.0,
+        );
+        sim.deselect_recycled(recycled);
+        assert_eq!(recycled, Some(0));
+        assert_eq!(sim.state.selected_particle, None);
+    }
+
+    #[test]
+    fn pool_reuses_freed_slots() {
+        let particle = create_particle(
+            ParticleType::Planet,
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+            1.0,
+            1.0,
+        );
+        let mut pool = ParticlePool::with_capacity(100);
+        for _ in 0..100 {
+            pool.alloc(particle);
+        }
+        for idx in 0..50 {
+            pool.free(idx);
+        }
+        assert_eq!(pool.len(), 50);
+
+        for _ in 0..50 {
+            assert!(pool.alloc(particle) < 50);
+        }
+        assert!(pool.free_list.is_empty());
+        assert_eq!(pool.slots.len(), 100);
+        assert_eq!(pool.len(), 100);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn simulation_json_round_trip() {
+        // exactly representable values, so the comparison can be exact.
+        let mut simulation = Simulation::new();
+        for (pos, vel, mass) in [
+            (vec2(0.0, 0.0), vec2(0.0, 0.0), 1000.0),
+            (vec2(120.5, -40.25), vec2(-1.5, 3.75), 0.125),
+        ] {
+            let mut particle = create_particle(ParticleType::Planet, pos, vel, 1.0, 1.0);
+            particle.mass = mass;
+            simulation.particles.alloc(particle);
+        }
+
+        let loaded = Simulation::deserialize(&simulation.serialize()).unwrap();
+        assert_eq!(loaded.particles.len(), 2);
+        for (a, b) in simulation
+            .particles
+            .iter_active()
+            .zip(loaded.particles.iter_active())
+        {
+            assert_eq!(a.pos, b.pos);
+            assert_eq!(a.vel, b.vel);
+            assert_eq!(a.mass, b.mass);
+        }
+    }
+
+    #[test]
+    fn adaptive_sub_steps_catch_fast_collisions() {
+        let mut sim = sim_with_particles(1, &[]);
+        sim.state.integration = IntegrationMethod::Euler;
+        sim.state.merge_on_collision = true;
+        sim.simulation
+            .spawn_particle(ParticleType::Planet, vec2(0.0, 0.0), vec2(1000.0, 0.0), 8.0);
+        sim.simulation
+            .spawn_particle(ParticleType::Planet, vec2(400.0, 0.0), vec2(0.0, 0.0), 8.0);
+
+        // 4 fixed sub steps of 250 would jump straight over the target.
+        sim.state.step_sim = true;
+        sim.physics_step(Duration::from_secs(1));
+        assert_eq!(sim.state.adaptive_sub_steps, MAX_SUB_STEPS);
+        assert_eq!(sim.simulation.get_particles().len(), 1);
+    }
+
+    #[test]
+    fn verlet_keeps_a_fixed_sub_step() {
+        let mut sim = sim_with_particles(1, &[]);
+        sim.state.integration = IntegrationMethod::Verlet;
+        sim.simulation
+            .spawn_particle(ParticleType::Planet, vec2(0.0, 0.0), vec2(1000.0, 0.0), 8.0);
+        assert_eq!(sim.adaptive_sub_steps(1.0), sim.state.sub_steps);
+    }
+
+    #[test]
+    fn black_hole_pulls_harder_than_a_planet() {
+        // force on a planet 100 units from other, before anything moves.
+        let pull_from = |other: ParticleType| {
+            let mut simulation = Simulation::new();
+            simulation.spawn_particle(other, vec2(0.0, 0.0), vec2(0.0, 0.0), 1.0);
+            simulation.spawn_particle(ParticleType::Planet, vec2(100.0, 0.0), vec2(0.0, 0.0), 1.0);
+            simulation.accumulate_forces(false);
+            simulation.particles.get(1).unwrap().force
+        };
+
+        let black_hole = pull_from(ParticleType::BlackHole);
+        let planet = pull_from(ParticleType::Planet);
+        // both pull towards the origin.
+        assert!(black_hole.x < 0.0 && planet.x < 0.0);
+        assert!(black_hole.length() > planet.length());
+    }
+
+    // A light planet in a circular orbit of radius 100 around a black hole, & its period.
+    // Unsoftened, so the potential energy is exactly -g * m1 * m2 / (1.5 * r).
+    fn orbiting_pair() -> (Simulation, f64) {
+        let mut simulation = Simulation::new();
+        simulation.params.softening = 0.0;
+        let offset = vec2(100.0, 0.0);
+        let vel = simulation.circular_velocity(BLACK_HOLE_MASS, offset);
+        simulation.spawn_particle(ParticleType::BlackHole, vec2(0.0, 0.0), vec2(0.0, 0.0), 1.0);
+        simulation.spawn_particle(ParticleType::Planet, offset, vel, 1.0);
+        // light enough that the black hole barely moves.
+        simulation.particles.iter_active_mut().nth(1).unwrap().mass = BLACK_HOLE_MASS * 1e-9;
+        (simulation, std::f64::consts::TAU * 100.0 / vel.length())
+    }
+
+    // Largest relative change in the pair's separation over steps of period / steps_per_orbit.
+    fn orbit_radius_drift(method: IntegrationMethod, steps: usize, steps_per_orbit: f64) -> f64 {
+        let (mut simulation, period) = orbiting_pair();
+        let mut drift: f64 = 0.0;
+        for _ in 0..steps {
+            simulation.update(period / steps_per_orbit, method, false);
+            let [black_hole, planet] = [0, 1].map(|i| simulation.particles.get(i).unwrap().pos);
+            drift = drift.max(((planet - black_hole).length() - 100.0).abs() / 100.0);
+        }
+        drift
+    }
+
+    #[test]
+    fn verlet_holds_a_circular_orbit() {
+        let verlet = orbit_radius_drift(IntegrationMethod::Verlet, 100, 200.0);
+        let euler = orbit_radius_drift(IntegrationMethod::Euler, 100, 200.0);
+        assert!(verlet < 0.001, "verlet drifted {verlet}");
+        assert!(euler > 0.01, "euler drifted {euler}");
+    }
+
+    // Largest relative change in total energy over 10 000 steps of period / 20.
+    fn orbit_energy_error(method: IntegrationMethod) -> f64 {
+        let (mut simulation, period) = orbiting_pair();
+        let energy = |simulation: &Simulation| {
+            let [p1, p2] = [0, 1].map(|i| *simulation.particles.get(i).unwrap());
+            let r = (p2.pos - p1.pos).length();
+            let potential = -simulation.params.scaled_g() * p1.mass * p2.mass / (1.5 * r);
+            p1.kinetic_energy() + p2.kinetic_energy() + potential
+        };
+
+        let initial = energy(&simulation);
+        let mut error: f64 = 0.0;
+        for _ in 0..10_000 {
+            simulation.update(period / 20.0, method, false);
+            error = error.max(((energy(&simulation) - initial) / initial).abs());
+        }
+        error
+    }
+
+    #[test]
+    fn verlet_conserves_energy_better_than_euler() {
+        let verlet = orbit_energy_error(IntegrationMethod::Verlet);
+        let euler = orbit_energy_error(IntegrationMethod::Euler);
+        assert!(verlet < 0.01, "verlet energy error {verlet}");
+        assert!(euler > 0.05, "euler energy error {euler}");
+    }
+
+    #[test]
+    fn merging_conserves_mass_and_momentum() {
+        let mut simulation = Simulation::new();
+        // no gravity, so the collision is all that changes their momentum.
+        simulation.params.g = 0.0;
+        simulation.spawn_particle(ParticleType::Planet, vec2(0.0, 0.0), vec2(10.0, 1.0), 2.0);
+        simulation.spawn_particle(ParticleType::Planet, vec2(50.0, 0.0), vec2(-10.0, 0.0), 3.0);
+        let mass: f64 = simulation.particles.iter_active().map(|p| p.mass).sum();
+        let momentum = simulation
+            .particles
+            .iter_active()
+            .fold(vec2(0.0, 0.0), |sum, p| sum + p.vel * p.mass);
+
+        for _ in 0..1000 {
+            if simulation.particles.len() == 1 {
+                break;
+            }
+            simulation.update(0.01, IntegrationMethod::Euler, true);
+        }
+        assert_eq!(simulation.particles.len(), 1);
+        let merged = simulation.particles.iter_active().next().unwrap();
+        assert!((merged.mass - mass).abs() <= mass * 1e-12);
+        assert!((merged.vel * merged.mass - momentum).length() <= momentum.length() * 1e-9);
+    }
+
+    #[test]
+    fn potential_of_a_single_particle() {
+        let mut simulation = Simulation::new();
+        simulation.spawn_particle(ParticleType::Planet, vec2(0.0, 0.0), vec2(0.0, 0.0), 1.0);
+        let mass = simulation.particles.get(0).unwrap().mass;
+
+        let expected = -simulation.params.scaled_g() * mass / 50.0;
+        let potential = simulation.potential_at(vec2(30.0, 40.0));
+        assert!(((potential - expected) / expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn doubling_g_doubles_the_force() {
+        let force_with = |g: f64| {
+            let mut simulation = Simulation::new();
+            simulation.set_gravity_params(GravityParams {
+                g,
+                ..GravityParams::default()
+            });
+            simulation.spawn_particle(ParticleType::Planet, vec2(0.0, 0.0), vec2(0.0, 0.0), 1.0);
+            simulation.spawn_particle(ParticleType::Planet, vec2(100.0, 0.0), vec2(0.0, 0.0), 1.0);
+            simulation.accumulate_forces(false);
+            simulation.particles.get(0).unwrap().force
+        };
+
+        let single = force_with(GRAV_CONST);
+        let double = force_with(GRAV_CONST * 2.0);
+        assert!(single.x > 0.0);
+        assert!((double - single * 2.0).length() <= single.length() * 1e-12);
+    }
+
+    #[test]
+    fn stats_of_a_pair_at_rest() {
+        let mut simulation = Simulation::new();
+        simulation.spawn_particle(ParticleType::Planet, vec2(0.0, 0.0), vec2(0.0, 0.0), 2.0);
+        simulation.spawn_particle(ParticleType::Planet, vec2(100.0, 50.0), vec2(0.0, 0.0), 2.0);
+
+        let stats = simulation.get_stats();
+        assert_eq!(stats.n_particles, 2);
+        assert_eq!(stats.net_momentum, vec2(0.0, 0.0));
+        assert_eq!(stats.total_kinetic_energy, 0.0);
+        assert!((stats.centre_of_mass - vec2(50.0, 25.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn attract_mode_pulls_towards_the_cursor() {
+        let mut sim = sim_with_particles(1, &[]);
+        sim.simulation.params.g = 0.0;
+        sim.state.running = true;
+        sim.state.attract_mode = true;
+        // the window centre, at scale 1 that's the middle of the view.
+        sim.state.mouse = vec2(400.0, 300.0);
+        let centre = sim.view_centre();
+        let offsets = [vec2(100.0, 0.0), vec2(-80.0, 60.0), vec2(0.0, -150.0)];
+        for offset in offsets {
+            sim.simulation.spawn_particle(
+                ParticleType::Planet,
+                centre + offset,
+                vec2(0.0, 0.0),
+                1.0,
+            );
+        }
+
+        for _ in 0..10 {
+            sim.physics_step(Duration::from_secs_f64(PHYSICS_DT));
+        }
+        for p in sim.simulation.get_particles().iter_active() {
+            let towards_centre = centre - p.pos;
+            assert!(p.vel.x * towards_centre.x + p.vel.y * towards_centre.y > 0.0);
+        }
+    }
+
+    #[test]
+    fn inspect_arrow_follows_the_velocity() {
+        // log10(2) * 20, rounded, along +x of the render buffer.
+        let arrow = GravitySim::log_scaled(vec2(1.0, 0.0), INSPECT_ARROW_SCALE);
+        assert_eq!(arrow, vec2(6, 0));
+        // a million times faster is only 20x longer.
+        let fast = GravitySim::log_scaled(vec2(1e6, 0.0), INSPECT_ARROW_SCALE);
+        assert_eq!(fast, vec2(120, 0));
+    }
+
+    #[test]
+    fn particles_within_softening_are_a_softened_pair() {
+        let mut simulation = Simulation::new();
+        simulation.params.softening = 5.0;
+        let at =
+            |x: f64| create_particle(ParticleType::Planet, vec2(x, 0.0), vec2(0.0, 0.0), 1.0, 1.0);
+
+        assert!(simulation.is_softened_pair(&at(0.0), &at(4.0)));
+        assert!(!simulation.is_softened_pair(&at(0.0), &at(12.0)));
+    }
+
+    #[test]
+    fn lensing_pulls_a_pixel_halfway_out_in_by_half() {
+        // 40x40, red channel is each pixel's x.
+        let sim_size = vec2(40, 40);
+        let buf: Vec<SyncCell<u8>> = (0..40 * 40 * 4)
+            .map(|i| SyncCell::new(if i % 4 == 0 { ((i / 4) % 40) as u8 } else { 0 }))
+            .collect();
+        GravitySim::render_lensing_effect(&buf, sim_size, vec2(20, 20), 20);
+
+        // 10 pixels right of the centre, so it reads from max_disp / 2 (3) pixels further in.
+        let index = 4 * (20 * 40 + 30);
+        let expected = 30.0 - LENSING_MAX_DISPLACEMENT / 2.0;
+        assert_eq!(f64::from(*buf[index].get()), expected);
+        // outside the lensing radius is untouched.
+        assert_eq!(*buf[4 * (2 * 40 + 2)].get(), 2);
+    }
+
+    #[test]
+    fn sub_steps_stop_a_projectile_tunnelling() {
+        // 50 units a step would jump straight over the target, 25 units away.
+        let particles_after_a_step = |sub_steps: u32| {
+            let mut sim = sim_with_particles(1, &[]);
+            sim.simulation.params.g = 0.0;
+            sim.state.integration = IntegrationMethod::Verlet;
+            sim.state.merge_on_collision = true;
+            sim.state.sub_steps = sub_steps;
+            sim.simulation.spawn_particle(
+                ParticleType::Planet,
+                vec2(0.0, 0.0),
+                vec2(1000.0, 0.0),
+                8.0,
+            );
+            sim.simulation.spawn_particle(
+                ParticleType::Planet,
+                vec2(25.0, 0.0),
+                vec2(0.0, 0.0),
+                8.0,
+            );
+
+            sim.state.step_sim = true;
+            sim.physics_step(Duration::from_millis(50));
+            sim.simulation.get_particles().len()
+        };
+
+        assert_eq!(particles_after_a_step(1), 2);
+        assert_eq!(particles_after_a_step(4), 1);
+    }
+
+    #[cfg(feature = "telemetry")]
+    #[test]
+    fn telemetry_energy_holds_over_a_verlet_orbit() {
+        let (mut simulation, period) = orbiting_pair();
+        for _ in 0..1000 {
+            simulation.update(period / 200.0, IntegrationMethod::Verlet, false);
+        }
+        let (latest, initial) = simulation.telemetry.unwrap();
+        assert!(
+            latest.drift(&initial).abs() < 0.1,
+            "{latest:?} from {initial:?}"
+        );
+    }
+
+    #[test]
+    fn presets_spawn_their_configured_counts() {
+        use presets::*;
+        let mut sim = sim_with_particles(1, &[vec2(0.0, 0.0)]);
+
+        sim.spawn_galaxy_disc();
+        assert_eq!(sim.simulation.particles.len(), GALAXY_PARTICLES + 1);
+        sim.spawn_binary_star();
+        assert_eq!(sim.simulation.particles.len(), 2);
+        sim.spawn_solar_system();
+        assert_eq!(sim.simulation.particles.len(), SOLAR_ORBITS.len() + 1);
+    }
+
+    #[test]
+    fn right_click_removes_the_particle_under_it() {
+        let positions = [vec2(100.0, 100.0), vec2(200.0, 100.0), vec2(300.0, 100.0)];
+        let mut sim = sim_with_particles(1, &positions);
+        let mut inputs = InputData::default();
+
+        // empty space does nothing.
+        inputs.right_click.state = true;
+        inputs.right_click.pos = vec2(150.0, 200.0);
+        sim.handle_input_state(&mut inputs);
+        assert_eq!(sim.simulation.particles.len(), 3);
+
+        let target = vec2(200.0, 100.0);
+        inputs.right_click.state = true;
+        inputs.right_click.pos = (target - sim.camera).cast_unit();
+        sim.handle_input_state(&mut inputs);
+        assert_eq!(sim.simulation.particles.len(), 2);
+        assert!(sim
+            .simulation
+            .particles
+            .iter_active()
+            .all(|p| p.pos != target));
+    }
 }
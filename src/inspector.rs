@@ -0,0 +1,182 @@
+use std::{
+    error::Error,
+    num::NonZeroU32,
+    rc::Rc,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use softbuffer::{Context, SoftBufferError, Surface};
+use winit::{
+    dpi::PhysicalSize,
+    event_loop::EventLoopWindowTarget,
+    window::{Window, WindowBuilder, WindowId},
+};
+
+use crate::utils::{
+    consts::{
+        DARK_GRAY, INSPECTOR_HEIGHT, INSPECTOR_POLL_MS, INSPECTOR_SCALE, INSPECTOR_SNAPSHOT_MS,
+        INSPECTOR_TITLE, INSPECTOR_WIDTH, WHITE,
+    },
+    font::GLYPH_HEIGHT,
+    vec2::{vec2, Vec2, WindowSpace},
+    world::World,
+};
+
+// Text dump of the frontend's state, rendered off the main thread.
+struct InspectorSnapshot {
+    text: String,
+    scroll: usize, // lines skipped from the top
+    size: Vec2<u32, WindowSpace>,
+}
+
+// 0RGB pixels, as softbuffer wants them.
+struct InspectorFrame {
+    pixels: Vec<u32>,
+    size: Vec2<u32, WindowSpace>,
+}
+
+// Secondary, text-only window. Rendering happens on its own thread, the main thread only
+// sends snapshots & blits finished frames, neither side ever blocks on the other.
+pub struct Inspector {
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+    snapshot_tx: Sender<InspectorSnapshot>,
+    frame_rx: Receiver<InspectorFrame>,
+    last_snapshot: Option<Instant>,
+    scroll: usize,
+}
+
+impl Inspector {
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    pub fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+        self.last_snapshot = None; // resend now, not in a second.
+    }
+
+    // Sends a snapshot at most every INSPECTOR_SNAPSHOT_MS, the text is only built when due.
+    pub fn update(&mut self, snapshot: impl FnOnce() -> String) {
+        if self
+            .last_snapshot
+            .is_some_and(|time| time.elapsed() < Duration::from_millis(INSPECTOR_SNAPSHOT_MS))
+        {
+            return;
+        }
+        self.last_snapshot = Some(Instant::now());
+
+        let size = self.window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+        // unbounded channel, never blocks. Only errors if the thread has died, nothing to do.
+        let _ = self.snapshot_tx.send(InspectorSnapshot {
+            text: snapshot(),
+            scroll: self.scroll,
+            size: vec2(size.width, size.height),
+        });
+    }
+
+    pub fn present(&mut self) -> Result<(), SoftBufferError> {
+        // frames can queue up while minimised, only the latest matters.
+        let Some(frame) = self.frame_rx.try_iter().last() else {
+            return Ok(());
+        };
+        let (Some(width), Some(height)) =
+            (NonZeroU32::new(frame.size.x), NonZeroU32::new(frame.size.y))
+        else {
+            return Ok(());
+        };
+
+        self.surface.resize(width, height)?;
+        let mut buffer = self.surface.buffer_mut()?;
+        if buffer.len() != frame.pixels.len() {
+            return Ok(()); // window resized since the snapshot, wait for the next one.
+        }
+        buffer.copy_from_slice(&frame.pixels);
+        buffer.present()
+    }
+
+    pub fn new(target: &EventLoopWindowTarget<()>) -> Result<Self, Box<dyn Error>> {
+        let window = Rc::new(
+            WindowBuilder::new()
+                .with_title(INSPECTOR_TITLE)
+                .with_inner_size(PhysicalSize::new(INSPECTOR_WIDTH, INSPECTOR_HEIGHT))
+                .build(target)?,
+        );
+        let context = Context::new(window.clone())?;
+        let surface = Surface::new(&context, window.clone())?;
+
+        let (snapshot_tx, snapshot_rx) = mpsc::channel();
+        let (frame_tx, frame_rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("inspector".to_string())
+            .spawn(move || Self::render_thread(&snapshot_rx, &frame_tx))?;
+
+        Ok(Self {
+            window,
+            surface,
+            snapshot_tx,
+            frame_rx,
+            last_snapshot: None,
+            scroll: 0,
+        })
+    }
+
+    // Polls with try_recv, exits once the Inspector (and so snapshot_tx) is dropped.
+    fn render_thread(snapshot_rx: &Receiver<InspectorSnapshot>, frame_tx: &Sender<InspectorFrame>) {
+        loop {
+            match snapshot_rx.try_recv() {
+                Ok(snapshot) => {
+                    if frame_tx.send(Self::render(&snapshot)).is_err() {
+                        return;
+                    }
+                }
+                Err(TryRecvError::Empty) => {
+                    thread::sleep(Duration::from_millis(INSPECTOR_POLL_MS));
+                }
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+    }
+
+    fn render(snapshot: &InspectorSnapshot) -> InspectorFrame {
+        optick::event!("Inspector::render");
+
+        // text is drawn at 1/INSPECTOR_SCALE resolution then upscaled, the font is tiny.
+        let size = snapshot.size;
+        let mut world = World::new(vec2(
+            size.x.div_ceil(INSPECTOR_SCALE),
+            size.y.div_ceil(INSPECTOR_SCALE),
+        ));
+        world.clear(DARK_GRAY);
+
+        let line_height = GLYPH_HEIGHT + 1;
+        let visible_lines = world.viewport_size.y as usize / line_height as usize;
+        for (i, line) in snapshot
+            .text
+            .lines()
+            .skip(snapshot.scroll)
+            .take(visible_lines)
+            .enumerate()
+        {
+            world.draw_text(vec2(2, 2 + i as i32 * line_height), line, WHITE);
+        }
+
+        let texture = world.get_viewport_texture();
+        let texture_width = world.viewport_size.x;
+        let pixels = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let index =
+                    4 * ((y / INSPECTOR_SCALE) * texture_width + x / INSPECTOR_SCALE) as usize;
+                u32::from_be_bytes([0, texture[index], texture[index + 1], texture[index + 2]])
+            })
+            .collect();
+
+        InspectorFrame { pixels, size }
+    }
+}
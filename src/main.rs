@@ -1,4 +1,6 @@
 #![feature(duration_millis_float)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+#![cfg_attr(test, feature(test))]
 #![warn(
     /* UNNECESSARYILY ANNOYING  
      * clippy::restriction, 
@@ -21,16 +23,20 @@
 
 mod app;
 mod backend;
+mod demo;
 mod frontend;
+mod inspector;
 
 pub mod frontends {
-    // pub mod cell_sim;
+    pub mod cell_sim;
     pub mod falling_everything;
-    // pub mod gravity_sim;
+    pub mod gravity_sim;
 }
 pub mod utils {
+    pub mod aabb;
     pub mod colour;
     pub mod consts;
+    pub mod font;
     pub mod input_data;
     pub mod sync_cell;
     pub mod vec2;
@@ -39,14 +45,18 @@ pub mod utils {
 
 use crate::{
     app::{init_window, App},
-    frontends::falling_everything::FallingEverything,
+    demo::DemoScript,
+    frontend::Frontend,
+    frontends::{
+        cell_sim::CellSim, falling_everything::FallingEverything, gravity_sim::GravitySim,
+    },
     utils::{
         consts::{INIT_HEIGHT, INIT_SCALE, INIT_TITLE, INIT_WIDTH},
         vec2::vec2,
     },
 };
 
-use log::info;
+use log::{info, warn};
 
 fn main() {
     std::env::set_var("RUST_BACKTRACE", "1");
@@ -55,13 +65,35 @@ fn main() {
 
     // NOTE(TOM): optick can be turned off by removing feature flag in cargo.toml
     // optick::start_capture();
+    let args: Vec<String> = std::env::args().collect();
+    let demo = args.iter().any(|arg| arg == "--demo");
+    // --sim gravity|cells picks the frontend, FallingEverything otherwise.
+    let sim = args
+        .iter()
+        .position(|arg| arg == "--sim")
+        .and_then(|i| args.get(i + 1));
+    match sim.map(String::as_str) {
+        Some("gravity") => run::<GravitySim>(demo),
+        Some("cells") => run::<CellSim>(demo),
+        Some(other) if other != "falling" => {
+            warn!("Unknown sim '{other}', expected gravity, cells or falling");
+            run::<FallingEverything>(demo);
+        }
+        _ => run::<FallingEverything>(demo),
+    }
+    // optick::stop_capture("captures/toy-physics");
+}
+
+fn run<F: Frontend + std::fmt::Debug>(demo: bool) {
     let (window, event_loop) = init_window(INIT_TITLE, vec2(INIT_WIDTH, INIT_HEIGHT));
-    App::<FallingEverything>::new(
+    let mut app = App::<F>::new(
         event_loop,
         &window,
         vec2(INIT_WIDTH, INIT_HEIGHT),
         INIT_SCALE,
-    )
-    .run()
-    // optick::stop_capture("captures/toy-physics");
+    );
+    if demo {
+        app.start_demo(DemoScript::solar_system_showcase());
+    }
+    app.run()
 }
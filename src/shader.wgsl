@@ -2,12 +2,17 @@ struct Uniforms {
     _padding: vec3<f32>,
     time: f32,
     texture_size: vec2<f32>,
-    window_size: vec2<f32>,
+    window_size: vec2<f32>, // size of the viewport, excluding letterbox bars
+    viewport_offset: vec2<f32>,
+    bloom_intensity: f32, // [0, 2], blurred glow added on top in fs_main_composite
+    bloom_threshold: f32, // luminance [0, 1] a texel needs before it glows
 }
 
 @group(0) @binding(0) var texture_sampler: sampler;
 @group(0) @binding(1) var texture_data: texture_2d<f32>;
 @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+// The vertical blur's output, only bound for fs_main_composite.
+@group(1) @binding(1) var bloom_data: texture_2d<f32>;
 
 struct VertexInput {
     @location(0) position: vec2<f32>,
@@ -35,7 +40,7 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<
 @fragment
 fn fs_main(@builtin(position) pixelCoord: vec4<f32>) -> @location(0) vec4<f32> {
     // Normalise coordinate to [0, 1] based on position in texture
-    let uv = pixelCoord.xy / uniforms.window_size;
+    let uv = (pixelCoord.xy - uniforms.viewport_offset) / uniforms.window_size;
     // texture_size 
 
     // let colour = random_colour(uv, uniforms.time);
@@ -43,6 +48,58 @@ fn fs_main(@builtin(position) pixelCoord: vec4<f32>) -> @location(0) vec4<f32> {
     return vec4<f32>(colour, 1.0);
 }
 
+// Bloom pass 1 of 3, into a texture sized target. Only texels over bloom_threshold contribute.
+@fragment
+fn fs_blur_horizontal(@builtin(position) pixelCoord: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = pixelCoord.xy / uniforms.texture_size;
+    let texel = vec2<f32>(1.0 / uniforms.texture_size.x, 0.0);
+    var weights = blur_weights();
+
+    var colour = bright_pass(textureSample(texture_data, texture_sampler, uv).xyz) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = texel * f32(i);
+        colour += bright_pass(textureSample(texture_data, texture_sampler, uv + offset).xyz) * weights[i];
+        colour += bright_pass(textureSample(texture_data, texture_sampler, uv - offset).xyz) * weights[i];
+    }
+    return vec4<f32>(colour, 1.0);
+}
+
+// Bloom pass 2 of 3, texture_data is bound to the horizontal pass' output.
+@fragment
+fn fs_blur_vertical(@builtin(position) pixelCoord: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = pixelCoord.xy / uniforms.texture_size;
+    let texel = vec2<f32>(0.0, 1.0 / uniforms.texture_size.y);
+    var weights = blur_weights();
+
+    var colour = textureSample(texture_data, texture_sampler, uv).xyz * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = texel * f32(i);
+        colour += textureSample(texture_data, texture_sampler, uv + offset).xyz * weights[i];
+        colour += textureSample(texture_data, texture_sampler, uv - offset).xyz * weights[i];
+    }
+    return vec4<f32>(colour, 1.0);
+}
+
+// Bloom pass 3 of 3, fs_main plus the blurred glow, onto the surface.
+@fragment
+fn fs_main_composite(@builtin(position) pixelCoord: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = (pixelCoord.xy - uniforms.viewport_offset) / uniforms.window_size;
+    let colour = textureSample(texture_data, texture_sampler, uv).xyz;
+    let bloom = textureSample(bloom_data, texture_sampler, uv).xyz;
+    return vec4<f32>(min(colour + bloom * uniforms.bloom_intensity, vec3<f32>(1.0)), 1.0);
+}
+
+// 9 tap gaussian, centre weight then each step outwards.
+fn blur_weights() -> array<f32, 5> {
+    return array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+}
+
+// Rec. 601 luma, matching Rgba::to_grayscale.
+fn bright_pass(colour: vec3<f32>) -> vec3<f32> {
+    let luminance = dot(colour, vec3<f32>(0.299, 0.587, 0.114));
+    return colour * step(uniforms.bloom_threshold, luminance);
+}
+
 // A simple hash function with global uniqueness based on position and a global seed
 fn hash(uv: vec2<f32>, seed: f32) -> f32 {
     let dot_product = dot(uv, vec2<f32>(12.9898, 78.233)) + seed;
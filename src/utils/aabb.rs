@@ -0,0 +1,154 @@
+use std::fmt::Debug;
+
+use num::{Num, NumCast};
+
+use crate::utils::vec2::{vec2, CoordSpace, Vec2};
+
+// Axis aligned bounding box, min & max are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Aabb<T: Debug + Num + Copy, U: CoordSpace> {
+    pub min: Vec2<T, U>,
+    pub max: Vec2<T, U>,
+}
+
+impl<T: Debug + Num + Copy + NumCast + PartialOrd, U: CoordSpace> Aabb<T, U> {
+    pub fn contains_point(&self, point: Vec2<T, U>) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+
+    pub fn clamp_point(&self, point: Vec2<T, U>) -> Vec2<T, U> {
+        point.clamp(self.min, self.max)
+    }
+
+    pub fn cast<DstT: Debug + Num + Copy + NumCast>(self) -> Aabb<DstT, U> {
+        Aabb {
+            min: self.min.cast(),
+            max: self.max.cast(),
+        }
+    }
+
+    pub fn size(&self) -> Vec2<T, U> {
+        vec2(self.max.x - self.min.x, self.max.y - self.min.y)
+    }
+
+    pub fn new(min: Vec2<T, U>, max: Vec2<T, U>) -> Self {
+        Self { min, max }
+    }
+
+    // Square box reaching radius out from centre on each axis.
+    pub fn from_centre_radius(centre: Vec2<T, U>, radius: T) -> Self {
+        Self {
+            min: centre - radius,
+            max: centre + radius,
+        }
+    }
+
+    // Touching edges count, like contains_point.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    // Smallest box around both.
+    pub fn union(&self, other: &Self) -> Self {
+        let min = |a: T, b: T| if b < a { b } else { a };
+        let max = |a: T, b: T| if b > a { b } else { a };
+        Self {
+            min: vec2(min(self.min.x, other.min.x), min(self.min.y, other.min.y)),
+            max: vec2(max(self.max.x, other.max.x), max(self.max.y, other.max.y)),
+        }
+    }
+
+    pub fn area(&self) -> T {
+        let size = self.size();
+        size.x * size.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec2::WorldSpace;
+
+    fn aabb(min: (i32, i32), max: (i32, i32)) -> Aabb<i32, WorldSpace> {
+        Aabb::new(vec2(min.0, min.1), vec2(max.0, max.1))
+    }
+
+    #[test]
+    fn from_centre_radius() {
+        let a = Aabb::<f64, WorldSpace>::from_centre_radius(vec2(1.0, -2.0), 3.0);
+        assert_eq!(a.min, vec2(-2.0, -5.0));
+        assert_eq!(a.max, vec2(4.0, 1.0));
+
+        // zero radius is a single point.
+        let point = Aabb::<i32, WorldSpace>::from_centre_radius(vec2(5, 5), 0);
+        assert_eq!(point, aabb((5, 5), (5, 5)));
+    }
+
+    #[test]
+    fn contains_point_is_inclusive() {
+        let a = aabb((0, 0), (10, 5));
+        assert!(a.contains_point(vec2(0, 0)));
+        assert!(a.contains_point(vec2(10, 5)));
+        assert!(a.contains_point(vec2(3, 4)));
+        assert!(!a.contains_point(vec2(11, 5)));
+        assert!(!a.contains_point(vec2(3, -1)));
+
+        let point = aabb((2, 2), (2, 2));
+        assert!(point.contains_point(vec2(2, 2)));
+        assert!(!point.contains_point(vec2(2, 3)));
+    }
+
+    #[test]
+    fn intersects_counts_tangent_boxes() {
+        let a = aabb((0, 0), (10, 10));
+        assert!(a.intersects(&aabb((5, 5), (15, 15))));
+        // sharing an edge, then just a corner.
+        assert!(a.intersects(&aabb((10, 0), (20, 10))));
+        assert!(a.intersects(&aabb((10, 10), (20, 20))));
+        assert!(!a.intersects(&aabb((11, 0), (20, 10))));
+        assert!(!a.intersects(&aabb((0, -5), (10, -1))));
+
+        // fully inside, either way round.
+        let inner = aabb((4, 4), (6, 6));
+        assert!(a.intersects(&inner) && inner.intersects(&a));
+
+        // zero size boxes still hit when on or inside the other.
+        assert!(a.intersects(&aabb((10, 3), (10, 3))));
+        assert!(!a.intersects(&aabb((12, 3), (12, 3))));
+    }
+
+    #[test]
+    fn union_covers_both() {
+        let a = aabb((0, 0), (2, 2));
+        let b = aabb((5, -3), (6, 1));
+        assert_eq!(a.union(&b), aabb((0, -3), (6, 2)));
+        assert_eq!(a.union(&b), b.union(&a));
+        assert_eq!(a.union(&a), a);
+
+        let point = aabb((1, 1), (1, 1));
+        assert_eq!(a.union(&point), a);
+    }
+
+    #[test]
+    fn area() {
+        assert_eq!(aabb((0, 0), (10, 5)).area(), 50);
+        assert_eq!(aabb((-3, -3), (3, 3)).area(), 36);
+        assert_eq!(aabb((2, 2), (2, 2)).area(), 0);
+        // a flat box has no area.
+        assert_eq!(aabb((0, 0), (10, 0)).area(), 0);
+    }
+}
@@ -1,4 +1,7 @@
+use std::ops::{Add, Mul, Sub};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgba {
     pub r: u8,
     pub g: u8,
@@ -20,6 +23,35 @@ impl Rgba {
         Self { r, g, b, a }
     }
 
+    // Tanner Helland's blackbody approximation, valid for 1000K (deep red) -> 40000K (blue).
+    pub fn from_blackbody(temperature_kelvin: f32) -> Self {
+        let temp = temperature_kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+        let r = if temp <= 66.0 {
+            255.0
+        } else {
+            329.698_73 * (temp - 60.0).powf(-0.133_204_76)
+        };
+        let g = if temp <= 66.0 {
+            99.470_8 * temp.ln() - 161.119_57
+        } else {
+            288.122_16 * (temp - 60.0).powf(-0.075_514_85)
+        };
+        let b = if temp >= 66.0 {
+            255.0
+        } else if temp <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (temp - 10.0).ln() - 305.044_8
+        };
+
+        Self::from_rgb(
+            r.clamp(0.0, 255.0) as u8,
+            g.clamp(0.0, 255.0) as u8,
+            b.clamp(0.0, 255.0) as u8,
+        )
+    }
+
     pub const fn from_u32(colour: u32) -> Self {
         Self {
             r: ((colour >> 24) & 0xFF) as u8,
@@ -28,4 +60,329 @@ impl Rgba {
             a: (colour & 0xFF) as u8,
         }
     }
+
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    // Per channel including alpha, t: 0.0 (self) -> 1.0 (other), clamped.
+    pub fn lerp(self, other: Rgba, t: f32) -> Self {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let lerp = |a: u8, b: u8| {
+            (f32::from(a) + (f32::from(b) - f32::from(a)) * t)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Self::from_rgba(
+            lerp(self.r, other.r),
+            lerp(self.g, other.g),
+            lerp(self.b, other.b),
+            lerp(self.a, other.a),
+        )
+    }
+
+    // Stylised glow ramp, t: 0.0 (blue) -> white -> red -> yellow -> 1.0 (white), clamped.
+    // Unlike from_blackbody this takes a normalised value rather than kelvin.
+    pub fn from_temperature(t: f32) -> Self {
+        const STOPS: [Rgba; 5] = [
+            Rgba::from_rgb(0, 0, 255),
+            Rgba::from_rgb(255, 255, 255),
+            Rgba::from_rgb(255, 0, 0),
+            Rgba::from_rgb(255, 255, 0),
+            Rgba::from_rgb(255, 255, 255),
+        ];
+        ramp(&STOPS, t)
+    }
+
+    // Porter-Duff "over" with straight (non-premultiplied) alpha, self on top of bg.
+    pub fn blend_over(self, bg: Rgba) -> Self {
+        let src_a = f32::from(self.a) / 255.0;
+        let bg_a = f32::from(bg.a) / 255.0 * (1.0 - src_a);
+        let out_a = src_a + bg_a;
+        if out_a <= 0.0 {
+            return Self::from_rgba(0, 0, 0, 0);
+        }
+
+        let blend = |s: u8, b: u8| {
+            ((f32::from(s) * src_a + f32::from(b) * bg_a) / out_a)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Self::from_rgba(
+            blend(self.r, bg.r),
+            blend(self.g, bg.g),
+            blend(self.b, bg.b),
+            (out_a * 255.0).round() as u8,
+        )
+    }
+
+    // Rec. 601 luma, alpha is kept.
+    pub fn to_grayscale(self) -> Self {
+        let luma =
+            (0.299 * f32::from(self.r) + 0.587 * f32::from(self.g) + 0.114 * f32::from(self.b))
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        Self::from_rgba(luma, luma, luma, self.a)
+    }
+
+    // Adds amount to the HSV saturation (clamped to [0, 1]), negative amounts desaturate.
+    pub fn saturate(self, amount: f32) -> Self {
+        let (h, s, v) = self.to_hsv();
+        Self::from_hsv(h, (s + amount).clamp(0.0, 1.0), v).with_alpha(self.a)
+    }
+
+    // h in [0, 360), s & v in [0, 1].
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| f32::from(c) / 255.0);
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let s = if max == 0.0 { 0.0 } else { delta / max };
+        (h, s, max)
+    }
+
+    // Inverse of to_hsv, h wraps. const so colour tables can live in consts.rs.
+    pub const fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let c = v * s;
+        let h = h % 360.0;
+        let h = if h < 0.0 { h + 360.0 } else { h } / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        Self::from_rgb(
+            ((r + m) * 255.0).round() as u8,
+            ((g + m) * 255.0).round() as u8,
+            ((b + m) * 255.0).round() as u8,
+        )
+    }
+}
+
+// Scales the colour channels, alpha is left alone. Clamped to [0, 255].
+impl Mul<f32> for Rgba {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        let scale = |c: u8| (f32::from(c) * rhs).round().clamp(0.0, 255.0) as u8;
+        Self::from_rgba(scale(self.r), scale(self.g), scale(self.b), self.a)
+    }
+}
+
+// Saturating per channel, including alpha, for additive blending.
+impl Add for Rgba {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_rgba(
+            self.r.saturating_add(rhs.r),
+            self.g.saturating_add(rhs.g),
+            self.b.saturating_add(rhs.b),
+            self.a.saturating_add(rhs.a),
+        )
+    }
+}
+
+// Saturating per channel, including alpha, for darkening.
+impl Sub for Rgba {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_rgba(
+            self.r.saturating_sub(rhs.r),
+            self.g.saturating_sub(rhs.g),
+            self.b.saturating_sub(rhs.b),
+            self.a.saturating_sub(rhs.a),
+        )
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgba {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Self::from_rgb(r, g, b)
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Rgba {
+    fn from((r, g, b, a): (u8, u8, u8, u8)) -> Self {
+        Self::from_rgba(r, g, b, a)
+    }
+}
+
+// Straight u8 -> [0, 1] mapping, the surface is Rgba8Unorm so no sRGB conversion is needed.
+impl From<Rgba> for wgpu::Color {
+    fn from(colour: Rgba) -> Self {
+        Self {
+            r: f64::from(colour.r) / 255.0,
+            g: f64::from(colour.g) / 255.0,
+            b: f64::from(colour.b) / 255.0,
+            a: f64::from(colour.a) / 255.0,
+        }
+    }
+}
+
+// Perceptually uniform "inferno" style colour map, t: 0.0 (cold) -> 1.0 (hot).
+// t is clamped, stops are linearly interpolated.
+pub fn heat_map(t: f64) -> Rgba {
+    const STOPS: [Rgba; 5] = [
+        Rgba::from_rgb(0, 0, 4),
+        Rgba::from_rgb(87, 16, 110),
+        Rgba::from_rgb(188, 55, 84),
+        Rgba::from_rgb(249, 142, 9),
+        Rgba::from_rgb(252, 255, 164),
+    ];
+    ramp(&STOPS, t as f32)
+}
+
+// Evenly spaced stops (at least 2), t: 0.0 (first) -> 1.0 (last) is clamped & linearly interpolated.
+fn ramp(stops: &[Rgba], t: f32) -> Rgba {
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+
+    let scaled = t * (stops.len() - 1) as f32;
+    let index = (scaled as usize).min(stops.len() - 2);
+    stops[index].lerp(stops[index + 1], scaled - index as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_saturates_on_overflow() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let sum = Rgba::from_rgba(a, a, a, a) + Rgba::from_rgba(b, b, b, b);
+                let expected = (u16::from(a) + u16::from(b)).min(255) as u8;
+                assert_eq!(sum, Rgba::from_rgba(expected, expected, expected, expected));
+            }
+        }
+        assert_eq!(
+            Rgba::from_rgba(200, 10, 255, 128) + Rgba::from_rgba(100, 10, 1, 200),
+            Rgba::from_rgba(255, 20, 255, 255)
+        );
+    }
+
+    #[test]
+    fn sub_saturates_on_underflow() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let diff = Rgba::from_rgba(a, a, a, a) - Rgba::from_rgba(b, b, b, b);
+                let expected = (i16::from(a) - i16::from(b)).max(0) as u8;
+                assert_eq!(
+                    diff,
+                    Rgba::from_rgba(expected, expected, expected, expected)
+                );
+            }
+        }
+        assert_eq!(
+            Rgba::from_rgba(10, 200, 0, 128) - Rgba::from_rgba(100, 50, 1, 200),
+            Rgba::from_rgba(0, 150, 0, 0)
+        );
+    }
+
+    #[test]
+    fn mul_by_zero_and_one_leaves_alpha() {
+        for c in 0..=255u8 {
+            let colour = Rgba::from_rgba(c, 255 - c, c / 2, c);
+            assert_eq!(colour * 0.0, Rgba::from_rgba(0, 0, 0, c));
+            assert_eq!(colour * 1.0, colour);
+        }
+        // clamped at both ends, alpha still untouched.
+        assert_eq!(
+            Rgba::from_rgba(200, 100, 0, 7) * 2.0,
+            Rgba::from_rgba(255, 200, 0, 7)
+        );
+        assert_eq!(
+            Rgba::from_rgba(200, 100, 0, 7) * -1.0,
+            Rgba::from_rgba(0, 0, 0, 7)
+        );
+    }
+
+    #[test]
+    fn blackbody_runs_red_to_white_to_blue() {
+        // below 1900K there's no blue at all.
+        assert_eq!(Rgba::from_blackbody(1000.0), Rgba::from_rgb(255, 67, 0));
+
+        let red = Rgba::from_blackbody(2000.0);
+        assert!(red.r > red.g && red.g > red.b);
+        assert_eq!(red, Rgba::from_rgb(255, 136, 13));
+
+        let white = Rgba::from_blackbody(6500.0);
+        assert!(white.r > 200 && white.g > 200 && white.b > 200);
+        assert_eq!(white, Rgba::from_rgb(255, 254, 250));
+
+        let blue_white = Rgba::from_blackbody(12000.0);
+        assert!(blue_white.b >= blue_white.r);
+    }
+
+    #[test]
+    fn lerp_temperature_blend_and_grayscale() {
+        let black = Rgba::from_rgba(0, 0, 0, 0);
+        let white = Rgba::from_rgb(255, 255, 255);
+        assert_eq!(black.lerp(white, 0.0), black);
+        assert_eq!(black.lerp(white, 1.0), white);
+        assert_eq!(black.lerp(white, 0.5), Rgba::from_rgba(128, 128, 128, 128));
+        // t is clamped & NaN falls back to self.
+        assert_eq!(black.lerp(white, 2.0), white);
+        assert_eq!(black.lerp(white, -1.0), black);
+        assert_eq!(black.lerp(white, f32::NAN), black);
+
+        assert_eq!(Rgba::from_temperature(0.0), Rgba::from_rgb(0, 0, 255));
+        assert_eq!(Rgba::from_temperature(0.25), white);
+        assert_eq!(Rgba::from_temperature(0.5), Rgba::from_rgb(255, 0, 0));
+        assert_eq!(Rgba::from_temperature(1.0), white);
+        assert_eq!(Rgba::from_temperature(-1.0), Rgba::from_temperature(0.0));
+        assert_eq!(Rgba::from_temperature(2.0), Rgba::from_temperature(1.0));
+
+        let red = Rgba::from_rgb(255, 0, 0);
+        assert_eq!(red.blend_over(white), red);
+        assert_eq!(red.with_alpha(0).blend_over(white), white);
+        assert_eq!(black.blend_over(black), black);
+        let half_white = white.with_alpha(128);
+        assert_eq!(
+            half_white.blend_over(Rgba::from_rgb(0, 0, 0)),
+            Rgba::from_rgb(128, 128, 128)
+        );
+
+        assert_eq!(white.to_grayscale(), white);
+        assert_eq!(black.to_grayscale(), black);
+        assert_eq!(
+            red.with_alpha(7).to_grayscale(),
+            Rgba::from_rgba(76, 76, 76, 7)
+        );
+    }
+
+    #[test]
+    fn hsv_round_trips_the_hue() {
+        for (s, v) in [(1.0, 1.0), (0.5, 0.8)] {
+            for h in (0..360).step_by(7).map(|h| h as f32) {
+                let (h2, s2, v2) = Rgba::from_hsv(h, s, v).to_hsv();
+                // hues wrap, 359.9 is as close to 0 as 0.1 is.
+                let diff = (h2 - h).rem_euclid(360.0);
+                assert!(diff.min(360.0 - diff) < 1.0, "hue {h} came back as {h2}");
+                assert!((s2 - s).abs() < 0.01 && (v2 - v).abs() < 0.01);
+            }
+        }
+
+        assert_eq!(Rgba::from_hsv(120.0, 1.0, 1.0), Rgba::from_rgb(0, 255, 0));
+        assert_eq!(
+            Rgba::from_hsv(-120.0, 1.0, 1.0),
+            Rgba::from_hsv(240.0, 1.0, 1.0)
+        );
+        assert_eq!(crate::utils::consts::RAINBOW[4], Rgba::from_rgb(0, 255, 0));
+    }
 }
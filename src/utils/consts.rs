@@ -7,7 +7,19 @@ pub const GRAY: Rgba = Rgba::from_rgb(44, 44, 44);
 pub const LIGHT_GRAY: Rgba = Rgba::from_rgb(65, 65, 65);
 pub const DARK_GRAY: Rgba = Rgba::from_rgb(20, 20, 20);
 pub const RED: Rgba = Rgba::from_rgb(255, 40, 40);
+pub const BLUE: Rgba = Rgba::from_rgb(40, 40, 255);
+pub const YELLOW: Rgba = Rgba::from_rgb(255, 230, 40);
 pub const BLACK: Rgba = Rgba::from_rgb(0, 0, 0);
+pub const RAINBOW: [Rgba; 12] = {
+    // fully saturated hues, 30 degrees apart starting at red
+    let mut colours = [BLACK; 12];
+    let mut i = 0;
+    while i < colours.len() {
+        colours[i] = Rgba::from_hsv(i as f32 * 30.0, 1.0, 1.0);
+        i += 1;
+    }
+    colours
+};
 
 // Generic Parameters (*)
 pub const INIT_TITLE: &str = "Gravity Sim";
@@ -15,6 +27,10 @@ pub const INIT_WIDTH: u32 = 800;
 pub const INIT_HEIGHT: u32 = 600;
 pub const INIT_SCALE: u32 = 2;
 pub const INIT_DRAW_SIZE: i32 = 8;
+pub const RING_INNER_RADIUS: i32 = 5; // Shape::CircleRing when cycled to
+pub const ELLIPSE_ASPECT: f32 = 2.0; // width / height of Shape::Ellipse* when cycled to
+pub const MAX_CURVE_ERROR: f32 = 0.5; // bezier control points within half this of the chord draw it as a line
+pub const MAX_CURVE_DEPTH: u32 = 10; // bezier subdivision limit, at most 2^10 segments
 pub const SIM_MAX_SCALE: u32 = 10;
 pub const MAX_DRAW_SIZE: i32 = 500;
 
@@ -23,14 +39,108 @@ pub const MOUSE_HOLD_THRESHOLD_MS: u64 = 250;
 pub const MOUSE_PRESS_COOLDOWN_MS: u64 = 100;
 pub const MOUSE_DRAG_THRESHOLD_PX: f64 = 5.0;
 pub const KEY_COOLDOWN_MS: u64 = 100;
+pub const TOUCH_PINCH_SCALE: f64 = 50.0; // pinch distance change (px) per scroll wheel line
+pub const SCROLL_PIXELS_PER_NOTCH: f64 = 50.0; // trackpad scroll (px) per zoom step
 pub const TARGET_FPS: f64 = 120.0;
 pub const FRAME_TIME_MS: f64 = 1000.0 / TARGET_FPS;
 pub const MS_BUFFER: f64 = 3.0;
+pub const FRAME_TIME_WINDOW: usize = 60; // number of frames in the moving average
+pub const HELP_BACKGROUND: Rgba = Rgba::from_rgba(0, 0, 0, 180);
+pub const PHYSICS_DT: f64 = 1.0 / 240.0; // fixed physics timestep in seconds
+pub const MAX_PHYSICS_STEPS: u32 = 8; // caps catch-up steps per frame, avoids a spiral of death.
+pub const SIM_SPEEDS: [f32; 6] = [0.0, 0.25, 0.5, 1.0, 2.0, 4.0]; // indexed by Ctrl+0..5
+
+// bloom (app.rs / backend.rs)
+pub const BLOOM_THRESHOLD: f32 = 0.6; // luminance [0, 1] a texel needs before it glows
+pub const INIT_BLOOM_INTENSITY: f32 = 1.0; // blurred glow is scaled by this before being added
+pub const MAX_BLOOM_INTENSITY: f32 = 2.0;
+pub const BLOOM_INTENSITY_STEP: f32 = 0.1; // per Alt+= / Alt+-
+
+// inspector.rs
+pub const INSPECTOR_TITLE: &str = "Inspector";
+pub const INSPECTOR_WIDTH: u32 = 480;
+pub const INSPECTOR_HEIGHT: u32 = 640;
+pub const INSPECTOR_SCALE: u32 = 2; // window pixels per texture pixel
+pub const INSPECTOR_SNAPSHOT_MS: u64 = 1000;
+pub const INSPECTOR_POLL_MS: u64 = 16;
+
+// cell_sim.rs
+pub const SELECTION_DASH_LEN: i32 = 3; // cells per dash of the selection outline
+pub const SELECTION_COLOUR: Rgba = Rgba::from_rgb(80, 160, 255);
+pub const RESET_SEED: u64 = 0x5EED; // InitialPattern::RandomDensity, resets are reproducible
+pub const RESET_DENSITY: f32 = 0.25;
+pub const MAX_COLOUR_AGE: u16 = 255; // generations for an Alive cell to fade GREEN -> WHITE
+pub const POP_HISTORY_LEN: usize = 120; // generations kept by PopHistory
+pub const SPARKLINE_LEN: usize = 60; // most recent generations drawn in the logged sparkline
+pub const PNG_EXPORT_PREFIX: &str = "cell_sim_"; // F2 saves "cell_sim_<unix secs>.png", F3 loads the newest
+pub const RLE_PATH: &str = "cell_sim.rle"; // Ctrl+S / Ctrl+L
+
+// falling_everything.rs
+pub const SCENE_PATH: &str = "scene.json"; // Ctrl+S / Ctrl+L
+pub const BODY_DENSITY: f32 = 0.001; // mass per square pixel, an 18x18 body is ~0.3 mass
+pub const WORLD_HALF_SIZE: f64 = 1000.0; // default world bounds, centred on the origin
+pub const WALL_THICKNESS: i32 = 4;
+pub const MAX_BODY_VELOCITY: f32 = 200.0; // world units/s mapped to the hot end of heat_map
+pub const WIND_DRAG: f32 = 0.1; // C_drag, wind force = C_drag * (wind - velocity)
+pub const WIND_STEP: f32 = 10.0; // Shift+Plus from still air, world units/s
+pub const WIND_SCALE_STEP: f32 = 1.25; // Shift+Plus/Minus multiply/divide wind speed by this
+pub const WIND_ARROW_COLOUR: Rgba = Rgba::from_rgba(255, 255, 255, 120);
+pub const TORQUE_ARC_RADIUS: u32 = 10;
+pub const TORQUE_ARC_SCALE: f32 = 0.5; // arc sweep in radians per rad/s of angular velocity
+pub const MOTOR_ANGULAR_ACCEL: f32 = 4.0; // rad/s² Q/E spin the first body at, whatever its inertia
+pub const COLLISION_FLASH_FRAMES: u8 = 3; // physics steps a body is outlined white for after a collision
+pub const INIT_PHYSICS_SUBSTEPS: u32 = 4;
+pub const MAX_PHYSICS_SUBSTEPS: u32 = 20; // Ctrl+= / Ctrl+-, min 1
+pub const CIRCLE_OUTLINE_SIDES: u32 = 16; // sides of the polygon standing in for a circle body's object
+pub const STATIC_WALL_THICKNESS: f32 = 12.0; // walls lining the viewport, thick enough that bodies don't tunnel through
+pub const WALL_RESTITUTION: f32 = 0.5; // fraction of the speed into a static wall kept on bouncing off
+pub const SPRING_STIFFNESS: f32 = 2.0; // Shift+click joints, force per unit of extension
+pub const SPRING_DAMPING: f32 = 0.2; // force per unit/s of the bodies closing or separating
+pub const JOINT_PICK_RADIUS: f32 = 18.0; // Shift+click picks the closest body centre within this
+pub const CCD_STEPS: u32 = 8; // bisections finding where a fast body first touches a static wall
+pub const DEFAULT_FRICTION: f32 = 0.4; // Coulomb coefficient of spawned bodies, tangential impulse <= this * normal impulse
+pub const GRAVITY: f32 = 9.8; // world units/s², KeyG points it down the screen (+y)
 
 // gravity_sim.rs
+pub const MAX_PARTICLES: usize = 1000;
+pub const MAX_PARTICLES_STEP: usize = 100; // Ctrl+PageUp/PageDown
+pub const SIMULATION_STATE_PATH: &str = "simulation_state.json"; // F5 / F9
+pub const INIT_SUB_STEPS: u32 = 4;
+pub const MAX_SUB_STEPS: u32 = 32; // BracketLeft/BracketRight, min 1
+pub const MIN_STEP_SAFETY: f64 = 0.5 * INIT_DRAW_SIZE as f64; // max world units moved per sub step
 pub const MOUSE_DRAWBACK_MULTIPLIER: f64 = 10.0;
+pub const CURSOR_ATTRACT_STRENGTH: f64 = 1e6; // attract mode, px/s² of pull 1px from the cursor (before softening)
+pub const EXPLOSION_STRENGTH: f64 = 100.0; // velocity at 1 unit distance, per second of charge
+pub const EXPLOSION_MAX_CHARGE_MS: u64 = 1000;
+pub const SPEED_STATS_INTERVAL: usize = 10; // frames between max speed recalculations (particle colouring)
 pub const CAMERA_RESISTANCE: f64 = 115.0 / TARGET_FPS; // reduce camera speed by this factor per second
 pub const CAMERA_SPEED: f64 = 5.0 / TARGET_FPS; // gets normalised to simulation size per second
+pub const GRAVITY_TUNER_STEP: f64 = 1.1; // Ctrl+G tuner multiplies/divides the active parameter by this
+pub const PHYSICS_MULTIPLIER_STEP: f64 = 10.0; // Shift+G/Shift+F multiply/divide by this
+pub const CAMERA_FIT_MS: u64 = 500; // camera transition time when fitting to particles
+pub const CAMERA_FIT_MARGIN: f64 = 0.1; // fraction of the particles' bounds padded onto each side when fitting
+pub const POTENTIAL_GRID_STEP: i32 = 8; // min render pixels between potential field samples
+pub const POTENTIAL_MAX_SAMPLES: i32 = 10_000; // step grows past POTENTIAL_GRID_STEP to stay under this
+pub const COMET_RADIUS: f64 = 3.0;
+pub const COMET_DENSITY_RATIO: f64 = 0.2; // relative to earth density, mostly ice
+pub const COMET_TRAIL_LENGTH: f64 = 12.0; // max render pixels, reached at the current max speed
+pub const COMET_COLOUR: Rgba = Rgba::from_rgb(170, 220, 255);
+pub const STAR_RADIUS: f64 = 20.0; // spawns with SUN_MASS at this radius
+pub const STAR_GLOW_COLOUR: Rgba = Rgba::from_rgb(120, 110, 70);
+pub const STAR_GLOW_SCALE: f64 = 1.4;
+pub const BLACK_HOLE_RADIUS: f64 = 12.0; // collision radius, drawn at half this
+pub const BLACK_HOLE_MASS: f64 = 1000.0 * SUN_MASS;
+pub const BLACK_HOLE_COLOUR: Rgba = Rgba::from_rgb(10, 10, 10);
+pub const EVENT_HORIZON_COLOUR: Rgba = Rgba::from_rgb(255, 200, 120);
+pub const LENSING_RADIUS_SCALE: f64 = 2.0; // pixels within this * radius of a black hole are lensed
+pub const LENSING_MAX_DISPLACEMENT: f64 = 6.0; // render pixels, at the black hole's centre
+pub const INSPECT_ARROW_SCALE: f64 = 20.0; // render pixels per decade of magnitude, log10(1 + |v|) * this
+pub const INSPECT_FORCE_SCALE: f64 = 0.5; // force arrow length relative to the velocity arrow
+pub const ORBIT_PREDICTION_STEPS: usize = 960; // PHYSICS_DT steps the selected particle's path is predicted for
+pub const ORBIT_SAMPLE_STEPS: usize = 32; // steps per bezier segment of the predicted path
+pub const VELOCITY_ARROW_SCALE: f64 = 2.0; // render pixels per unit of velocity, KeyV overlay
+pub const GRAV_SOFTENING: f64 = 5.0; // world units, force uses r² + softening² so close passes can't blow up
+pub const SOFTENING_RING_COLOUR: Rgba = Rgba::from_rgba(255, 255, 255, 40);
 
 pub const SMALL_VALUE: f64 = 1e-6;
 pub const COLLISION_RESTITUTION: f64 = 0.8;
@@ -42,8 +152,10 @@ pub const DISTANCE_SCALE: f64 = 1.1970456e+15; // pixel to meters conversion sca
 
 pub const GRAV_CONST: f64 = 6.6743e-11;
 pub const EARTH_MASS: f64 = 5.972e24;
-pub const EARTH_DENSITY: f64 = 5514.0 * DISTANCE_SCALE;
+pub const EARTH_DENSITY_SI: f64 = 5514.0; // kg/m^3
+pub const EARTH_DENSITY: f64 = EARTH_DENSITY_SI * DISTANCE_SCALE;
 pub const SUN_MASS: f64 = 1.989e30;
+pub const SUN_TEMPERATURE: f64 = 5778.0; // kelvin
 
 /*
     Particle Conversion to real world units -- to not spaz float precision
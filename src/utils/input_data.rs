@@ -1,11 +1,19 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use educe::Educe;
-use winit::keyboard::KeyCode;
+use winit::{
+    event::{ElementState, Touch, TouchPhase},
+    keyboard::KeyCode,
+};
 
 use crate::utils::{
-    consts::{MOUSE_DRAG_THRESHOLD_PX, MOUSE_HOLD_THRESHOLD_MS},
-    vec2::{Vec2, WindowSpace},
+    consts::{
+        KEY_COOLDOWN_MS, MOUSE_DRAG_THRESHOLD_PX, MOUSE_HOLD_THRESHOLD_MS, TOUCH_PINCH_SCALE,
+    },
+    vec2::{vec2, Vec2, WindowSpace},
 };
 
 #[derive(Educe, Clone, Copy)]
@@ -16,7 +24,14 @@ pub struct MouseInput {
     pub pos: Vec2<f64, WindowSpace>,
 }
 
-#[derive(Educe, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchPoint {
+    pub id: u64,
+    pub pos: Vec2<f64, WindowSpace>,
+    pub phase: TouchPhase,
+}
+
+#[derive(Educe, Clone)]
 #[educe(Debug)]
 pub struct InputData {
     pub mouse_pos: Vec2<f64, WindowSpace>,
@@ -29,6 +44,8 @@ pub struct InputData {
     // this records the release event, holding its current state, the time of release and pos of release
     // this is currently (13/11) used for the gravity_sim angry birds particle fire!
     pub mouse_released: MouseInput, // records an event's current state, with timestamp of press
+    // right button press, no hold or drag tracking. state is only true on the frame it happened.
+    pub right_click: MouseInput,
 
     // TODO(TOM): should keys_held have a cooldown?
     // both fields have a tap_cooldown, however "keys_tapped is reset each frame"
@@ -38,6 +55,49 @@ pub struct InputData {
     pub keys_pressed: [bool; 256],
     #[educe(Debug(ignore))]
     pub tap_cooldowns: [Instant; 256],
+    // when each currently held key was first pressed, None if not held.
+    #[educe(Debug(ignore))]
+    pub keys_held_since: [Option<Instant>; 256],
+
+    // active touches by finger id, removed once they end.
+    pub touches: HashMap<u64, TouchPoint>,
+    // the touch currently acting as the mouse, None when the real mouse (or nothing) is.
+    pub mouse_touch: Option<u64>,
+    // distance between the two touches of a pinch, on the previous touch event.
+    pub pinch_distance: Option<f64>,
+    // scroll wheel notches (or pinch equivalent) not yet consumed, positive is up / zoom in.
+    pub scroll_delta: f64,
+}
+
+impl Default for MouseInput {
+    fn default() -> Self {
+        Self {
+            state: false,
+            time: Instant::now(),
+            pos: vec2(0.0, 0.0),
+        }
+    }
+}
+
+// Nothing held, pressed or touching.
+impl Default for InputData {
+    fn default() -> Self {
+        Self {
+            mouse_pos: vec2(0.0, 0.0),
+            mouse_down: false,
+            mouse_pressed: MouseInput::default(),
+            mouse_released: MouseInput::default(),
+            right_click: MouseInput::default(),
+            keys_held: [false; 256],
+            keys_pressed: [false; 256],
+            tap_cooldowns: [Instant::now(); 256],
+            keys_held_since: [None; 256],
+            touches: HashMap::new(),
+            mouse_touch: None,
+            pinch_distance: None,
+            scroll_delta: 0.0,
+        }
+    }
 }
 
 impl InputData {
@@ -48,6 +108,101 @@ impl InputData {
         self.keys_held[key as usize]
     }
 
+    // how long a key has been held for, e.g. for charge-up mechanics.
+    pub fn held_duration(&self, key: KeyCode) -> Option<Duration> {
+        self.keys_held_since[key as usize].map(|since| since.elapsed())
+    }
+
+    // Key press or release, presses within KEY_COOLDOWN_MS of the last are ignored.
+    pub fn register_key(&mut self, key: KeyCode, state: ElementState) {
+        let code = key as usize;
+        if code > 255 {
+            return;
+        }
+        match state {
+            ElementState::Pressed => {
+                // key repeats don't reset the hold.
+                if self.keys_held_since[code].is_none() {
+                    self.keys_held_since[code] = Some(Instant::now());
+                }
+                if self.tap_cooldowns[code].elapsed() > Duration::from_millis(KEY_COOLDOWN_MS) {
+                    self.keys_held[code] = true;
+                    self.keys_pressed[code] = true;
+                    self.tap_cooldowns[code] = Instant::now();
+                }
+            }
+            ElementState::Released => {
+                self.keys_held[code] = false;
+                self.keys_held_since[code] = None;
+            }
+        }
+    }
+
+    // touch with the lowest id, i.e. the first finger down.
+    pub fn primary_touch(&self) -> Option<&TouchPoint> {
+        self.touches.values().min_by_key(|touch| touch.id)
+    }
+
+    // Tracks touches, a single finger acts as the mouse & a two finger pinch as the scroll wheel.
+    pub fn register_touch(&mut self, touch: &Touch) {
+        let point = TouchPoint {
+            id: touch.id,
+            pos: vec2(touch.location.x, touch.location.y),
+            phase: touch.phase,
+        };
+        // decided before updating touches, an ending primary is no longer in the map after.
+        let is_primary = self
+            .primary_touch()
+            .is_none_or(|primary| primary.id >= touch.id);
+        match touch.phase {
+            TouchPhase::Started | TouchPhase::Moved => {
+                self.touches.insert(touch.id, point);
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.remove(&touch.id);
+            }
+        }
+
+        if self.touches.len() == 2 {
+            let mut points = self.touches.values();
+            let (a, b) = (points.next().unwrap(), points.next().unwrap());
+            let distance = (a.pos - b.pos).length();
+            if let Some(prev_distance) = self.pinch_distance {
+                self.scroll_delta += (distance - prev_distance) / TOUCH_PINCH_SCALE;
+            }
+            self.pinch_distance = Some(distance);
+        } else {
+            self.pinch_distance = None;
+        }
+
+        // only the primary finger drives the mouse, & only when the real mouse isn't held.
+        if !is_primary {
+            return;
+        }
+        let event = MouseInput {
+            state: true,
+            time: Instant::now(),
+            pos: point.pos,
+        };
+        let owns_mouse = self.mouse_touch == Some(touch.id);
+        match touch.phase {
+            TouchPhase::Started if !self.mouse_down => {
+                self.mouse_pos = point.pos;
+                self.mouse_down = true;
+                self.mouse_pressed = event;
+                self.mouse_touch = Some(touch.id);
+            }
+            TouchPhase::Moved if owns_mouse || !self.mouse_down => self.mouse_pos = point.pos,
+            TouchPhase::Ended | TouchPhase::Cancelled if owns_mouse => {
+                self.mouse_pos = point.pos;
+                self.mouse_down = false;
+                self.mouse_released = event;
+                self.mouse_touch = None;
+            }
+            _ => {}
+        }
+    }
+
     pub fn is_mouse_pressed(&self) -> bool {
         self.mouse_pressed.state
     }
@@ -92,3 +247,46 @@ impl InputData {
                 < Duration::from_millis(MOUSE_HOLD_THRESHOLD_MS)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::{dpi::PhysicalPosition, event::DeviceId};
+
+    #[test]
+    fn held_duration_runs_from_press_to_release() {
+        let mut inputs = InputData::default();
+        assert_eq!(inputs.held_duration(KeyCode::KeyE), None);
+
+        inputs.register_key(KeyCode::KeyE, ElementState::Pressed);
+        let held = inputs.held_duration(KeyCode::KeyE).unwrap();
+        assert!(held < Duration::from_millis(10), "{held:?}");
+
+        inputs.register_key(KeyCode::KeyE, ElementState::Released);
+        assert_eq!(inputs.held_duration(KeyCode::KeyE), None);
+    }
+
+    fn touch(id: u64, x: f64, phase: TouchPhase) -> Touch {
+        Touch {
+            // SAFETY: only stored in the Touch, never passed back into winit.
+            device_id: unsafe { DeviceId::dummy() },
+            phase,
+            location: PhysicalPosition::new(x, 0.0),
+            force: None,
+            id,
+        }
+    }
+
+    #[test]
+    fn primary_touch_is_the_lowest_id() {
+        let mut inputs = InputData::default();
+        inputs.register_touch(&touch(7, 100.0, TouchPhase::Started));
+        inputs.register_touch(&touch(3, 300.0, TouchPhase::Started));
+        assert_eq!(inputs.touches.len(), 2);
+        assert_eq!(inputs.primary_touch().unwrap().id, 3);
+
+        // spreading the pinch by 100px scrolls up.
+        inputs.register_touch(&touch(7, 0.0, TouchPhase::Moved));
+        assert!((inputs.scroll_delta - 100.0 / TOUCH_PINCH_SCALE).abs() < 1e-9);
+    }
+}
@@ -2,13 +2,14 @@ use educe::Educe;
 use num::{Float, Num, NumCast, Signed};
 use paste::paste;
 use std::{
-    fmt::{Debug, Formatter},
+    fmt::{Debug, Display, Formatter},
     marker::PhantomData,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 use wgpu::Texture;
 
-pub trait CoordSpace {}
+// Copy so the derived Clone/Copy on Vec2<T, U> only depend on T.
+pub trait CoordSpace: Copy + Clone {}
 macro_rules! create_coordinate_space {
     ($name:ident) => {
         #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -23,6 +24,7 @@ create_coordinate_space!(CentredTextureSpace); // Texture space situated around
 create_coordinate_space!(WorldSpace); // Space of the world, any number
 
 #[derive(Educe, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 #[educe(Debug)]
 pub struct Vec2<T: Debug, U: CoordSpace> {
     #[educe(Debug(method("fmt_limited_precision")))]
@@ -30,9 +32,33 @@ pub struct Vec2<T: Debug, U: CoordSpace> {
     #[educe(Debug(method("fmt_limited_precision")))]
     pub y: T,
     #[educe(Debug(ignore))]
+    #[cfg_attr(feature = "serde", serde(skip))]
     _unit: PhantomData<U>,
 }
 
+// Written as {"x", "y", "space"}, the space tag is just for readability,
+// deserialising ignores it as the space is already fixed by the type.
+#[cfg(feature = "serde")]
+impl<T: Debug + serde::Serialize, U: CoordSpace> serde::Serialize for Vec2<T, U> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let space = std::any::type_name::<U>();
+        let space = space.rsplit("::").next().unwrap_or(space);
+
+        let mut state = serializer.serialize_struct("Vec2", 3)?;
+        state.serialize_field("x", &self.x)?;
+        state.serialize_field("y", &self.y)?;
+        state.serialize_field("space", space)?;
+        state.end()
+    }
+}
+
+// Unit vector at angle radians from the +x axis.
+pub fn vec2_from_angle<T: Float + Debug, U: CoordSpace>(angle: T) -> Vec2<T, U> {
+    vec2(angle.cos(), angle.sin())
+}
+
 #[inline]
 pub fn vec2<T: Debug, U: CoordSpace>(p1: T, p2: T) -> Vec2<T, U> {
     Vec2 {
@@ -142,15 +168,82 @@ impl<T: Debug + Signed + Copy + NumCast, U: CoordSpace> Vec2<T, U> {
     }
 }
 
+impl<T: Debug + Float + NumCast + Signed, U: CoordSpace> Vec2<T, U> {
+    // Counter-clockwise about the origin (with y up).
+    pub fn rotate(self, angle_radians: T) -> Self {
+        let (s, c) = angle_radians.sin_cos();
+        Vec2 {
+            x: self.x * c - self.y * s,
+            y: self.x * s + self.y * c,
+            _unit: PhantomData,
+        }
+    }
+
+    pub fn rotate_around(self, pivot: Vec2<T, U>, angle_radians: T) -> Self {
+        (self - pivot).rotate(angle_radians) + pivot
+    }
+
+    // Signed angle in radians from self to other, in (-PI, PI].
+    pub fn angle_between(self, other: Vec2<T, U>) -> T {
+        T::atan2(self.cross_product(other), self.dot_product(other))
+    }
+
+    // Angle in radians from the +x axis.
+    pub fn angle(self) -> T {
+        T::atan2(self.y, self.x)
+    }
+
+    // The component of self along axis, axis needn't be normalised.
+    pub fn project_onto(self, axis: Vec2<T, U>) -> Self {
+        axis * (self.dot_product(axis) / axis.dot_product(axis))
+    }
+
+    // The component of self perpendicular to axis.
+    pub fn reject_from(self, axis: Vec2<T, U>) -> Self {
+        self - self.project_onto(axis)
+    }
+
+    // Straight line from self (t = 0) to other (t = 1), t is clamped to [0, 1].
+    pub fn lerp(self, other: Vec2<T, U>, t: T) -> Self {
+        let t = t.max(T::zero()).min(T::one());
+        self + (other - self) * t
+    }
+
+    // Sweeps the angle from self to other the short way round, the length is lerped.
+    // Unit vectors stay unit length, unlike lerp. Falls back to lerp from a zero vector.
+    pub fn slerp(self, other: Vec2<T, U>, t: T) -> Self {
+        let t = t.max(T::zero()).min(T::one());
+        let (from, to) = (self.length(), other.length());
+        if from <= T::zero() || to <= T::zero() {
+            return self.lerp(other, t);
+        }
+
+        let angle = self.angle_between(other);
+        let length = from + (to - from) * t;
+        self.rotate(angle * t) * (length / from)
+    }
+}
+
 // region: Vec2 CoordSpace translations
+// Divides/multiplies through f64, so a fractional texture_scale (e.g. 1.5) isn't truncated to 1
+// before the division, integer vectors still truncate the result like integer division would.
+fn div_scale<T: NumCast, X: num::ToPrimitive>(n: T, scale: X) -> T {
+    T::from(n.to_f64().unwrap() / scale.to_f64().unwrap()).unwrap()
+}
+
+fn mul_scale<T: NumCast, X: num::ToPrimitive>(n: T, scale: X) -> T {
+    T::from(n.to_f64().unwrap() * scale.to_f64().unwrap()).unwrap()
+}
+
 impl<T: Debug + Num + Copy + NumCast> Vec2<T, WindowSpace> {
+    // Larger window space down to the smaller texture space, e.g. 1600px / 4 -> 400px.
     pub fn to_texture_space<X: num::ToPrimitive + Copy>(
         self,
         texture_scale: X,
     ) -> Vec2<T, TextureSpace> {
         Vec2 {
-            x: self.x / T::from(texture_scale).unwrap(),
-            y: self.y / T::from(texture_scale).unwrap(),
+            x: div_scale(self.x, texture_scale),
+            y: div_scale(self.y, texture_scale),
             _unit: PhantomData,
         }
     }
@@ -160,16 +253,27 @@ impl<T: Debug + Num + Copy + NumCast> Vec2<T, WindowSpace> {
         texture_scale: X,
         camera: Vec2<T2, WorldSpace>,
     ) -> Vec2<T, WorldSpace> {
-        let scale = T::from(texture_scale).unwrap();
         Vec2 {
-            x: self.x / scale + T::from(camera.x).unwrap(),
-            y: self.y / scale - T::from(camera.y).unwrap(),
+            x: div_scale(self.x, texture_scale) + T::from(camera.x).unwrap(),
+            y: div_scale(self.y, texture_scale) - T::from(camera.y).unwrap(),
             _unit: PhantomData,
         }
     }
 }
 
 impl<T: Debug + Num + Copy + NumCast> Vec2<T, TextureSpace> {
+    // Inverse of to_texture_space, smaller texture space up to window space, e.g. 400px * 4 -> 1600px.
+    pub fn to_window_space<X: num::ToPrimitive + Copy>(
+        self,
+        texture_scale: X,
+    ) -> Vec2<T, WindowSpace> {
+        Vec2 {
+            x: mul_scale(self.x, texture_scale),
+            y: mul_scale(self.y, texture_scale),
+            _unit: PhantomData,
+        }
+    }
+
     pub fn to_world_space<T2: Debug + Num + Copy + NumCast>(
         self,
         camera: Vec2<T2, WorldSpace>,
@@ -266,8 +370,193 @@ impl_vec2_op!(Add);
 impl_vec2_op!(Sub);
 impl_vec2_op!(Mul);
 impl_vec2_op!(Div);
+
+// Axis access, 0 == x, 1 == y. e.g. selecting an axis at runtime in SAT.
+impl<T: Debug + Copy, U: CoordSpace> Index<usize> for Vec2<T, U> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Vec2 index out of bounds: {index}, expected 0 (x) or 1 (y)"),
+        }
+    }
+}
+
+impl<T: Debug + Copy, U: CoordSpace> IndexMut<usize> for Vec2<T, U> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Vec2 index out of bounds: {index}, expected 0 (x) or 1 (y)"),
+        }
+    }
+}
+
+// Yields x then y.
+impl<T: Debug + Copy, U: CoordSpace> IntoIterator for Vec2<T, U> {
+    type Item = T;
+    type IntoIter = std::array::IntoIter<T, 2>;
+    fn into_iter(self) -> Self::IntoIter {
+        [self.x, self.y].into_iter()
+    }
+}
+// endregion
+
+// region: Vec2 Display
+// User facing "(x, y)", floats default to 2dp, e.g. format!("{v}") or format!("{v:.4}").
+impl<T: Debug + Display, U: CoordSpace> Display for Vec2<T, U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(f, "({:.*}, {:.*})", precision, self.x, precision, self.y)
+    }
+}
+
+impl<T: Debug + Display + Copy, U: CoordSpace> Vec2<T, U> {
+    pub fn fmt_with_precision(self, precision: usize) -> impl Display {
+        struct WithPrecision<T: Debug, U: CoordSpace>(Vec2<T, U>, usize);
+        impl<T: Debug + Display, U: CoordSpace> Display for WithPrecision<T, U> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{:.*}", self.1, self.0)
+            }
+        }
+        WithPrecision(self, precision)
+    }
+}
 // endregion
 
 pub fn fmt_limited_precision<T: Debug>(x: T, format: &mut Formatter) -> std::fmt::Result {
     write!(format, "{x:.2?}") // Specify precision here
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_and_iterate_components() {
+        let v: Vec2<i32, WorldSpace> = vec2(3, 4);
+        assert_eq!(v[0], 3);
+        assert_eq!(v[1], 4);
+
+        let mut v = v;
+        v[1] = 7;
+        assert_eq!(v, vec2(3, 7));
+
+        let components: Vec<i32> = vec2::<i32, WorldSpace>(5, 6).into_iter().collect();
+        assert_eq!(components, [5, 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds_panics() {
+        let _ = vec2::<i32, WorldSpace>(3, 4)[2];
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn display_defaults_to_two_decimal_places() {
+        let v: Vec2<f32, WorldSpace> = vec2(3.14159, 2.71828);
+        assert_eq!(format!("{v}"), "(3.14, 2.72)");
+        assert_eq!(format!("{}", v.fmt_with_precision(1)), "(3.1, 2.7)");
+        assert_eq!(format!("{}", vec2::<i32, WorldSpace>(3, -4)), "(3, -4)");
+    }
+
+    #[test]
+    fn texture_and_window_space_round_trip() {
+        let window: Vec2<u32, WindowSpace> = vec2(1600, 1200);
+        let texture = window.to_texture_space(4);
+        assert_eq!(texture, vec2(400, 300));
+        assert_eq!(texture.to_window_space(4), window);
+
+        // 1.5 isn't truncated to 1 on the way through.
+        let window: Vec2<f64, WindowSpace> = vec2(1500.0, 900.0);
+        let texture = window.to_texture_space(1.5);
+        assert_eq!(texture, vec2(1000.0, 600.0));
+        assert_eq!(texture.to_window_space(1.5), window);
+        assert_eq!(
+            vec2::<u32, WindowSpace>(1500, 900).to_texture_space(1.5),
+            vec2(1000, 600)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn json_round_trip_tags_the_space() {
+        let v: Vec2<f64, WorldSpace> = vec2(3.14, -2.71);
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"x":3.14,"y":-2.71,"space":"WorldSpace"}"#);
+
+        let parsed: Vec2<f64, WorldSpace> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, v);
+    }
+
+    #[test]
+    fn rotate_a_quarter_turn() {
+        let v: Vec2<f32, WorldSpace> = vec2(1.0, 0.0);
+        let rotated = v.rotate(std::f32::consts::FRAC_PI_2);
+        assert!(
+            (rotated - vec2(0.0, 1.0)).length() <= f32::EPSILON * 4.0,
+            "{rotated:?}"
+        );
+
+        let pivot = vec2(1.0, 1.0);
+        let around = vec2(2.0, 1.0).rotate_around(pivot, std::f32::consts::FRAC_PI_2);
+        assert!(
+            (around - vec2(1.0, 2.0)).length() <= f32::EPSILON * 4.0,
+            "{around:?}"
+        );
+    }
+
+    #[test]
+    fn lerp_and_slerp() {
+        let a: Vec2<f32, WorldSpace> = vec2(1.0, 2.0);
+        let b = vec2(3.0, -2.0);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), vec2(2.0, 0.0));
+        // t is clamped.
+        assert_eq!(a.lerp(b, 2.0), b);
+
+        let from: Vec2<f32, WorldSpace> = vec2(1.0, 0.0);
+        let to = vec2(0.0, 1.0);
+        for t in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let length = from.slerp(to, t).length();
+            assert!((length - 1.0).abs() < 1e-6, "t {t}: {length}");
+        }
+        let halfway = from.slerp(to, 0.5);
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((halfway - vec2(diagonal, diagonal)).length() < 1e-6);
+    }
+
+    #[test]
+    fn project_onto_and_reject_from() {
+        let v: Vec2<f32, WorldSpace> = vec2(3.0, 4.0);
+        assert_eq!(v.project_onto(vec2(1.0, 0.0)), vec2(3.0, 0.0));
+        // the axis needn't be normalised.
+        assert_eq!(v.project_onto(vec2(2.0, 0.0)), vec2(3.0, 0.0));
+
+        let axis = vec2(1.0, 1.0);
+        let rejection = v.reject_from(axis);
+        assert!(rejection.dot_product(axis).abs() < 1e-6, "{rejection:?}");
+        assert!((rejection + v.project_onto(axis) - v).length() < 1e-6);
+    }
+
+    #[test]
+    fn angles() {
+        use std::f32::consts::FRAC_PI_2;
+
+        let x: Vec2<f32, WorldSpace> = vec2(1.0, 0.0);
+        let y = vec2(0.0, 1.0);
+        assert!((x.angle_between(y) - FRAC_PI_2).abs() < 1e-6);
+        // signed, so back the other way is negative.
+        assert!((y.angle_between(x) + FRAC_PI_2).abs() < 1e-6);
+        assert!((y.angle() - FRAC_PI_2).abs() < 1e-6);
+
+        let from_angle: Vec2<f32, WorldSpace> = vec2_from_angle(0.0);
+        assert!((from_angle - x).length() < 1e-6, "{from_angle:?}");
+        let from_angle: Vec2<f32, WorldSpace> = vec2_from_angle(FRAC_PI_2);
+        assert!((from_angle - y).length() < 1e-6, "{from_angle:?}");
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    f32::consts::{FRAC_PI_6, TAU},
     fmt::Debug,
     marker::PhantomData,
     ops::{Div, Sub},
@@ -8,8 +9,13 @@ use num::{Num, NumCast};
 use wgpu::RenderBundleDepthStencil;
 
 use crate::utils::{
+    aabb::Aabb,
     colour::Rgba,
-    consts::{BLACK, CAMERA_RESISTANCE, RED},
+    consts::{
+        BLACK, CAMERA_RESISTANCE, ELLIPSE_ASPECT, MAX_CURVE_DEPTH, MAX_CURVE_ERROR, RED,
+        RING_INNER_RADIUS,
+    },
+    font::{glyph, GLYPH_WIDTH},
     vec2::{vec2, CentredTextureSpace, CoordSpace, TextureSpace, Vec2, WindowSpace, WorldSpace},
 };
 
@@ -17,6 +23,8 @@ use crate::utils::{
 pub struct World {
     pub camera_pos: Vec2<f64, WorldSpace>,
     pub camera_vel: Vec2<f64, WorldSpace>,
+    // camera_pos is clamped to these, None lets it wander anywhere.
+    pub camera_bounds: Option<Aabb<f64, WorldSpace>>,
 
     viewport_centre: Vec2<i32, CentredTextureSpace>,
     pub viewport_size: Vec2<u32, TextureSpace>,
@@ -50,6 +58,28 @@ impl World {
         self.camera_vel += acceleration;
         self.camera_vel *= resistance;
         self.camera_pos += self.camera_vel;
+
+        if let Some(bounds) = self.camera_bounds {
+            let clamped = bounds.clamp_point(self.camera_pos);
+            // stop against the bound rather than pushing into it every frame.
+            if clamped.x != self.camera_pos.x {
+                self.camera_vel.x = 0.0;
+            }
+            if clamped.y != self.camera_pos.y {
+                self.camera_vel.y = 0.0;
+            }
+            self.camera_pos = clamped;
+        }
+    }
+
+    // Eases the camera smoothing (0..=1) of the way to target each call, instead of
+    // update_camera's velocity, e.g. to follow a body.
+    pub fn lerp_to(&mut self, target: Vec2<f64, WorldSpace>, smoothing: f64) {
+        self.camera_vel = vec2(0.0, 0.0);
+        self.camera_pos = self.camera_pos.lerp(target, smoothing);
+        if let Some(bounds) = self.camera_bounds {
+            self.camera_pos = bounds.clamp_point(self.camera_pos);
+        }
     }
 
     pub fn new(viewport_size: Vec2<u32, TextureSpace>) -> Self {
@@ -57,6 +87,7 @@ impl World {
         Self {
             camera_pos: vec2(0.0, 0.0),
             camera_vel: vec2(0.0, 0.0),
+            camera_bounds: None,
             viewport_centre: viewport_size.cast::<i32>().cast_unit().div(2),
             viewport_size,
             viewport_texture,
@@ -82,8 +113,52 @@ impl World {
         }
     }
 
-    pub fn draw_all(&mut self, colour: Rgba) {
-        for chunk in self.viewport_texture.chunks_exact_mut(4) {
+    // Alpha blends colour over the existing cell, using colour.a.
+    pub fn blend_cell(&mut self, position: Vec2<i32, WorldSpace>, colour: Rgba) {
+        let position = position.to_texture_space(self.camera_pos);
+
+        if self.is_out_of_bounds(position) {
+            return;
+        }
+
+        let index = 4 * (position.y as u32 * self.viewport_size.x + position.x as u32) as usize;
+        let alpha = colour.a as u32;
+        let blend =
+            |dst: u8, src: u8| ((src as u32 * alpha + dst as u32 * (255 - alpha)) / 255) as u8;
+        self.viewport_texture[index] = blend(self.viewport_texture[index], colour.r);
+        self.viewport_texture[index + 1] = blend(self.viewport_texture[index + 1], colour.g);
+        self.viewport_texture[index + 2] = blend(self.viewport_texture[index + 2], colour.b);
+    }
+
+    // Fills the whole viewport, memset when every channel is the same byte.
+    pub fn clear(&mut self, colour: Rgba) {
+        optick::event!("World::clear");
+
+        if colour.r == colour.g && colour.g == colour.b && colour.b == colour.a {
+            // SAFETY: writes exactly len bytes from the start of the buffer, u8 has no invalid values.
+            unsafe {
+                self.viewport_texture
+                    .as_mut_ptr()
+                    .write_bytes(colour.r, self.viewport_texture.len());
+            }
+            return;
+        }
+
+        #[cfg(feature = "simd")]
+        let remainder = {
+            use std::simd::Simd;
+            let pixel = [colour.r, colour.g, colour.b, colour.a];
+            let pattern = Simd::<u8, 16>::from_array(std::array::from_fn(|i| pixel[i % 4]));
+            let mut chunks = self.viewport_texture.chunks_exact_mut(16);
+            for chunk in &mut chunks {
+                pattern.copy_to_slice(chunk);
+            }
+            chunks.into_remainder()
+        };
+        #[cfg(not(feature = "simd"))]
+        let remainder = self.viewport_texture.as_mut_slice();
+
+        for chunk in remainder.chunks_exact_mut(4) {
             chunk[0] = colour.r;
             chunk[1] = colour.g;
             chunk[2] = colour.b;
@@ -140,25 +215,142 @@ impl World {
         }
     }
 
+    // Xiaolin Wu's line algorithm, each step along the major axis splits colour between the two
+    // cells straddling the line, weighted by coverage & blended over what is already drawn.
+    #[cfg(feature = "antialiased")]
+    pub fn draw_line_aa(
+        &mut self,
+        start: Vec2<f32, WorldSpace>,
+        end: Vec2<f32, WorldSpace>,
+        colour: Rgba,
+    ) {
+        let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
+        // walk along x, swapping axes for steep lines & ends so x increases.
+        let (mut start, mut end) = if steep {
+            (vec2(start.y, start.x), vec2(end.y, end.x))
+        } else {
+            (start, end)
+        };
+        if start.x > end.x {
+            std::mem::swap(&mut start, &mut end);
+        }
+
+        let dx = end.x - start.x;
+        let gradient = if dx == 0.0 {
+            1.0
+        } else {
+            (end.y - start.y) / dx
+        };
+        let (x_start, x_end) = (start.x.round() as i32, end.x.round() as i32);
+        let mut y = start.y + gradient * (x_start as f32 - start.x);
+
+        for x in x_start..=x_end {
+            let (row, coverage) = (y.floor(), y.fract());
+            for (row, coverage) in [(row as i32, 1.0 - coverage), (row as i32 + 1, coverage)] {
+                let position = if steep { vec2(row, x) } else { vec2(x, row) };
+                self.blend_cell_coverage(position, colour, coverage);
+            }
+            y += gradient;
+        }
+    }
+
+    // Rgba::blend_over of colour, with its alpha scaled by coverage [0, 1], onto the existing cell.
+    #[cfg(feature = "antialiased")]
+    fn blend_cell_coverage(
+        &mut self,
+        position: Vec2<i32, WorldSpace>,
+        colour: Rgba,
+        coverage: f32,
+    ) {
+        let position = position.to_texture_space(self.camera_pos);
+
+        if coverage <= 0.0 || self.is_out_of_bounds(position) {
+            return;
+        }
+
+        let index = 4 * (position.y as u32 * self.viewport_size.x + position.x as u32) as usize;
+        let pixel = &mut self.viewport_texture[index..index + 4];
+        let alpha = (<f32 as From<u8>>::from(colour.a) * coverage).round() as u8;
+        let blended = colour
+            .with_alpha(alpha)
+            .blend_over(Rgba::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]));
+        pixel.copy_from_slice(&[blended.r, blended.g, blended.b, blended.a]);
+    }
+
+    // Bresenham's line algorithm, plot is called for every cell from start to end inclusive.
+    fn plot_line(
+        mut start: Vec2<i32, WorldSpace>,
+        end: Vec2<i32, WorldSpace>,
+        plot: &mut impl FnMut(i32, i32),
+    ) {
+        let dx = (end.x - start.x).abs();
+        let sx = if start.x < end.x { 1 } else { -1 };
+        let dy = -(end.y - start.y).abs();
+        let sy = if start.y < end.y { 1 } else { -1 };
+        let mut error = dx + dy;
+
+        loop {
+            plot(start.x, start.y);
+            if start.x == end.x && start.y == end.y {
+                break;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                start.x += sx;
+            }
+            if e2 <= dx {
+                error += dx;
+                start.y += sy;
+            }
+        }
+    }
+
     pub fn draw_circle_outline(
         &mut self,
         centre: Vec2<i32, WorldSpace>,
         radius: u32,
         colour: Rgba,
     ) {
+        midpoint_circle(radius, |x, y| self.draw_cell(centre + vec2(x, y), colour));
+    }
+
+    // Midpoint circle, only keeping points between the two angles (radians, either order).
+    pub fn draw_arc(
+        &mut self,
+        centre: Vec2<i32, WorldSpace>,
+        radius: u32,
+        start_angle: f32,
+        end_angle: f32,
+        colour: Rgba,
+    ) {
+        let start = start_angle.min(end_angle);
+        let sweep = (end_angle - start_angle).abs();
+        let in_arc = |p: Vec2<i32, WorldSpace>| {
+            sweep >= TAU || (p.cast::<f32>().angle() - start).rem_euclid(TAU) <= sweep
+        };
+
+        midpoint_circle(radius, |x, y| {
+            if in_arc(vec2(x, y)) {
+                self.draw_cell(centre + vec2(x, y), colour);
+            }
+        });
+    }
+
+    pub fn draw_circle_fill(&mut self, centre: Vec2<i32, WorldSpace>, radius: u32, colour: Rgba) {
         let mut x = radius as i32;
         let mut y = 0;
         let mut d = 1 - radius as i32;
 
         while x >= y {
-            self.draw_cell(centre + vec2(x, y), colour);
-            self.draw_cell(centre + vec2(y, x), colour);
-            self.draw_cell(centre - vec2(y, x), colour);
-            self.draw_cell(centre - vec2(x, y), colour);
-            self.draw_cell(centre + vec2(-x, -y), colour);
-            self.draw_cell(centre + vec2(-y, -x), colour);
-            self.draw_cell(centre - vec2(-y, -x), colour);
-            self.draw_cell(centre - vec2(-x, -y), colour);
+            for i in -x..=x {
+                self.draw_cell(centre + vec2(i, y).cast(), colour);
+                self.draw_cell(centre + vec2(i, -y).cast(), colour);
+            }
+            for i in -y..=y {
+                self.draw_cell(centre + vec2(i, x).cast(), colour);
+                self.draw_cell(centre + vec2(i, -x).cast(), colour);
+            }
             y += 1;
             if d < 0 {
                 d += 2 * y + 1;
@@ -169,20 +361,24 @@ impl World {
         }
     }
 
-    pub fn draw_circle_fill(&mut self, centre: Vec2<i32, WorldSpace>, radius: u32, colour: Rgba) {
+    // Same pixels as draw_circle_fill, but each midpoint step fills whole rows at once, the
+    // camera offset is applied once rather than per cell.
+    pub fn draw_circle_fill_fast(
+        &mut self,
+        centre: Vec2<i32, WorldSpace>,
+        radius: u32,
+        colour: Rgba,
+    ) {
+        let centre = centre.to_texture_space(self.camera_pos);
         let mut x = radius as i32;
         let mut y = 0;
         let mut d = 1 - radius as i32;
 
         while x >= y {
-            for i in -x..=x {
-                self.draw_cell(centre + vec2(i, y).cast(), colour);
-                self.draw_cell(centre + vec2(i, -y).cast(), colour);
-            }
-            for i in -y..=y {
-                self.draw_cell(centre + vec2(i, x).cast(), colour);
-                self.draw_cell(centre + vec2(i, -x).cast(), colour);
-            }
+            self.fill_span(centre.y + y, centre.x - x, centre.x + x, colour);
+            self.fill_span(centre.y - y, centre.x - x, centre.x + x, colour);
+            self.fill_span(centre.y + x, centre.x - y, centre.x + y, colour);
+            self.fill_span(centre.y - x, centre.x - y, centre.x + y, colour);
             y += 1;
             if d < 0 {
                 d += 2 * y + 1;
@@ -193,6 +389,117 @@ impl World {
         }
     }
 
+    // a & b are the x & y semi-axes.
+    pub fn draw_ellipse_outline(
+        &mut self,
+        centre: Vec2<i32, WorldSpace>,
+        a: u32,
+        b: u32,
+        colour: Rgba,
+    ) {
+        midpoint_ellipse(a, b, |x, y| {
+            for p in [vec2(x, y), vec2(-x, y), vec2(x, -y), vec2(-x, -y)] {
+                self.draw_cell(centre + p, colour);
+            }
+        });
+    }
+
+    // Scanline fill between each pair of boundary points, rows are filled whole like
+    // draw_circle_fill_fast.
+    pub fn draw_ellipse_fill(
+        &mut self,
+        centre: Vec2<i32, WorldSpace>,
+        a: u32,
+        b: u32,
+        colour: Rgba,
+    ) {
+        let centre = centre.to_texture_space(self.camera_pos);
+        midpoint_ellipse(a, b, |x, y| {
+            self.fill_span(centre.y + y, centre.x - x, centre.x + x, colour);
+            self.fill_span(centre.y - y, centre.x - x, centre.x + x, colour);
+        });
+    }
+
+    // Fills texture row y from x_min to x_max inclusive, clipped to the viewport.
+    fn fill_span(&mut self, y: i32, x_min: i32, x_max: i32, colour: Rgba) {
+        let width = self.viewport_size.x as i32;
+        if y < 0 || y >= self.viewport_size.y as i32 || x_max < 0 || x_min >= width {
+            return;
+        }
+        let row = 4 * (y * width) as usize;
+        let start = row + 4 * x_min.max(0) as usize;
+        let end = row + 4 * (x_max.min(width - 1) + 1) as usize;
+        let pixel = [colour.r, colour.g, colour.b, colour.a];
+        for chunk in self.viewport_texture[start..end].chunks_exact_mut(4) {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    // min inclusive, max exclusive.
+    pub fn draw_filled_rect(
+        &mut self,
+        min: Vec2<i32, WorldSpace>,
+        max: Vec2<i32, WorldSpace>,
+        colour: Rgba,
+    ) {
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                self.draw_cell(vec2(x, y), colour);
+            }
+        }
+    }
+
+    // min inclusive, max exclusive.
+    pub fn draw_rect_blend(
+        &mut self,
+        min: Vec2<i32, WorldSpace>,
+        max: Vec2<i32, WorldSpace>,
+        colour: Rgba,
+    ) {
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                self.blend_cell(vec2(x, y), colour);
+            }
+        }
+    }
+
+    // position is the top left of the first character.
+    pub fn draw_text(&mut self, position: Vec2<i32, WorldSpace>, text: &str, colour: Rgba) {
+        for (i, c) in text.chars().enumerate() {
+            let origin = position + vec2(i as i32 * (GLYPH_WIDTH + 1), 0);
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if (bits >> (GLYPH_WIDTH - 1 - col)) & 1 == 1 {
+                        self.draw_cell(origin + vec2(col, row as i32), colour);
+                    }
+                }
+            }
+        }
+    }
+
+    // Line with a two stroke head at end, alpha blended using colour.a.
+    pub fn draw_arrow_blend(
+        &mut self,
+        start: Vec2<i32, WorldSpace>,
+        end: Vec2<i32, WorldSpace>,
+        head_len: f32,
+        colour: Rgba,
+    ) {
+        let dir = (end - start).cast::<f32>();
+        let len = dir.length();
+        if len < 1.0 {
+            return;
+        }
+        let back = dir / len * -head_len;
+        let heads = [back.rotate(FRAC_PI_6), back.rotate(-FRAC_PI_6)];
+
+        let mut plot = |x, y| self.blend_cell(vec2(x, y), colour);
+        Self::plot_line(start, end, &mut plot);
+        for head in heads {
+            Self::plot_line(end, end + head.cast(), &mut plot);
+        }
+    }
+
     pub fn draw_polygon(&mut self, vertices: &[Vec2<f32, WorldSpace>], colour: Rgba) {
         for i in 0..vertices.len() {
             let start = vertices[i];
@@ -200,27 +507,262 @@ impl World {
             self.draw_line(start, end, colour);
         }
     }
+
+    // Scanline fill with the even-odd rule, so concave & self-intersecting polygons work.
+    // Cells are filled when their centre is inside, rows are clipped to the viewport.
+    pub fn draw_filled_polygon(&mut self, vertices: &[Vec2<f32, WorldSpace>], colour: Rgba) {
+        if vertices.len() < 3 {
+            return;
+        }
+
+        let verts: Vec<_> = vertices
+            .iter()
+            .map(|v| v.to_texture_space(self.camera_pos))
+            .collect();
+        let (min_y, max_y) = verts
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v.y), hi.max(v.y))
+            });
+        let first_row = (min_y.floor() as i32).max(0);
+        let last_row = (max_y.ceil() as i32).min(self.viewport_size.y as i32 - 1);
+
+        let mut crossings = Vec::new();
+        for row in first_row..=last_row {
+            let scan_y = row as f32 + 0.5;
+            crossings.clear();
+            for i in 0..verts.len() {
+                let (a, b) = (verts[i], verts[(i + 1) % verts.len()]);
+                // half-open, so a vertex shared by two edges is only counted once.
+                if (a.y <= scan_y) != (b.y <= scan_y) {
+                    crossings.push(a.x + (scan_y - a.y) * (b.x - a.x) / (b.y - a.y));
+                }
+            }
+            crossings.sort_by(f32::total_cmp);
+
+            for pair in crossings.chunks_exact(2) {
+                let x_min = (pair[0] - 0.5).ceil() as i32;
+                let x_max = (pair[1] - 0.5).floor() as i32;
+                if x_min <= x_max {
+                    self.fill_span(row, x_min, x_max, colour);
+                }
+            }
+        }
+    }
+
+    // Adaptive de Casteljau subdivision, flat sections are drawn as a single line.
+    pub fn draw_cubic_bezier(
+        &mut self,
+        p0: Vec2<f32, WorldSpace>,
+        p1: Vec2<f32, WorldSpace>,
+        p2: Vec2<f32, WorldSpace>,
+        p3: Vec2<f32, WorldSpace>,
+        colour: Rgba,
+    ) {
+        subdivide_bezier([p0, p1, p2, p3], 0, &mut |start, end| {
+            self.draw_line(start, end, colour);
+        });
+    }
+
+    // Degree elevated to the equivalent cubic.
+    pub fn draw_quadratic_bezier(
+        &mut self,
+        p0: Vec2<f32, WorldSpace>,
+        p1: Vec2<f32, WorldSpace>,
+        p2: Vec2<f32, WorldSpace>,
+        colour: Rgba,
+    ) {
+        let c1 = p0 + (p1 - p0) * (2.0 / 3.0);
+        let c2 = p2 + (p1 - p2) * (2.0 / 3.0);
+        self.draw_cubic_bezier(p0, c1, c2, p2, colour);
+    }
+
+    // Edge i runs from vertex i to i + 1, colours wrap if there are fewer than edges.
+    pub fn draw_polygon_coloured(&mut self, vertices: &[Vec2<f32, WorldSpace>], colours: &[Rgba]) {
+        if colours.is_empty() {
+            return;
+        }
+        for i in 0..vertices.len() {
+            let start = vertices[i];
+            let end = vertices[(i + 1) % vertices.len()];
+            self.draw_line(start, end, colours[i % colours.len()]);
+        }
+    }
+
+    // The dash pattern carries across corners, so it doesn't restart on every edge.
+    pub fn draw_polygon_outline_dashed(
+        &mut self,
+        vertices: &[Vec2<f32, WorldSpace>],
+        colour: Rgba,
+        dash_len: u32,
+        gap_len: u32,
+    ) {
+        let dash_len = dash_len.max(1);
+        let mut remaining_dash = dash_len;
+        let mut remaining_gap = 0;
+
+        let mut plot = |x, y| {
+            if remaining_dash > 0 {
+                self.draw_cell(vec2(x, y), colour);
+                remaining_dash -= 1;
+                remaining_gap = (remaining_dash == 0) as u32 * gap_len;
+            } else {
+                remaining_gap = remaining_gap.saturating_sub(1);
+            }
+            if remaining_dash == 0 && remaining_gap == 0 {
+                remaining_dash = dash_len;
+            }
+        };
+        for i in 0..vertices.len() {
+            let start = vertices[i].cast();
+            let end = vertices[(i + 1) % vertices.len()].cast();
+            Self::plot_line(start, end, &mut plot);
+        }
+    }
 }
 
-/*
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-#[allow(dead_code)] // don't match shape, I index into it (app::handle_inputs)
+// de Casteljau subdivision of a cubic bezier, segment is called with the chord of each piece
+// once it's flat to within MAX_CURVE_ERROR, in order from points[0] to points[3].
+pub fn subdivide_bezier<U: CoordSpace>(
+    points: [Vec2<f32, U>; 4],
+    depth: u32,
+    segment: &mut impl FnMut(Vec2<f32, U>, Vec2<f32, U>),
+) {
+    let [p0, p1, p2, p3] = points;
+
+    // distance of the inner control points from the chord, the curve lies within them.
+    let chord = p3 - p0;
+    let chord_len = chord.length();
+    let deviation = |p: Vec2<f32, U>| match chord_len > f32::EPSILON {
+        true => chord.cross_product(p - p0).abs() / chord_len,
+        false => (p - p0).length(),
+    };
+    if depth >= MAX_CURVE_DEPTH || deviation(p1).max(deviation(p2)) < 0.5 * MAX_CURVE_ERROR {
+        segment(p0, p3);
+        return;
+    }
+
+    // split at t = 0.5
+    let p01 = (p0 + p1) / 2.0;
+    let p12 = (p1 + p2) / 2.0;
+    let p23 = (p2 + p3) / 2.0;
+    let p012 = (p01 + p12) / 2.0;
+    let p123 = (p12 + p23) / 2.0;
+    let mid = (p012 + p123) / 2.0;
+    subdivide_bezier([p0, p01, p012, mid], depth + 1, segment);
+    subdivide_bezier([mid, p123, p23, p3], depth + 1, segment);
+}
+
+// Midpoint circle algorithm, plot is called with every boundary point, all 8 octants mirrored.
+fn midpoint_circle(radius: u32, mut plot: impl FnMut(i32, i32)) {
+    let mut x = radius as i32;
+    let mut y = 0;
+    let mut d = 1 - radius as i32;
+
+    while x >= y {
+        for (px, py) in [
+            (x, y),
+            (y, x),
+            (-y, x),
+            (-x, y),
+            (-x, -y),
+            (-y, -x),
+            (y, -x),
+            (x, -y),
+        ] {
+            plot(px, py);
+        }
+        y += 1;
+        if d < 0 {
+            d += 2 * y + 1;
+        } else {
+            x -= 1;
+            d += 2 * (y - x) + 1;
+        }
+    }
+}
+
+// Midpoint ellipse algorithm, plot is called with the first quadrant's boundary points (x, y >= 0),
+// callers mirror them. Decision variables are scaled by 4 to stay in integers.
+fn midpoint_ellipse(a: u32, b: u32, mut plot: impl FnMut(i32, i32)) {
+    if b == 0 {
+        (0..=a as i32).for_each(|x| plot(x, 0));
+        return;
+    }
+    let (a2, b2) = ((a as i64).pow(2), (b as i64).pow(2));
+    let (mut x, mut y) = (0_i64, b as i64);
+    let (mut dx, mut dy) = (0, 2 * a2 * y);
+
+    // Region 1, gradient shallower than -1, step x every time.
+    let mut d = 4 * b2 - 4 * a2 * y + a2;
+    while dx < dy {
+        plot(x as i32, y as i32);
+        x += 1;
+        dx += 2 * b2;
+        if d >= 0 {
+            y -= 1;
+            dy -= 2 * a2;
+            d -= 4 * dy;
+        }
+        d += 4 * (dx + b2);
+    }
+
+    // Region 2, step y every time.
+    let mut d = b2 * (2_i64 * x + 1).pow(2) + 4 * a2 * (y - 1).pow(2) - 4 * a2 * b2;
+    while y >= 0 {
+        plot(x as i32, y as i32);
+        y -= 1;
+        dy -= 2 * a2;
+        if d <= 0 {
+            x += 1;
+            dx += 2 * b2;
+            d += 4 * dx;
+        }
+        d += 4 * (a2 - dy);
+    }
+}
+
+// Brush shapes, drawn as offsets from a centre by Shape::draw.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum Shape {
     CircleOutline,
     CircleFill,
     SquareCentered,
+    // Annulus, size is the outer radius.
+    CircleRing { inner_radius: i32 },
+    // aspect = width / height, size is the x semi-axis.
+    EllipseFill { aspect: f32 },
+    EllipseOutline { aspect: f32 },
+    // Equilateral & point up, size is half the base, centred on the centroid.
+    Triangle,
 }
 
 impl Shape {
+    // Cycles through the shapes, e.g. on Tab.
+    pub const fn next(self) -> Self {
+        match self {
+            Self::CircleOutline => Self::CircleFill,
+            Self::CircleFill => Self::SquareCentered,
+            Self::SquareCentered => Self::CircleRing {
+                inner_radius: RING_INNER_RADIUS,
+            },
+            Self::CircleRing { .. } => Self::EllipseFill {
+                aspect: ELLIPSE_ASPECT,
+            },
+            Self::EllipseFill { aspect } => Self::EllipseOutline { aspect },
+            Self::EllipseOutline { .. } => Self::Triangle,
+            Self::Triangle => Self::CircleOutline,
+        }
+    }
+
     // Provides Offsets relative to be used with a a presumed central point of origin.
     // The lambda captures the offsets, combines with the central point and does stuff with the data (drawing).
     pub fn draw(self, size: i32, mut lambda: impl FnMut(i32, i32)) {
         match self {
             Self::CircleOutline => {
                 let mut x = 0;
-                let mut y = size as i32;
-                let mut d = 3 - 2 * size as i32;
+                let mut y = size;
+                let mut d = 3 - 2 * size;
                 let mut draw_circle = |x, y| {
                     lambda(x, y);
                     lambda(-x, y);
@@ -234,10 +776,10 @@ impl Shape {
                 draw_circle(x, y);
                 while x < y {
                     if d < 0 {
-                        d = d + 4 * x + 6;
+                        d += 4 * x + 6;
                     } else {
                         y -= 1;
-                        d = d + 4 * (x - y) + 10;
+                        d += 4 * (x - y) + 10;
                     }
                     x += 1;
                     draw_circle(x, y);
@@ -245,8 +787,8 @@ impl Shape {
             }
             Self::CircleFill => {
                 let mut x = 0;
-                let mut y = size as i32;
-                let mut d = 3 - 2 * size as i32;
+                let mut y = size;
+                let mut d = 3 - 2 * size;
                 let mut draw_line = |x1, x2, y| {
                     for x in x1..x2 {
                         lambda(x, y);
@@ -261,23 +803,65 @@ impl Shape {
                 draw_circle(x, y);
                 while x < y {
                     if d < 0 {
-                        d = d + 4 * x + 6;
+                        d += 4 * x + 6;
                     } else {
                         y -= 1;
-                        d = d + 4 * (x - y) + 10;
+                        d += 4 * (x - y) + 10;
                     }
                     x += 1;
                     draw_circle(x, y);
                 }
             }
             Self::SquareCentered => {
-                let half = (size / 2) as i32;
+                let half = size / 2;
                 for y_off in -(half)..(half) {
                     for x_off in -(half)..(half) {
                         lambda(x_off, y_off);
                     }
                 }
             }
+            Self::CircleRing { inner_radius } => {
+                let (outer_sq, inner_sq) = (size * size, inner_radius * inner_radius);
+                for y_off in -size..=size {
+                    for x_off in -size..=size {
+                        let dist_sq = x_off * x_off + y_off * y_off;
+                        if dist_sq >= inner_sq && dist_sq <= outer_sq {
+                            lambda(x_off, y_off);
+                        }
+                    }
+                }
+            }
+            Self::EllipseFill { aspect } => {
+                let b = (size as f32 / aspect) as u32;
+                midpoint_ellipse(size as u32, b, |x, y| {
+                    for x_off in -x..=x {
+                        lambda(x_off, y);
+                        lambda(x_off, -y);
+                    }
+                });
+            }
+            Self::EllipseOutline { aspect } => {
+                let b = (size as f32 / aspect) as u32;
+                midpoint_ellipse(size as u32, b, |x, y| {
+                    lambda(x, y);
+                    lambda(-x, y);
+                    lambda(x, -y);
+                    lambda(-x, -y);
+                });
+            }
+            Self::Triangle => {
+                // scanline fill, each row spans the edges either side of the apex.
+                let height = size as f32 * 3.0_f32.sqrt();
+                let apex = -(height * 2.0 / 3.0).round() as i32;
+                let base = (height / 3.0).round() as i32;
+                for y_off in apex..=base {
+                    let down = (y_off - apex) as f32 / (base - apex).max(1) as f32;
+                    let half_width = (size as f32 * down).round() as i32;
+                    for x_off in -half_width..=half_width {
+                        lambda(x_off, y_off);
+                    }
+                }
+            }
         }
     }
 
@@ -352,6 +936,7 @@ impl Shape {
     }
 }
 
+/*
 #[derive(Debug, Clone)]
 pub struct Canvas {
     pub camera: Vec2<f32, WorldSpace>,
@@ -465,3 +1050,209 @@ impl Canvas {
     }
 }
  */
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use super::*;
+    use crate::utils::consts::{BLUE, GREEN, LIGHT_GRAY, WHITE};
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    // texels that aren't the clear colour.
+    fn drawn_cells(world: &World) -> Vec<usize> {
+        world
+            .viewport_texture
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| pixel != &[0, 0, 0, 0])
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn full_arc_matches_circle_outline() {
+        let mut circle = World::new(vec2(64, 64));
+        circle.draw_circle_outline(vec2(32, 32), 20, RED);
+        let mut arc = World::new(vec2(64, 64));
+        arc.draw_arc(vec2(32, 32), 20, 0.0, TAU, RED);
+
+        let cells = drawn_cells(&circle);
+        assert!(!cells.is_empty());
+        assert_eq!(drawn_cells(&arc), cells);
+    }
+
+    #[test]
+    fn circle_outline_covers_every_octant() {
+        let mut world = World::new(vec2(64, 64));
+        world.draw_circle_outline(vec2(32, 32), 10, RED);
+        let cells = drawn_cells(&world);
+        for (x, y) in [(42, 32), (22, 32), (32, 42), (32, 22)] {
+            assert!(cells.contains(&(y * 64 + x)), "({x}, {y}) not drawn");
+        }
+    }
+
+    #[test]
+    fn quarter_arc_only_draws_its_quadrant() {
+        let mut world = World::new(vec2(64, 64));
+        world.draw_arc(vec2(32, 32), 10, 0.0, FRAC_PI_2, RED);
+        let cells = drawn_cells(&world);
+        assert!(cells.contains(&(32 * 64 + 42)));
+        assert!(cells.contains(&(42 * 64 + 32)));
+        assert!(!cells.contains(&(32 * 64 + 22)));
+        assert!(!cells.contains(&(22 * 64 + 32)));
+    }
+
+    #[test]
+    fn clear_fills_every_pixel() {
+        // 7x3 isn't a multiple of the simd path's 16 bytes, so the remainder is covered too.
+        let mut world = World::new(vec2(7, 3));
+        world.clear(WHITE);
+        assert_eq!(world.viewport_texture[..4], [255, 255, 255, 255]);
+        assert!(world.viewport_texture.iter().all(|&b| b == 255));
+
+        world.clear(LIGHT_GRAY);
+        assert!(world
+            .viewport_texture
+            .chunks_exact(4)
+            .all(|pixel| pixel == [65, 65, 65, 255]));
+    }
+
+    // Should stay under 0.5ms, `cargo bench` in release.
+    #[bench]
+    fn clear_800x600(b: &mut test::Bencher) {
+        let mut world = World::new(vec2(800, 600));
+        b.iter(|| {
+            world.clear(LIGHT_GRAY);
+            test::black_box(&world.viewport_texture);
+        });
+    }
+
+    #[test]
+    fn collinear_bezier_is_a_straight_line() {
+        let (p0, p3) = (vec2(0.0, 0.0), vec2(30.0, 15.0));
+        let direction = p3 - p0;
+        let mut segments = vec![];
+        subdivide_bezier::<WorldSpace>(
+            [p0, vec2(-10.0, -5.0), vec2(50.0, 25.0), p3],
+            0,
+            &mut |start, end| segments.push((start, end)),
+        );
+
+        assert!(!segments.is_empty());
+        for (start, end) in segments {
+            for point in [start, end] {
+                assert!(
+                    direction.cross_product(point - p0).abs() < 1e-3,
+                    "{point:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ring_covers_between_its_radii() {
+        let mut offsets = vec![];
+        Shape::CircleRing { inner_radius: 5 }.draw(8, |x, y| offsets.push((x, y)));
+        assert!(offsets.contains(&(6, 0)));
+        assert!(offsets.contains(&(0, -6)));
+        assert!(!offsets.contains(&(4, 0)));
+        assert!(!offsets.contains(&(9, 0)));
+    }
+
+    #[test]
+    fn fast_circle_fill_matches_per_cell() {
+        let mut slow = World::new(vec2(100, 100));
+        slow.draw_circle_fill(vec2(50, 50), 7, RED);
+        let mut fast = World::new(vec2(100, 100));
+        fast.draw_circle_fill_fast(vec2(50, 50), 7, RED);
+
+        let cells = drawn_cells(&slow);
+        assert!(!cells.is_empty());
+        assert_eq!(drawn_cells(&fast), cells);
+    }
+
+    #[test]
+    fn polygon_edges_take_their_own_colours() {
+        let mut world = World::new(vec2(40, 40));
+        let square = [
+            vec2(10.0, 10.0),
+            vec2(30.0, 10.0),
+            vec2(30.0, 30.0),
+            vec2(10.0, 30.0),
+        ];
+        world.draw_polygon_coloured(&square, &[RED, GREEN, BLUE, WHITE]);
+
+        let colour_at = |x: usize, y: usize| {
+            let index = 4 * (y * 40 + x);
+            world.viewport_texture[index..index + 4].to_vec()
+        };
+        // the first edge runs along the top, the third back along the bottom.
+        assert_eq!(colour_at(20, 10), [RED.r, RED.g, RED.b, RED.a]);
+        assert_eq!(colour_at(20, 30), [BLUE.r, BLUE.g, BLUE.b, BLUE.a]);
+    }
+
+    #[test]
+    fn triangle_and_ring_cover_their_area() {
+        // cells on the edges count whole, so allow up to a cell per unit of perimeter.
+        let cover = |shape: Shape, size: i32, area: f32, perimeter: f32| {
+            let mut offsets = vec![];
+            shape.draw(size, |x, y| offsets.push((x, y)));
+            let count = offsets.len();
+            offsets.sort_unstable();
+            offsets.dedup();
+            assert_eq!(offsets.len(), count, "{shape:?} drew a cell twice");
+            assert!(
+                (count as f32 - area).abs() < perimeter,
+                "{shape:?}: {count} cells for an area of {area}"
+            );
+        };
+
+        // half the base is 20, so the sides are 40.
+        let root_3 = 3.0_f32.sqrt();
+        cover(Shape::Triangle, 20, 400.0 * root_3, 120.0);
+        cover(
+            Shape::CircleRing { inner_radius: 10 },
+            20,
+            PI * (400.0 - 100.0),
+            TAU * 30.0,
+        );
+    }
+
+    #[cfg(feature = "antialiased")]
+    #[test]
+    fn diagonal_aa_line_splits_each_column_between_two_cells() {
+        let mut world = World::new(vec2(16, 16));
+        // a quarter cell below the row, so the cells either side get 3/4 & 1/4 coverage.
+        world.draw_line_aa(vec2(2.0, 2.25), vec2(12.0, 12.25), RED);
+
+        let alpha_at = |x: usize, y: usize| world.viewport_texture[4 * (y * 16 + x) + 3];
+        for x in 2..=12 {
+            let (upper, lower) = (alpha_at(x, x), alpha_at(x, x + 1));
+            assert!(upper > 0 && lower > 0, "column {x}: {upper} & {lower}");
+            assert_eq!(u16::from(upper) + u16::from(lower), 255, "column {x}");
+        }
+        assert_eq!(drawn_cells(&world).len(), 2 * 11);
+    }
+
+    #[test]
+    fn filled_square_covers_every_inner_cell() {
+        let mut world = World::new(vec2(16, 16));
+        let square = [
+            vec2(4.0, 4.0),
+            vec2(12.0, 4.0),
+            vec2(12.0, 12.0),
+            vec2(4.0, 12.0),
+        ];
+        world.draw_filled_polygon(&square, RED);
+
+        let cells = drawn_cells(&world);
+        for y in 4..12 {
+            for x in 4..12 {
+                assert!(cells.contains(&(y * 16 + x)), "({x}, {y}) not filled");
+            }
+        }
+        // cells are filled by their centres, so nothing spills past the edges.
+        assert_eq!(cells.len(), 8 * 8);
+    }
+}